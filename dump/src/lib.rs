@@ -114,6 +114,7 @@ pub enum KindDump {
         is_deletion: bool,
         allow_index_creation: bool,
     },
+    EmbeddingCacheClear,
     IndexDeletion,
     IndexCreation {
         primary_key: Option<String>,
@@ -187,6 +188,7 @@ impl From<KindWithContent> for KindDump {
                 allow_index_creation,
                 ..
             } => KindDump::Settings { settings: new_settings, is_deletion, allow_index_creation },
+            KindWithContent::EmbeddingCacheClear { .. } => KindDump::EmbeddingCacheClear,
             KindWithContent::IndexDeletion { .. } => KindDump::IndexDeletion,
             KindWithContent::IndexCreation { primary_key, .. } => {
                 KindDump::IndexCreation { primary_key }
@@ -286,6 +288,8 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            rules: Setting::NotSet,
+            rerankers: Setting::NotSet,
             _kind: std::marker::PhantomData,
         };
         settings.check()
@@ -308,6 +312,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        embedding_failures: Vec::new(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -331,6 +336,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        embedding_failures: Vec::new(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
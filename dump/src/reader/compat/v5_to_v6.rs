@@ -132,11 +132,13 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                embedding_failures: Vec::new(),
                             }
                         }
-                        v5::Details::Settings { settings } => {
-                            v6::Details::SettingsUpdate { settings: Box::new(settings.into()) }
-                        }
+                        v5::Details::Settings { settings } => v6::Details::SettingsUpdate {
+                            settings: Box::new(settings.into()),
+                            embedding_failures: Vec::new(),
+                        },
                         v5::Details::IndexInfo { primary_key } => {
                             v6::Details::IndexInfo { primary_key }
                         }
@@ -380,6 +382,8 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             },
             embedders: v6::Setting::NotSet,
             search_cutoff_ms: v6::Setting::NotSet,
+            rules: v6::Setting::NotSet,
+            rerankers: v6::Setting::NotSet,
             _kind: std::marker::PhantomData,
         }
     }
@@ -31,6 +31,7 @@ enum AutobatchKind {
     Settings {
         allow_index_creation: bool,
     },
+    EmbeddingCacheClear,
     IndexCreation,
     IndexDeletion,
     IndexUpdate,
@@ -75,6 +76,7 @@ impl From<KindWithContent> for AutobatchKind {
                     allow_index_creation: allow_index_creation && !is_deletion,
                 }
             }
+            KindWithContent::EmbeddingCacheClear { .. } => AutobatchKind::EmbeddingCacheClear,
             KindWithContent::IndexDeletion { .. } => AutobatchKind::IndexDeletion,
             KindWithContent::IndexCreation { .. } => AutobatchKind::IndexCreation,
             KindWithContent::IndexUpdate { .. } => AutobatchKind::IndexUpdate,
@@ -125,6 +127,9 @@ pub enum BatchKind {
         allow_index_creation: bool,
         settings_ids: Vec<TaskId>,
     },
+    EmbeddingCacheClear {
+        id: TaskId,
+    },
     IndexDeletion {
         ids: Vec<TaskId>,
     },
@@ -215,6 +220,9 @@ impl BatchKind {
                 Continue(BatchKind::Settings { allow_index_creation, settings_ids: vec![task_id] }),
                 allow_index_creation,
             ),
+            K::EmbeddingCacheClear => {
+                (Break(BatchKind::EmbeddingCacheClear { id: task_id }), false)
+            }
         }
     }
 
@@ -228,7 +236,7 @@ impl BatchKind {
 
         match (self, kind) {
             // We don't batch any of these operations
-            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::DocumentEdition | K::DocumentDeletionByFilter) => Break(this),
+            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::DocumentEdition | K::DocumentDeletionByFilter | K::EmbeddingCacheClear) => Break(this),
             // We must not batch tasks that don't have the same index creation rights if the index doesn't already exists.
             (this, kind) if !index_already_exists && this.allow_index_creation() == Some(false) && kind.allow_index_creation() == Some(true) => {
                 Break(this)
@@ -526,7 +534,8 @@ impl BatchKind {
                 | BatchKind::IndexUpdate { .. }
                 | BatchKind::IndexSwap { .. }
                 | BatchKind::DocumentEdition { .. }
-                | BatchKind::DocumentDeletionByFilter { .. },
+                | BatchKind::DocumentDeletionByFilter { .. }
+                | BatchKind::EmbeddingCacheClear { .. },
                 _,
             ) => {
                 unreachable!()
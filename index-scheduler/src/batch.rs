@@ -26,11 +26,12 @@ use std::io::BufWriter;
 use dump::IndexMetadata;
 use meilisearch_types::error::Code;
 use meilisearch_types::heed::{RoTxn, RwTxn};
-use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader};
+use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader, PrimaryKey};
 use meilisearch_types::milli::heed::CompactionOption;
 use meilisearch_types::milli::update::{
     IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, Settings as MilliSettings,
 };
+use meilisearch_types::milli::vector::error::EmbeddingFailure;
 use meilisearch_types::milli::vector::parsed_vectors::{
     ExplicitVectors, VectorOrArrayOfVectors, RESERVED_VECTORS_FIELD_NAME,
 };
@@ -124,6 +125,10 @@ pub(crate) enum IndexOperation {
         settings: Vec<(bool, Settings<Unchecked>)>,
         tasks: Vec<Task>,
     },
+    EmbeddingCacheClear {
+        index_uid: String,
+        task: Task,
+    },
     DocumentClearAndSetting {
         index_uid: String,
         cleared_tasks: Vec<Task>,
@@ -169,7 +174,8 @@ impl Batch {
                     RoaringBitmap::from_iter(tasks.iter().map(|task| task.uid))
                 }
                 IndexOperation::DocumentEdition { task, .. }
-                | IndexOperation::IndexDocumentDeletionByFilter { task, .. } => {
+                | IndexOperation::IndexDocumentDeletionByFilter { task, .. }
+                | IndexOperation::EmbeddingCacheClear { task, .. } => {
                     RoaringBitmap::from_sorted_iter(std::iter::once(task.uid)).unwrap()
                 }
                 IndexOperation::SettingsAndDocumentOperation {
@@ -237,6 +243,7 @@ impl IndexOperation {
             | IndexOperation::IndexDocumentDeletionByFilter { index_uid, .. }
             | IndexOperation::DocumentClear { index_uid, .. }
             | IndexOperation::Settings { index_uid, .. }
+            | IndexOperation::EmbeddingCacheClear { index_uid, .. }
             | IndexOperation::DocumentClearAndSetting { index_uid, .. }
             | IndexOperation::SettingsAndDocumentOperation { index_uid, .. } => index_uid,
         }
@@ -257,6 +264,9 @@ impl fmt::Display for IndexOperation {
             }
             IndexOperation::DocumentClear { .. } => f.write_str("IndexOperation::DocumentClear"),
             IndexOperation::Settings { .. } => f.write_str("IndexOperation::Settings"),
+            IndexOperation::EmbeddingCacheClear { .. } => {
+                f.write_str("IndexOperation::EmbeddingCacheClear")
+            }
             IndexOperation::DocumentClearAndSetting { .. } => {
                 f.write_str("IndexOperation::DocumentClearAndSetting")
             }
@@ -319,6 +329,13 @@ impl IndexScheduler {
                     _ => unreachable!(),
                 }
             }
+            BatchKind::EmbeddingCacheClear { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                Ok(Some(Batch::IndexOperation {
+                    op: IndexOperation::EmbeddingCacheClear { index_uid, task },
+                    must_create_index: false,
+                }))
+            }
             BatchKind::DocumentOperation { method, operation_ids, .. } => {
                 let tasks = self.get_existing_tasks(rtxn, operation_ids)?;
                 let primary_key = tasks
@@ -1328,7 +1345,11 @@ impl IndexScheduler {
                     || must_stop_processing.get(),
                 )?;
 
-                for (operation, task) in operations.into_iter().zip(tasks.iter_mut()) {
+                // the succeeded additions, to report the embedding failures on the tasks that sent the documents
+                let mut additions = Vec::new();
+                for (task_index, (operation, task)) in
+                    operations.into_iter().zip(tasks.iter_mut()).enumerate()
+                {
                     match operation {
                         DocumentOperation::Add(content_uuid) => {
                             let content_file = self.file_store.get_update(content_uuid)?;
@@ -1357,13 +1378,16 @@ impl IndexScheduler {
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(count),
-                                    })
+                                        embedding_failures: Vec::new(),
+                                    });
+                                    additions.push((task_index, content_uuid));
                                 }
                                 Err(e) => {
                                     task.status = Status::Failed;
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(0),
+                                        embedding_failures: Vec::new(),
                                     });
                                     task.error = Some(milli::Error::from(e).into());
                                 }
@@ -1395,8 +1419,19 @@ impl IndexScheduler {
                 }
 
                 if !tasks.iter().all(|res| res.error.is_some()) {
-                    let addition = builder.execute()?;
+                    let mut addition = builder.execute()?;
                     tracing::info!(indexing_result = ?addition, processed_in = ?started_processing_at.elapsed(), "document indexing done");
+
+                    let embedding_failures = std::mem::take(&mut addition.embedding_failures);
+                    if !embedding_failures.is_empty() {
+                        self.report_embedding_failures(
+                            index_wtxn,
+                            index,
+                            &additions,
+                            &mut tasks,
+                            embedding_failures,
+                        )?;
+                    }
                 } else if primary_key_has_been_set {
                     // Everything failed but we've set a primary key.
                     // We need to remove it.
@@ -1411,6 +1446,12 @@ impl IndexScheduler {
 
                 Ok(tasks)
             }
+            IndexOperation::EmbeddingCacheClear { mut task, .. } => {
+                index.clear_embedding_cache(index_wtxn)?;
+                task.status = Status::Succeeded;
+
+                Ok(vec![task])
+            }
             IndexOperation::DocumentEdition { mut task, .. } => {
                 let (filter, context, function) =
                     if let KindWithContent::DocumentEdition {
@@ -1522,7 +1563,10 @@ impl IndexScheduler {
 
                 for (task, (_, settings)) in tasks.iter_mut().zip(settings) {
                     let checked_settings = settings.clone().check();
-                    task.details = Some(Details::SettingsUpdate { settings: Box::new(settings) });
+                    task.details = Some(Details::SettingsUpdate {
+                        settings: Box::new(settings),
+                        embedding_failures: Vec::new(),
+                    });
                     apply_settings_to_builder(&checked_settings, &mut builder);
 
                     // We can apply the status right now and if an update fail later
@@ -1531,11 +1575,19 @@ impl IndexScheduler {
                 }
 
                 let must_stop_processing = self.must_stop_processing.clone();
-                builder.execute(
+                let embedding_failures = builder.execute_with_embedding_failures(
                     |indexing_step| tracing::debug!(update = ?indexing_step),
                     || must_stop_processing.get(),
                 )?;
 
+                // The settings of the batch are applied at once, so the documents are reindexed
+                // with the settings of the last task: it reports the embedding failures.
+                if let Some(Details::SettingsUpdate { embedding_failures: failures, .. }) =
+                    tasks.last_mut().and_then(|task| task.details.as_mut())
+                {
+                    *failures = embedding_failures;
+                }
+
                 Ok(tasks)
             }
             IndexOperation::SettingsAndDocumentOperation {
@@ -1603,6 +1655,65 @@ impl IndexScheduler {
         }
     }
 
+    /// Reports each embedding failure of a document addition batch on the last succeeded task
+    /// that sent the failed document.
+    ///
+    /// The documents are read again from the update files of the tasks, so this must be called
+    /// before they are deleted.
+    fn report_embedding_failures(
+        &self,
+        rtxn: &RoTxn,
+        index: &Index,
+        additions: &[(usize, Uuid)],
+        tasks: &mut [Task],
+        mut failures: Vec<EmbeddingFailure>,
+    ) -> Result<()> {
+        let primary_key = index.primary_key(rtxn)?.map(String::from);
+        for &(task_index, content_uuid) in additions.iter().rev() {
+            if failures.is_empty() {
+                break;
+            }
+
+            let content_file = self.file_store.get_update(content_uuid)?;
+            let reader =
+                DocumentsBatchReader::from_reader(content_file).map_err(milli::Error::from)?;
+            let (mut cursor, fields_index) = reader.into_cursor_and_fields_index();
+            let Some(primary_key) =
+                primary_key.as_deref().and_then(|name| PrimaryKey::new(name, &fields_index))
+            else {
+                continue;
+            };
+
+            let mut external_ids = HashSet::new();
+            while let Some(document) = cursor.next_document().map_err(milli::Error::from)? {
+                if let Ok(external_id) = primary_key.document_id(&document, &fields_index)? {
+                    external_ids.insert(external_id);
+                }
+            }
+
+            let (sent, others): (Vec<_>, Vec<_>) = failures
+                .into_iter()
+                .partition(|failure| external_ids.contains(&failure.document_id));
+            failures = others;
+            if let Some(Details::DocumentAdditionOrUpdate { embedding_failures, .. }) =
+                &mut tasks[task_index].details
+            {
+                *embedding_failures = sent;
+            }
+        }
+
+        // the failures that could not be traced back to a task are reported on the last addition
+        if let Some(&(task_index, _)) = additions.last() {
+            if let Some(Details::DocumentAdditionOrUpdate { embedding_failures, .. }) =
+                &mut tasks[task_index].details
+            {
+                embedding_failures.extend(failures);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Delete each given task from all the databases (if it is deleteable).
     ///
     /// Return the number of tasks that were actually deleted.
@@ -6,6 +6,7 @@ use std::{fs, thread};
 use meilisearch_types::heed::types::{SerdeJson, Str};
 use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::vector::cache::EmbeddingCacheStats;
 use meilisearch_types::milli::{FieldDistribution, Index};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
@@ -111,6 +112,9 @@ pub struct IndexStats {
     pub created_at: OffsetDateTime,
     /// Date of the last update of the index.
     pub updated_at: OffsetDateTime,
+    /// Statistics of the cache of the embeddings generated while indexing.
+    #[serde(default)]
+    pub embedding_cache: EmbeddingCacheStats,
 }
 
 impl IndexStats {
@@ -127,6 +131,7 @@ impl IndexStats {
             field_distribution: index.field_distribution(rtxn)?,
             created_at: index.created_at(rtxn)?,
             updated_at: index.updated_at(rtxn)?,
+            embedding_cache: index.embedding_cache_stats(rtxn)?,
         })
     }
 }
@@ -174,6 +174,7 @@ fn snapshot_details(d: &Details) -> String {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
+            embedding_failures: _,
         } => {
             format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
         }
@@ -188,7 +189,7 @@ fn snapshot_details(d: &Details) -> String {
                 "{{ deleted_documents: {deleted_documents:?}, edited_documents: {edited_documents:?}, context: {context:?}, function: {function:?}, original_filter: {original_filter:?} }}"
             )
         }
-        Details::SettingsUpdate { settings } => {
+        Details::SettingsUpdate { settings, embedding_failures: _ } => {
             format!("{{ settings: {settings:?} }}")
         }
         Details::IndexInfo { primary_key } => {
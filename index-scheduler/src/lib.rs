@@ -68,6 +68,7 @@ use time::OffsetDateTime;
 use utils::{filter_out_references_to_newer_tasks, keep_tasks_within_datetimes, map_bound};
 use uuid::Uuid;
 
+use crate::batch::Batch;
 use crate::index_mapper::IndexMapper;
 use crate::utils::{check_index_swap_validity, clamp_to_page_size};
 
@@ -278,6 +279,14 @@ pub struct IndexSchedulerOptions {
     pub instance_features: InstanceTogglableFeatures,
 }
 
+/// The number of batches processed on each index.
+#[derive(Debug, Default)]
+struct IndexGenerations {
+    /// Incremented by the batches writing to several indexes at once, like the index swaps.
+    global: u64,
+    per_index: HashMap<String, u64>,
+}
+
 /// Structure which holds meilisearch's indexes and schedules the tasks
 /// to be performed on them.
 pub struct IndexScheduler {
@@ -357,6 +366,9 @@ pub struct IndexScheduler {
 
     embedders: Arc<RwLock<HashMap<EmbedderOptions, Arc<Embedder>>>>,
 
+    /// Counts the batches that wrote to each index, see [`IndexScheduler::index_generation`].
+    index_generations: Arc<RwLock<IndexGenerations>>,
+
     // ================= test
     // The next entry is dedicated to the tests.
     /// Provide a way to set a breakpoint in multiple part of the scheduler.
@@ -404,6 +416,7 @@ impl IndexScheduler {
             webhook_url: self.webhook_url.clone(),
             webhook_authorization_header: self.webhook_authorization_header.clone(),
             embedders: self.embedders.clone(),
+            index_generations: self.index_generations.clone(),
             #[cfg(test)]
             test_breakpoint_sdr: self.test_breakpoint_sdr.clone(),
             #[cfg(test)]
@@ -506,6 +519,7 @@ impl IndexScheduler {
             webhook_url: options.webhook_url,
             webhook_authorization_header: options.webhook_authorization_header,
             embedders: Default::default(),
+            index_generations: Default::default(),
 
             #[cfg(test)]
             test_breakpoint_sdr,
@@ -1140,6 +1154,7 @@ impl IndexScheduler {
                 None => return Ok(TickOutcome::WaitForSignal),
             };
         let index_uid = batch.index_uid().map(ToOwned::to_owned);
+        let is_index_swap = matches!(batch, Batch::IndexSwap { .. });
         drop(rtxn);
 
         // 1. store the starting date with the bitmap of processing tasks.
@@ -1167,6 +1182,18 @@ impl IndexScheduler {
         // Reset the currently updating index to relinquish the index handle
         self.index_mapper.set_currently_updating_index(None);
 
+        // The batch may have written to the index, whether it succeeded or not
+        {
+            let mut generations = self.index_generations.write().unwrap();
+            match &index_uid {
+                Some(index_uid) => {
+                    *generations.per_index.entry(index_uid.clone()).or_default() += 1
+                }
+                None if is_index_swap => generations.global += 1,
+                None => (),
+            }
+        }
+
         #[cfg(test)]
         self.maybe_fail(tests::FailureLocation::AcquiringWtxn)?;
 
@@ -1448,6 +1475,15 @@ impl IndexScheduler {
         self.features.features()
     }
 
+    /// Returns a number that changes each time a batch is processed on the given index.
+    ///
+    /// It lets the caller know whether something computed from the index may be outdated.
+    /// The generations are only kept in memory and start over when the scheduler is restarted.
+    pub fn index_generation(&self, index_uid: &str) -> u64 {
+        let generations = self.index_generations.read().unwrap();
+        generations.global + generations.per_index.get(index_uid).copied().unwrap_or_default()
+    }
+
     pub fn put_runtime_features(&self, features: RuntimeTogglableFeatures) -> Result<()> {
         let wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
         self.features.put_runtime_features(wtxn, features)?;
@@ -1461,6 +1497,29 @@ impl IndexScheduler {
         }
     }
 
+    /// Returns the embedder spawned from these options, spawning it if it doesn't exist yet.
+    fn embedder(&self, embedder_options: EmbedderOptions) -> Result<Arc<Embedder>> {
+        // optimistically return existing embedder
+        {
+            let embedders = self.embedders.read().unwrap();
+            if let Some(embedder) = embedders.get(&embedder_options) {
+                return Ok(embedder.clone());
+            }
+        }
+
+        // add missing embedder
+        let embedder = Arc::new(
+            Embedder::new(embedder_options.clone())
+                .map_err(meilisearch_types::milli::vector::Error::from)
+                .map_err(meilisearch_types::milli::Error::from)?,
+        );
+        {
+            let mut embedders = self.embedders.write().unwrap();
+            embedders.insert(embedder_options, embedder.clone());
+        }
+        Ok(embedder)
+    }
+
     // TODO: consider using a type alias or a struct embedder/template
     pub fn embedders(
         &self,
@@ -1468,35 +1527,36 @@ impl IndexScheduler {
     ) -> Result<EmbeddingConfigs> {
         let res: Result<_> = embedding_configs
             .into_iter()
-            .map(
-                |IndexEmbeddingConfig {
-                     name,
-                     config: milli::vector::EmbeddingConfig { embedder_options, prompt },
-                     ..
-                 }| {
-                    let prompt =
-                        Arc::new(prompt.try_into().map_err(meilisearch_types::milli::Error::from)?);
-                    // optimistically return existing embedder
-                    {
-                        let embedders = self.embedders.read().unwrap();
-                        if let Some(embedder) = embedders.get(&embedder_options) {
-                            return Ok((name, (embedder.clone(), prompt)));
-                        }
-                    }
-
-                    // add missing embedder
-                    let embedder = Arc::new(
-                        Embedder::new(embedder_options.clone())
-                            .map_err(meilisearch_types::milli::vector::Error::from)
-                            .map_err(meilisearch_types::milli::Error::from)?,
-                    );
-                    {
-                        let mut embedders = self.embedders.write().unwrap();
-                        embedders.insert(embedder_options, embedder.clone());
-                    }
-                    Ok((name, (embedder, prompt)))
-                },
-            )
+            .map(|IndexEmbeddingConfig { name, config, .. }| {
+                let options = config.arroy_options();
+                let milli::vector::EmbeddingConfig {
+                    embedder_options,
+                    prompt,
+                    search_embedder,
+                    ..
+                } = config;
+                let prompt =
+                    Arc::new(prompt.try_into().map_err(meilisearch_types::milli::Error::from)?);
+
+                let mut embedder = self.embedder(embedder_options.clone())?;
+                if let Some(search_options) = search_embedder {
+                    let search_embedder = if search_options.has_distinct_embedder() {
+                        self.embedder(embedder_options.with_search_options(&search_options))?
+                    } else {
+                        embedder.clone()
+                    };
+                    embedder = Arc::new(Embedder::Asymmetric(
+                        milli::vector::asymmetric::Embedder::new(
+                            embedder,
+                            search_embedder,
+                            search_options.prefix,
+                        )
+                        .map_err(meilisearch_types::milli::vector::Error::from)
+                        .map_err(meilisearch_types::milli::Error::from)?,
+                    ));
+                }
+                Ok((name, (embedder, prompt, options)))
+            })
             .collect();
         res.map(EmbeddingConfigs::new)
     }
@@ -1626,6 +1686,9 @@ impl<'a> Dump<'a> {
                         allow_index_creation,
                     }
                 }
+                KindDump::EmbeddingCacheClear => KindWithContent::EmbeddingCacheClear {
+                    index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                },
                 KindDump::IndexDeletion => KindWithContent::IndexDeletion {
                     index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
                 },
@@ -5388,12 +5451,18 @@ mod tests {
                             revision: Some(
                                 "e4ce9877abf3edfe10b0d82785e83bdcb973e22e",
                             ),
+                            model_path: None,
                             distribution: None,
                         },
                     ),
                     prompt: PromptData {
                         template: "{{doc.doggo}}",
                     },
+                    quantized: None,
+                    distance: None,
+                    chunking: None,
+                    search_embedder: None,
+                    on_error: None,
                 },
                 user_provided: RoaringBitmap<[1, 2]>,
             },
@@ -5406,28 +5475,13 @@ mod tests {
 
         // the document with the id 3 should keep its original embedding
         let docid = index.external_documents_ids.get(&rtxn, "3").unwrap().unwrap();
-        let mut embeddings = Vec::new();
-
-        'vectors: for i in 0..=u8::MAX {
-            let reader = arroy::Reader::open(&rtxn, i as u16, index.vector_arroy)
-                .map(Some)
-                .or_else(|e| match e {
-                    arroy::Error::MissingMetadata(_) => Ok(None),
-                    e => Err(e),
-                })
-                .transpose();
-
-            let Some(reader) = reader else {
-                break 'vectors;
-            };
-
-            let embedding = reader.unwrap().item_vector(&rtxn, docid).unwrap();
-            if let Some(embedding) = embedding {
-                embeddings.push(embedding)
-            } else {
-                break 'vectors;
-            }
-        }
+        let embeddings = meilisearch_types::milli::vector::ArroyWrapper::new(
+            index.vector_arroy,
+            0,
+            Default::default(),
+        )
+        .item_vectors(&rtxn, docid)
+        .unwrap();
 
         snapshot!(embeddings.len(), @"1");
         assert!(embeddings[0].iter().all(|i| *i == 3.0), "{:?}", embeddings[0]);
@@ -5604,11 +5658,17 @@ mod tests {
                         EmbedderOptions {
                             dimensions: 3,
                             distribution: None,
+                            sparse: false,
                         },
                     ),
                     prompt: PromptData {
                         template: "{% for field in fields %} {{ field.name }}: {{ field.value }}\n{% endfor %}",
                     },
+                    quantized: None,
+                    distance: None,
+                    chunking: None,
+                    search_embedder: None,
+                    on_error: None,
                 },
                 user_provided: RoaringBitmap<[0]>,
             },
@@ -5644,11 +5704,17 @@ mod tests {
                         EmbedderOptions {
                             dimensions: 3,
                             distribution: None,
+                            sparse: false,
                         },
                     ),
                     prompt: PromptData {
                         template: "{% for field in fields %} {{ field.name }}: {{ field.value }}\n{% endfor %}",
                     },
+                    quantized: None,
+                    distance: None,
+                    chunking: None,
+                    search_embedder: None,
+                    on_error: None,
                 },
                 user_provided: RoaringBitmap<[]>,
             },
@@ -11,7 +11,9 @@ use fst::IntoStreamer;
 use milli::index::IndexEmbeddingConfig;
 use milli::proximity::ProximityPrecision;
 use milli::update::Setting;
-use milli::{Criterion, CriterionError, Index, DEFAULT_VALUES_PER_FACET};
+use milli::{
+    Criterion, CriterionError, Index, QueryRule, RerankerSettings, DEFAULT_VALUES_PER_FACET,
+};
 use serde::{Deserialize, Serialize, Serializer};
 
 use crate::deserr::DeserrJsonError;
@@ -198,6 +200,12 @@ pub struct Settings<T> {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSearchCutoffMs>)]
     pub search_cutoff_ms: Setting<u64>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsRules>)]
+    pub rules: Setting<Vec<QueryRule>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsRerankers>)]
+    pub rerankers: Setting<BTreeMap<String, RerankerSettings>>,
 
     #[serde(skip)]
     #[deserr(skip)]
@@ -206,6 +214,17 @@ pub struct Settings<T> {
 
 impl<T> Settings<T> {
     pub fn hide_secrets(&mut self) {
+        if let Setting::Set(rerankers) = &mut self.rerankers {
+            for reranker in rerankers.values_mut() {
+                for value in reranker.headers.values_mut() {
+                    Self::hide_secret(value);
+                }
+                if let Some(api_key) = &mut reranker.api_key {
+                    Self::hide_secret(api_key);
+                }
+            }
+        }
+
         let Setting::Set(embedders) = &mut self.embedders else {
             return;
         };
@@ -215,6 +234,13 @@ impl<T> Settings<T> {
                 continue;
             };
 
+            // custom headers frequently carry credentials
+            if let Setting::Set(headers) = &mut embedder.headers {
+                for value in headers.values_mut() {
+                    Self::hide_secret(value);
+                }
+            }
+
             let Setting::Set(api_key) = &mut embedder.api_key else {
                 continue;
             };
@@ -261,6 +287,8 @@ impl Settings<Checked> {
             pagination: Setting::Reset,
             embedders: Setting::Reset,
             search_cutoff_ms: Setting::Reset,
+            rules: Setting::Reset,
+            rerankers: Setting::Reset,
             _kind: PhantomData,
         }
     }
@@ -284,6 +312,8 @@ impl Settings<Checked> {
             pagination,
             embedders,
             search_cutoff_ms,
+            rules,
+            rerankers,
             ..
         } = self;
 
@@ -305,6 +335,8 @@ impl Settings<Checked> {
             pagination,
             embedders,
             search_cutoff_ms,
+            rules,
+            rerankers,
             _kind: PhantomData,
         }
     }
@@ -352,12 +384,28 @@ impl Settings<Unchecked> {
             pagination: self.pagination,
             embedders: self.embedders,
             search_cutoff_ms: self.search_cutoff_ms,
+            rules: self.rules,
+            rerankers: self.rerankers,
             _kind: PhantomData,
         }
     }
 
     pub fn validate(self) -> Result<Self, milli::Error> {
-        self.validate_embedding_settings()
+        self.validate_embedding_settings()?.validate_rules()?.validate_rerankers()
+    }
+
+    fn validate_rerankers(self) -> Result<Self, milli::Error> {
+        if let Setting::Set(rerankers) = &self.rerankers {
+            milli::rerank::validate_rerankers(rerankers)?;
+        }
+        Ok(self)
+    }
+
+    fn validate_rules(self) -> Result<Self, milli::Error> {
+        if let Setting::Set(rules) = &self.rules {
+            milli::query_rules::validate_query_rules(rules)?;
+        }
+        Ok(self)
     }
 
     fn validate_embedding_settings(mut self) -> Result<Self, milli::Error> {
@@ -402,6 +450,8 @@ pub fn apply_settings_to_builder(
         pagination,
         embedders,
         search_cutoff_ms,
+        rules,
+        rerankers,
         _kind,
     } = settings;
 
@@ -583,6 +633,18 @@ pub fn apply_settings_to_builder(
         Setting::Reset => builder.reset_search_cutoff(),
         Setting::NotSet => (),
     }
+
+    match rules {
+        Setting::Set(rules) => builder.set_query_rules(rules.clone()),
+        Setting::Reset => builder.reset_query_rules(),
+        Setting::NotSet => (),
+    }
+
+    match rerankers {
+        Setting::Set(rerankers) => builder.set_rerankers(rerankers.clone()),
+        Setting::Reset => builder.reset_rerankers(),
+        Setting::NotSet => (),
+    }
 }
 
 pub enum SecretPolicy {
@@ -679,6 +741,10 @@ pub fn settings(
 
     let search_cutoff_ms = index.search_cutoff(rtxn)?;
 
+    let rules = index.query_rules(rtxn)?;
+
+    let rerankers = index.rerankers(rtxn)?;
+
     let mut settings = Settings {
         displayed_attributes: match displayed_attributes {
             Some(attrs) => Setting::Set(attrs),
@@ -711,6 +777,8 @@ pub fn settings(
             Some(cutoff) => Setting::Set(cutoff),
             None => Setting::Reset,
         },
+        rules: Setting::Set(rules),
+        rerankers: Setting::Set(rerankers),
         _kind: PhantomData,
     };
 
@@ -903,6 +971,8 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            rules: Setting::NotSet,
+            rerankers: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -931,6 +1001,8 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            rules: Setting::NotSet,
+            rerankers: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
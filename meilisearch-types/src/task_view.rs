@@ -1,3 +1,4 @@
+use milli::vector::error::EmbeddingFailure;
 use milli::Object;
 use serde::Serialize;
 use time::{Duration, OffsetDateTime};
@@ -55,6 +56,8 @@ pub struct DetailsView {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indexed_documents: Option<Option<u64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_failures: Option<Vec<EmbeddingFailure>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub edited_documents: Option<Option<u64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub primary_key: Option<Option<String>>,
@@ -86,13 +89,16 @@ pub struct DetailsView {
 impl From<Details> for DetailsView {
     fn from(details: Details) -> Self {
         match details {
-            Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
-                DetailsView {
-                    received_documents: Some(received_documents),
-                    indexed_documents: Some(indexed_documents),
-                    ..DetailsView::default()
-                }
-            }
+            Details::DocumentAdditionOrUpdate {
+                received_documents,
+                indexed_documents,
+                embedding_failures,
+            } => DetailsView {
+                received_documents: Some(received_documents),
+                indexed_documents: Some(indexed_documents),
+                embedding_failures: (!embedding_failures.is_empty()).then_some(embedding_failures),
+                ..DetailsView::default()
+            },
             Details::DocumentEdition {
                 deleted_documents,
                 edited_documents,
@@ -107,9 +113,14 @@ impl From<Details> for DetailsView {
                 function: Some(function),
                 ..DetailsView::default()
             },
-            Details::SettingsUpdate { mut settings } => {
+            Details::SettingsUpdate { mut settings, embedding_failures } => {
                 settings.hide_secrets();
-                DetailsView { settings: Some(settings), ..DetailsView::default() }
+                DetailsView {
+                    settings: Some(settings),
+                    embedding_failures: (!embedding_failures.is_empty())
+                        .then_some(embedding_failures),
+                    ..DetailsView::default()
+                }
             }
             Details::IndexInfo { primary_key } => {
                 DetailsView { primary_key: Some(primary_key), ..DetailsView::default() }
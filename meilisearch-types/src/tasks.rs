@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use enum_iterator::Sequence;
 use milli::update::IndexDocumentsMethod;
+use milli::vector::error::EmbeddingFailure;
 use milli::Object;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
@@ -54,6 +55,7 @@ impl Task {
             | DocumentDeletionByFilter { index_uid, .. }
             | DocumentClear { index_uid }
             | SettingsUpdate { index_uid, .. }
+            | EmbeddingCacheClear { index_uid }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
             | IndexDeletion { index_uid } => Some(index_uid),
@@ -74,6 +76,7 @@ impl Task {
             | KindWithContent::DocumentDeletionByFilter { .. }
             | KindWithContent::DocumentClear { .. }
             | KindWithContent::SettingsUpdate { .. }
+            | KindWithContent::EmbeddingCacheClear { .. }
             | KindWithContent::IndexDeletion { .. }
             | KindWithContent::IndexCreation { .. }
             | KindWithContent::IndexUpdate { .. }
@@ -120,6 +123,9 @@ pub enum KindWithContent {
         is_deletion: bool,
         allow_index_creation: bool,
     },
+    EmbeddingCacheClear {
+        index_uid: String,
+    },
     IndexDeletion {
         index_uid: String,
     },
@@ -164,6 +170,7 @@ impl KindWithContent {
             KindWithContent::DocumentDeletionByFilter { .. } => Kind::DocumentDeletion,
             KindWithContent::DocumentClear { .. } => Kind::DocumentDeletion,
             KindWithContent::SettingsUpdate { .. } => Kind::SettingsUpdate,
+            KindWithContent::EmbeddingCacheClear { .. } => Kind::EmbeddingCacheClear,
             KindWithContent::IndexCreation { .. } => Kind::IndexCreation,
             KindWithContent::IndexDeletion { .. } => Kind::IndexDeletion,
             KindWithContent::IndexUpdate { .. } => Kind::IndexUpdate,
@@ -189,6 +196,7 @@ impl KindWithContent {
             | DocumentDeletionByFilter { index_uid, .. }
             | DocumentClear { index_uid }
             | SettingsUpdate { index_uid, .. }
+            | EmbeddingCacheClear { index_uid }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
             | IndexDeletion { index_uid } => vec![index_uid],
@@ -211,6 +219,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_failures: Vec::new(),
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -237,9 +246,11 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } | KindWithContent::IndexDeletion { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_failures: Vec::new(),
+            }),
+            KindWithContent::EmbeddingCacheClear { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -268,6 +279,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    embedding_failures: Vec::new(),
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -294,9 +306,11 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_failures: Vec::new(),
+            }),
+            KindWithContent::EmbeddingCacheClear { .. } => None,
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
@@ -328,15 +342,18 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_failures: Vec::new(),
                 })
             }
             KindWithContent::DocumentEdition { .. } => None,
             KindWithContent::DocumentDeletion { .. } => None,
             KindWithContent::DocumentDeletionByFilter { .. } => None,
             KindWithContent::DocumentClear { .. } => None,
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_failures: Vec::new(),
+            }),
+            KindWithContent::EmbeddingCacheClear { .. } => None,
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -427,6 +444,7 @@ pub enum Kind {
     DocumentEdition,
     DocumentDeletion,
     SettingsUpdate,
+    EmbeddingCacheClear,
     IndexCreation,
     IndexDeletion,
     IndexUpdate,
@@ -444,6 +462,7 @@ impl Kind {
             | Kind::DocumentEdition
             | Kind::DocumentDeletion
             | Kind::SettingsUpdate
+            | Kind::EmbeddingCacheClear
             | Kind::IndexCreation
             | Kind::IndexDeletion
             | Kind::IndexUpdate => true,
@@ -462,6 +481,7 @@ impl Display for Kind {
             Kind::DocumentEdition => write!(f, "documentEdition"),
             Kind::DocumentDeletion => write!(f, "documentDeletion"),
             Kind::SettingsUpdate => write!(f, "settingsUpdate"),
+            Kind::EmbeddingCacheClear => write!(f, "embeddingCacheClear"),
             Kind::IndexCreation => write!(f, "indexCreation"),
             Kind::IndexDeletion => write!(f, "indexDeletion"),
             Kind::IndexUpdate => write!(f, "indexUpdate"),
@@ -493,6 +513,8 @@ impl FromStr for Kind {
             Ok(Kind::DocumentDeletion)
         } else if kind.eq_ignore_ascii_case("settingsUpdate") {
             Ok(Kind::SettingsUpdate)
+        } else if kind.eq_ignore_ascii_case("embeddingCacheClear") {
+            Ok(Kind::EmbeddingCacheClear)
         } else if kind.eq_ignore_ascii_case("taskCancelation") {
             Ok(Kind::TaskCancelation)
         } else if kind.eq_ignore_ascii_case("taskDeletion") {
@@ -533,9 +555,13 @@ pub enum Details {
     DocumentAdditionOrUpdate {
         received_documents: u64,
         indexed_documents: Option<u64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        embedding_failures: Vec<EmbeddingFailure>,
     },
     SettingsUpdate {
         settings: Box<Settings<Unchecked>>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        embedding_failures: Vec<EmbeddingFailure>,
     },
     IndexInfo {
         primary_key: Option<String>,
@@ -34,10 +34,10 @@ use crate::routes::indexes::documents::{DocumentEditionByFunction, UpdateDocumen
 use crate::routes::indexes::facet_search::FacetSearchQuery;
 use crate::routes::{create_all_stats, Stats};
 use crate::search::{
-    FacetSearchResult, FederatedSearch, MatchingStrategy, SearchQuery, SearchQueryWithIndex,
-    SearchResult, SimilarQuery, SimilarResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
-    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
-    DEFAULT_SEMANTIC_RATIO,
+    FacetSearchResult, FederatedSearch, HybridFusion, MatchingStrategy, MergeStrategy, SearchQuery,
+    SearchQueryWithIndex, SearchResult, SimilarQuery, SimilarResult, DEFAULT_CROP_LENGTH,
+    DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG,
+    DEFAULT_SEARCH_LIMIT, DEFAULT_SEMANTIC_RATIO,
 };
 use crate::Opt;
 
@@ -264,11 +264,14 @@ struct Infos {
     experimental_contains_filter: bool,
     experimental_enable_metrics: bool,
     experimental_search_queue_size: usize,
+    experimental_search_cache_size: Byte,
+    experimental_search_cache_ttl: u64,
     experimental_logs_mode: LogMode,
     experimental_replication_parameters: bool,
     experimental_enable_logs_route: bool,
     experimental_reduce_indexing_memory_usage: bool,
     experimental_max_number_of_batched_tasks: usize,
+    experimental_local_models_dir: bool,
     gpu_enabled: bool,
     db_path: bool,
     import_dump: bool,
@@ -307,11 +310,14 @@ impl From<Opt> for Infos {
             experimental_contains_filter,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_search_cache_size,
+            experimental_search_cache_ttl,
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
             experimental_reduce_indexing_memory_usage,
             experimental_max_number_of_batched_tasks,
+            experimental_local_models_dir,
             http_addr,
             master_key: _,
             env,
@@ -358,6 +364,8 @@ impl From<Opt> for Infos {
             experimental_contains_filter,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_search_cache_size,
+            experimental_search_cache_ttl,
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
@@ -376,6 +384,7 @@ impl From<Opt> for Infos {
             http_addr: http_addr != default_http_addr(),
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
+            experimental_local_models_dir: experimental_local_models_dir.is_some(),
             task_queue_webhook: task_webhook_url.is_some(),
             task_webhook_authorization_header: task_webhook_authorization_header.is_some(),
             log_level: log_level.to_string(),
@@ -623,6 +632,20 @@ pub struct SearchAggregator {
     // distinct
     distinct: bool,
 
+    // collapse
+    collapse: bool,
+    // every time a request collapses its results, this field must be incremented by the number of requested inner hits
+    collapse_sum_of_inner_hits: usize,
+
+    // rerank
+    rerank: bool,
+    rerank_max_top_k: usize,
+
+    // boost
+    boost: bool,
+    // every time a request boosts documents, this field must be incremented by the number of boost clauses
+    boost_sum_of_clauses: usize,
+
     // filter
     filter_with_geo_radius: bool,
     filter_with_geo_bounding_box: bool,
@@ -643,11 +666,15 @@ pub struct SearchAggregator {
     // vector
     // The maximum number of floats in a vector request
     max_vector_size: usize,
+    // Whether a sparse vector was passed
+    sparse_vector: bool,
     // Whether the semantic ratio passed to a hybrid search equals the default ratio.
     semantic_ratio: bool,
     // Whether a non-default embedder was specified
     embedder: bool,
     hybrid: bool,
+    // Whether the keyword and semantic results were merged by reciprocal rank fusion
+    hybrid_rrf: bool,
     retrieve_vectors: bool,
 
     // every time a search is done, we increment the counter linked to the used settings
@@ -657,6 +684,8 @@ pub struct SearchAggregator {
     max_limit: usize,
     max_offset: usize,
     finite_pagination: usize,
+    // every time a request is paginated with a cursor, this field must be incremented by one
+    cursor_pagination: usize,
 
     // formatting
     max_attributes_to_retrieve: usize,
@@ -684,6 +713,7 @@ impl SearchAggregator {
         let SearchQuery {
             q,
             vector,
+            sparse_vector,
             offset,
             limit,
             page,
@@ -699,6 +729,11 @@ impl SearchAggregator {
             filter,
             sort,
             distinct,
+            collapse,
+            rerank,
+            boost,
+            boost_position: _,
+            search_after,
             facets: _,
             highlight_pre_tag,
             highlight_post_tag,
@@ -723,6 +758,21 @@ impl SearchAggregator {
 
         ret.distinct = distinct.is_some();
 
+        if let Some(collapse) = collapse {
+            ret.collapse = true;
+            ret.collapse_sum_of_inner_hits = collapse.inner_hits;
+        }
+
+        if let Some(rerank) = rerank {
+            ret.rerank = true;
+            ret.rerank_max_top_k = rerank.top_k;
+        }
+
+        if let Some(boost) = boost {
+            ret.boost = true;
+            ret.boost_sum_of_clauses = boost.len();
+        }
+
         if let Some(ref filter) = filter {
             static RE: Lazy<Regex> = Lazy::new(|| Regex::new("AND | OR").unwrap());
             ret.filter_total_number_of_criteria = 1;
@@ -759,6 +809,7 @@ impl SearchAggregator {
         if let Some(ref vector) = vector {
             ret.max_vector_size = vector.len();
         }
+        ret.sparse_vector = sparse_vector.is_some();
         ret.retrieve_vectors |= retrieve_vectors;
 
         if query.is_finite_pagination() {
@@ -771,6 +822,7 @@ impl SearchAggregator {
             ret.max_offset = *offset;
             ret.finite_pagination = 0;
         }
+        ret.cursor_pagination = search_after.is_some() as usize;
 
         ret.matching_strategy.insert(format!("{:?}", matching_strategy), 1);
 
@@ -788,6 +840,7 @@ impl SearchAggregator {
             ret.semantic_ratio = hybrid.semantic_ratio != DEFAULT_SEMANTIC_RATIO();
             ret.embedder = hybrid.embedder.is_some();
             ret.hybrid = true;
+            ret.hybrid_rrf = hybrid.fusion == HybridFusion::Rrf;
         }
 
         ret
@@ -802,6 +855,8 @@ impl SearchAggregator {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            applied_rules: _,
+            next_cursor: _,
             degraded,
             used_negative_operator,
         } = result;
@@ -828,6 +883,12 @@ impl SearchAggregator {
             sort_sum_of_criteria_terms,
             sort_total_number_of_criteria,
             distinct,
+            collapse,
+            collapse_sum_of_inner_hits,
+            rerank,
+            rerank_max_top_k,
+            boost,
+            boost_sum_of_clauses,
             filter_with_geo_radius,
             filter_with_geo_bounding_box,
             filter_sum_of_criteria_terms,
@@ -836,11 +897,13 @@ impl SearchAggregator {
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            sparse_vector,
             retrieve_vectors,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            cursor_pagination,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
@@ -856,6 +919,7 @@ impl SearchAggregator {
             semantic_ratio,
             embedder,
             hybrid,
+            hybrid_rrf,
             total_degraded,
             total_used_negative_operator,
             ranking_score_threshold,
@@ -888,6 +952,19 @@ impl SearchAggregator {
         // distinct
         self.distinct |= distinct;
 
+        // collapse
+        self.collapse |= collapse;
+        self.collapse_sum_of_inner_hits =
+            self.collapse_sum_of_inner_hits.saturating_add(collapse_sum_of_inner_hits);
+
+        // rerank
+        self.rerank |= rerank;
+        self.rerank_max_top_k = self.rerank_max_top_k.max(rerank_max_top_k);
+
+        // boost
+        self.boost |= boost;
+        self.boost_sum_of_clauses = self.boost_sum_of_clauses.saturating_add(boost_sum_of_clauses);
+
         // filter
         self.filter_with_geo_radius |= filter_with_geo_radius;
         self.filter_with_geo_bounding_box |= filter_with_geo_bounding_box;
@@ -910,15 +987,18 @@ impl SearchAggregator {
 
         // vector
         self.max_vector_size = self.max_vector_size.max(max_vector_size);
+        self.sparse_vector |= sparse_vector;
         self.retrieve_vectors |= retrieve_vectors;
         self.semantic_ratio |= semantic_ratio;
         self.hybrid |= hybrid;
+        self.hybrid_rrf |= hybrid_rrf;
         self.embedder |= embedder;
 
         // pagination
         self.max_limit = self.max_limit.max(max_limit);
         self.max_offset = self.max_offset.max(max_offset);
         self.finite_pagination += finite_pagination;
+        self.cursor_pagination += cursor_pagination;
 
         // formatting
         self.max_attributes_to_retrieve =
@@ -960,6 +1040,12 @@ impl SearchAggregator {
             sort_sum_of_criteria_terms,
             sort_total_number_of_criteria,
             distinct,
+            collapse,
+            collapse_sum_of_inner_hits,
+            rerank,
+            rerank_max_top_k,
+            boost,
+            boost_sum_of_clauses,
             filter_with_geo_radius,
             filter_with_geo_bounding_box,
             filter_sum_of_criteria_terms,
@@ -968,11 +1054,13 @@ impl SearchAggregator {
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            sparse_vector,
             retrieve_vectors,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            cursor_pagination,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
@@ -988,6 +1076,7 @@ impl SearchAggregator {
             semantic_ratio,
             embedder,
             hybrid,
+            hybrid_rrf,
             total_degraded,
             total_used_negative_operator,
             ranking_score_threshold,
@@ -1018,6 +1107,18 @@ impl SearchAggregator {
                     "avg_criteria_number": format!("{:.2}", sort_sum_of_criteria_terms as f64 / sort_total_number_of_criteria as f64),
                 },
                 "distinct": distinct,
+                "collapse": {
+                    "used": collapse,
+                    "total_inner_hits": collapse_sum_of_inner_hits,
+                },
+                "rerank": {
+                    "used": rerank,
+                    "max_top_k": rerank_max_top_k,
+                },
+                "boost": {
+                    "used": boost,
+                    "total_clauses": boost_sum_of_clauses,
+                },
                 "filter": {
                    "with_geoRadius": filter_with_geo_radius,
                    "with_geoBoundingBox": filter_with_geo_bounding_box,
@@ -1032,17 +1133,20 @@ impl SearchAggregator {
                 },
                 "vector": {
                     "max_vector_size": max_vector_size,
+                    "sparse_vector": sparse_vector,
                     "retrieve_vectors": retrieve_vectors,
                 },
                 "hybrid": {
                     "enabled": hybrid,
                     "semantic_ratio": semantic_ratio,
                     "embedder": embedder,
+                    "rrf": hybrid_rrf,
                 },
                 "pagination": {
                    "max_limit": max_limit,
                    "max_offset": max_offset,
                    "most_used_navigation": if finite_pagination > (total_received / 2) { "exhaustive" } else { "estimated" },
+                   "total_cursor_pagination": cursor_pagination,
                 },
                 "formatting": {
                     "max_attributes_to_retrieve": max_attributes_to_retrieve,
@@ -1100,6 +1204,8 @@ pub struct MultiSearchAggregator {
 
     // federation
     use_federation: bool,
+    merge_facets: bool,
+    use_rrf: bool,
 
     // context
     user_agents: HashSet<String>,
@@ -1115,6 +1221,14 @@ impl MultiSearchAggregator {
         let user_agents = extract_user_agents(request).into_iter().collect();
 
         let use_federation = federated_search.federation.is_some();
+        let merge_facets = federated_search
+            .federation
+            .as_ref()
+            .map_or(false, |federation| federation.merge_facets.is_some());
+        let use_rrf = federated_search
+            .federation
+            .as_ref()
+            .map_or(false, |federation| federation.merge_strategy == MergeStrategy::Rrf);
 
         let distinct_indexes: HashSet<_> = federated_search
             .queries
@@ -1127,6 +1241,7 @@ impl MultiSearchAggregator {
                     federation_options: _,
                     q: _,
                     vector: _,
+                    sparse_vector: _,
                     offset: _,
                     limit: _,
                     page: _,
@@ -1142,6 +1257,11 @@ impl MultiSearchAggregator {
                     filter: _,
                     sort: _,
                     distinct: _,
+                    collapse: _,
+                    rerank: _,
+                    boost: _,
+                    boost_position: _,
+                    search_after: _,
                     facets: _,
                     highlight_pre_tag: _,
                     highlight_post_tag: _,
@@ -1172,6 +1292,8 @@ impl MultiSearchAggregator {
             show_ranking_score_details,
             user_agents,
             use_federation,
+            merge_facets,
+            use_rrf,
         }
     }
 
@@ -1198,6 +1320,8 @@ impl MultiSearchAggregator {
             this.show_ranking_score_details || other.show_ranking_score_details;
         let mut user_agents = this.user_agents;
         let use_federation = this.use_federation || other.use_federation;
+        let merge_facets = this.merge_facets || other.merge_facets;
+        let use_rrf = this.use_rrf || other.use_rrf;
 
         for user_agent in other.user_agents.into_iter() {
             user_agents.insert(user_agent);
@@ -1215,6 +1339,8 @@ impl MultiSearchAggregator {
             show_ranking_score,
             show_ranking_score_details,
             use_federation,
+            merge_facets,
+            use_rrf,
             // do not add _ or ..Default::default() here
         };
 
@@ -1234,6 +1360,8 @@ impl MultiSearchAggregator {
             show_ranking_score,
             show_ranking_score_details,
             use_federation,
+            merge_facets,
+            use_rrf,
         } = self;
 
         if total_received == 0 {
@@ -1261,6 +1389,8 @@ impl MultiSearchAggregator {
                 },
                 "federation": {
                     "use_federation": use_federation,
+                    "merge_facets": merge_facets,
+                    "use_rrf": use_rrf,
                 }
             });
 
@@ -1766,6 +1896,9 @@ pub struct SimilarAggregator {
     // Whether a non-default embedder was specified
     embedder: bool,
     retrieve_vectors: bool,
+    // The maximum number of documents the results should be similar or dissimilar to
+    max_positive_ids: usize,
+    max_negative_ids: usize,
 
     // pagination
     max_limit: usize,
@@ -1784,7 +1917,9 @@ impl SimilarAggregator {
     #[allow(clippy::field_reassign_with_default)]
     pub fn from_query(query: &SimilarQuery, request: &HttpRequest) -> Self {
         let SimilarQuery {
-            id: _,
+            id,
+            positive_ids,
+            negative_ids,
             embedder,
             offset,
             limit,
@@ -1835,6 +1970,8 @@ impl SimilarAggregator {
 
         ret.embedder = embedder.is_some();
         ret.retrieve_vectors = *retrieve_vectors;
+        ret.max_positive_ids = positive_ids.len() + usize::from(id.is_some());
+        ret.max_negative_ids = negative_ids.len();
 
         ret
     }
@@ -1868,6 +2005,8 @@ impl SimilarAggregator {
             embedder,
             ranking_score_threshold,
             retrieve_vectors,
+            max_positive_ids,
+            max_negative_ids,
         } = other;
 
         if self.timestamp.is_none() {
@@ -1898,6 +2037,8 @@ impl SimilarAggregator {
 
         self.embedder |= embedder;
         self.retrieve_vectors |= retrieve_vectors;
+        self.max_positive_ids = self.max_positive_ids.max(max_positive_ids);
+        self.max_negative_ids = self.max_negative_ids.max(max_negative_ids);
 
         // pagination
         self.max_limit = self.max_limit.max(max_limit);
@@ -1933,6 +2074,8 @@ impl SimilarAggregator {
             embedder,
             ranking_score_threshold,
             retrieve_vectors,
+            max_positive_ids,
+            max_negative_ids,
         } = self;
 
         if total_received == 0 {
@@ -1961,6 +2104,8 @@ impl SimilarAggregator {
                 },
                 "vector": {
                     "retrieve_vectors": retrieve_vectors,
+                    "max_positive_ids": max_positive_ids,
+                    "max_negative_ids": max_negative_ids,
                 },
                 "hybrid": {
                     "embedder": embedder,
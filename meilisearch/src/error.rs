@@ -31,10 +31,20 @@ pub enum MeilisearchHttpError {
     FederationOptionsInNonFederatedRequest(usize),
     #[error("Inside `.queries[{0}]`: Using pagination options is not allowed in federated queries.\n Hint: remove `{1}` from query #{0} or remove `federation: {{}}` from the request")]
     PaginationInFederatedQuery(usize, &'static str),
+    #[error("Inside `.queries[{0}]`: Using `collapse` is not allowed in federated queries.\n Hint: remove `collapse` from query #{0} or remove `federation: {{}}` from the request")]
+    CollapseInFederatedQuery(usize),
+    #[error("Inside `.queries[{0}]`: Using `rerank` is not allowed in federated queries.\n Hint: remove `rerank` from query #{0} or remove `federation: {{}}` from the request")]
+    RerankInFederatedQuery(usize),
+    #[error("Using `federation.rankConstant` is only allowed with the `rrf` merge strategy.\n Hint: remove `rankConstant` or add `mergeStrategy: \"rrf\"` to the federation")]
+    RankConstantWithoutRrf,
+    #[error("Using `hybrid.rankConstant` is only allowed with the `rrf` fusion.\n Hint: remove `rankConstant` or add `fusion: \"rrf\"` to `hybrid`")]
+    HybridRankConstantWithoutRrf,
     #[error("A {0} payload is missing.")]
     MissingPayload(PayloadType),
     #[error("Too many search requests running at the same time: {0}. Retry after 10s.")]
     TooManySearchRequests(usize),
+    #[error("The search export was stopped after {0} seconds.\n - Hint: narrow the export down with a `filter`.")]
+    SearchExportTimeout(u64),
     #[error("Internal error: Search limiter is down.")]
     SearchLimiterIsDown,
     #[error("The provided payload reached the size limit. The maximum accepted payload size is {}.",  Byte::from_u64(*.0 as u64).get_appropriate_unit(UnitType::Binary))]
@@ -79,6 +89,7 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::PayloadTooLarge(_) => Code::PayloadTooLarge,
             MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
             MeilisearchHttpError::SearchLimiterIsDown => Code::Internal,
+            MeilisearchHttpError::SearchExportTimeout(_) => Code::BadRequest,
             MeilisearchHttpError::SwapIndexPayloadWrongLength(_) => Code::InvalidSwapIndexes,
             MeilisearchHttpError::IndexUid(e) => e.error_code(),
             MeilisearchHttpError::SerdeJson(_) => Code::Internal,
@@ -96,6 +107,10 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::PaginationInFederatedQuery(_, _) => {
                 Code::InvalidMultiSearchQueryPagination
             }
+            MeilisearchHttpError::CollapseInFederatedQuery(_) => Code::InvalidSearchCollapse,
+            MeilisearchHttpError::RerankInFederatedQuery(_) => Code::InvalidSearchRerank,
+            MeilisearchHttpError::RankConstantWithoutRrf => Code::InvalidMultiSearchRankConstant,
+            MeilisearchHttpError::HybridRankConstantWithoutRrf => Code::InvalidSearchRankConstant,
         }
     }
 }
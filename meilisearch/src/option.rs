@@ -55,10 +55,13 @@ const MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE: &str = "MEILI_EXPERIMENTAL_ENABLE_LO
 const MEILI_EXPERIMENTAL_CONTAINS_FILTER: &str = "MEILI_EXPERIMENTAL_CONTAINS_FILTER";
 const MEILI_EXPERIMENTAL_ENABLE_METRICS: &str = "MEILI_EXPERIMENTAL_ENABLE_METRICS";
 const MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE";
+const MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE";
+const MEILI_EXPERIMENTAL_SEARCH_CACHE_TTL: &str = "MEILI_EXPERIMENTAL_SEARCH_CACHE_TTL";
 const MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE: &str =
     "MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE";
 const MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS: &str =
     "MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS";
+const MEILI_EXPERIMENTAL_LOCAL_MODELS_DIR: &str = "MEILI_EXPERIMENTAL_LOCAL_MODELS_DIR";
 
 const DEFAULT_CONFIG_FILE_PATH: &str = "./config.toml";
 const DEFAULT_DB_PATH: &str = "./data.ms";
@@ -361,6 +364,22 @@ pub struct Opt {
     #[serde(default)]
     pub experimental_search_queue_size: usize,
 
+    /// Experimental search cache feature.
+    ///
+    /// Sets the maximum amount of memory used to keep the results of the searches, so that a search made again
+    /// is answered without being computed until its index is updated. Value must be given in bytes or explicitly
+    /// stating a base unit (for instance: '100 MB').
+    /// The default value is 0, which disables the cache.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE, default_value_t = default_search_cache_size())]
+    #[serde(default = "default_search_cache_size")]
+    pub experimental_search_cache_size: Byte,
+
+    /// Sets the number of seconds a result is kept in the search cache, even if its index isn't updated.
+    /// The default value is 60.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_CACHE_TTL, default_value_t = default_search_cache_ttl())]
+    #[serde(default = "default_search_cache_ttl")]
+    pub experimental_search_cache_ttl: u64,
+
     /// Experimental logs mode feature. For more information, see: <https://github.com/orgs/meilisearch/discussions/723>
     ///
     /// Change the mode of the logs on the console.
@@ -395,6 +414,13 @@ pub struct Opt {
     #[serde(default = "default_limit_batched_tasks")]
     pub experimental_max_number_of_batched_tasks: usize,
 
+    /// Experimental local models feature.
+    ///
+    /// Sets the directory from which the Hugging Face embedders can load a model with `modelPath`.
+    /// The `modelPath` of an embedder must be inside this directory, and cannot be used when it isn't set.
+    #[clap(long, env = MEILI_EXPERIMENTAL_LOCAL_MODELS_DIR)]
+    pub experimental_local_models_dir: Option<PathBuf>,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -492,10 +518,13 @@ impl Opt {
             experimental_contains_filter,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_search_cache_size,
+            experimental_search_cache_ttl,
             experimental_logs_mode,
             experimental_enable_logs_route,
             experimental_replication_parameters,
             experimental_reduce_indexing_memory_usage,
+            experimental_local_models_dir,
         } = self;
         export_to_env_if_not_present(MEILI_DB_PATH, db_path);
         export_to_env_if_not_present(MEILI_HTTP_ADDR, http_addr);
@@ -559,6 +588,14 @@ impl Opt {
             MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE,
             experimental_search_queue_size.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE,
+            experimental_search_cache_size.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_CACHE_TTL,
+            experimental_search_cache_ttl.to_string(),
+        );
         export_to_env_if_not_present(
             MEILI_EXPERIMENTAL_LOGS_MODE,
             experimental_logs_mode.to_string(),
@@ -575,6 +612,12 @@ impl Opt {
             MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE,
             experimental_reduce_indexing_memory_usage.to_string(),
         );
+        if let Some(experimental_local_models_dir) = experimental_local_models_dir {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_LOCAL_MODELS_DIR,
+                experimental_local_models_dir,
+            );
+        }
         indexer_options.export_to_env();
     }
 
@@ -878,6 +921,14 @@ fn default_limit_batched_tasks() -> usize {
     usize::MAX
 }
 
+fn default_search_cache_size() -> Byte {
+    Byte::from_u64(0)
+}
+
+fn default_search_cache_ttl() -> u64 {
+    60
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
@@ -132,12 +132,18 @@ impl From<FacetSearchQuery> for SearchQuery {
             filter,
             sort: None,
             distinct: None,
+            collapse: None,
+            rerank: None,
+            boost: None,
+            boost_position: None,
+            search_after: None,
             facets: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
             matching_strategy,
             vector,
+            sparse_vector: None,
             attributes_to_search_on,
             hybrid,
             ranking_score_threshold,
@@ -10,6 +10,7 @@ use meilisearch_types::deserr::{immutable_field_error, DeserrJsonError, DeserrQu
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::vector::cache::EmbeddingCacheStats;
 use meilisearch_types::milli::{self, FieldDistribution, Index};
 use meilisearch_types::tasks::KindWithContent;
 use serde::Serialize;
@@ -27,6 +28,7 @@ use crate::Opt;
 
 pub mod documents;
 pub mod facet_search;
+pub mod render;
 pub mod search;
 pub mod settings;
 pub mod similar;
@@ -46,10 +48,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                     .route(web::delete().to(SeqHandler(delete_index))),
             )
             .service(web::resource("/stats").route(web::get().to(SeqHandler(get_index_stats))))
+            .service(
+                web::resource("/embedding-cache")
+                    .route(web::delete().to(SeqHandler(clear_embedding_cache))),
+            )
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
             .service(web::scope("/similar").configure(similar::configure))
+            .service(web::scope("/render").configure(render::configure))
             .service(web::scope("/settings").configure(settings::configure)),
     );
 }
@@ -246,6 +253,25 @@ pub async fn delete_index(
     Ok(HttpResponse::Accepted().json(task))
 }
 
+pub async fn clear_embedding_cache(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SETTINGS_UPDATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let task = KindWithContent::EmbeddingCacheClear { index_uid: index_uid.into_inner() };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+    debug!(returns = ?task, "Clear embedding cache");
+
+    Ok(HttpResponse::Accepted().json(task))
+}
+
 /// Stats of an `Index`, as known to the `stats` route.
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -256,14 +282,19 @@ pub struct IndexStats {
     pub is_indexing: bool,
     /// Association of every field name with the number of times it occurs in the documents.
     pub field_distribution: FieldDistribution,
+    /// Statistics of the embedding cache, only present once embeddings were generated for the index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_cache: Option<EmbeddingCacheStats>,
 }
 
 impl From<index_scheduler::IndexStats> for IndexStats {
     fn from(stats: index_scheduler::IndexStats) -> Self {
+        let embedding_cache = stats.inner_stats.embedding_cache;
         IndexStats {
             number_of_documents: stats.inner_stats.number_of_documents,
             is_indexing: stats.is_indexing,
             field_distribution: stats.inner_stats.field_distribution,
+            embedding_cache: (!embedding_cache.is_empty()).then_some(embedding_cache),
         }
     }
 }
@@ -0,0 +1,145 @@
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::keys::actions;
+use meilisearch_types::milli::prompt::Prompt;
+use meilisearch_types::milli::vector::chunking::count_tokens;
+use meilisearch_types::milli::{self, Index};
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use super::ActionPolicy;
+use crate::analytics::Analytics;
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::search::ExternalDocumentId;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(render_post))));
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct RenderQuery {
+    #[deserr(default, error = DeserrJsonError<InvalidRenderIds>)]
+    pub ids: Vec<ExternalDocumentId>,
+    #[deserr(default, error = DeserrJsonError<InvalidRenderDocumentTemplate>)]
+    pub document_template: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidEmbedder>)]
+    pub embedder: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderResult {
+    pub document_template: String,
+    pub prompts: Vec<RenderedPrompt>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedPrompt {
+    pub id: String,
+    pub prompt: String,
+    /// Number of tokens of the prompt, counted with the `cl100k_base` tokenizer.
+    pub tokens: usize,
+}
+
+pub async fn render_post(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<RenderQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Render post");
+
+    index_scheduler.features().check_vector("Using the render API")?;
+
+    analytics.publish(
+        "Documents Rendered POST".to_string(),
+        json!({
+            "ids": query.ids.len(),
+            "document_template": query.document_template.is_some(),
+            "embedder": query.embedder.is_some(),
+        }),
+        Some(&req),
+    );
+
+    let index = index_scheduler.index(&index_uid)?;
+    let result = tokio::task::spawn_blocking(move || perform_render(&index, query)).await??;
+
+    debug!(returns = ?result, "Render post");
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Renders the template against the documents as they are currently stored, without indexing anything.
+fn perform_render(index: &Index, query: RenderQuery) -> Result<RenderResult, ResponseError> {
+    let RenderQuery { ids, document_template, embedder } = query;
+
+    if ids.is_empty() {
+        return Err(ResponseError::from_msg(
+            "Missing field `ids`: at least one document id must be given.".to_string(),
+            Code::InvalidRenderIds,
+        ));
+    }
+
+    let rtxn = index.read_txn()?;
+
+    let template = match (document_template, embedder) {
+        (Some(_), Some(_)) => {
+            return Err(ResponseError::from_msg(
+                "`documentTemplate` and `embedder` cannot be used together.\n - Hint: Remove `embedder` to render the given `documentTemplate`.".to_string(),
+                Code::InvalidRenderDocumentTemplate,
+            ))
+        }
+        (Some(template), None) => template,
+        (None, embedder) => {
+            let configs = index.embedding_configs(&rtxn)?;
+            // same rule as `EmbeddingConfigs::get_default_embedder_name`
+            let embedder = embedder.unwrap_or_else(|| match configs.as_slice() {
+                [config] => config.name.clone(),
+                _ => "default".to_string(),
+            });
+            let config = configs
+                .into_iter()
+                .find(|config| config.name == embedder)
+                .ok_or(milli::UserError::InvalidEmbedder(embedder))
+                .map_err(milli::Error::from)?;
+            config.config.prompt.template
+        }
+    };
+
+    let prompt = Prompt::new(template.clone()).map_err(|error| {
+        ResponseError::from_msg(error.to_string(), Code::InvalidRenderDocumentTemplate)
+    })?;
+
+    let external_documents_ids = index.external_documents_ids();
+    let mut docids = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let docid = external_documents_ids
+            .get(&rtxn, id.as_ref())?
+            .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(id.as_ref().to_owned()))?;
+        docids.push(docid);
+    }
+
+    let fields_ids_map = index.fields_ids_map(&rtxn)?;
+    let mut prompts = Vec::with_capacity(ids.len());
+    for (id, (_docid, document)) in ids.into_iter().zip(index.documents(&rtxn, docids)?) {
+        let prompt = prompt.render_stored(document, &fields_ids_map)?;
+        let tokens = count_tokens(&prompt);
+        prompts.push(RenderedPrompt { id: id.into_inner(), prompt, tokens });
+    }
+
+    Ok(RenderResult { document_template: template, prompts })
+}
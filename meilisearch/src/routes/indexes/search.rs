@@ -1,15 +1,20 @@
-use actix_web::web::Data;
+use std::convert::Infallible;
+use std::time::Instant;
+
+use actix_web::web::{Bytes, Data};
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
+use deserr::{DeserializeError, ErrorKind, ValuePointerRef};
 use index_scheduler::{IndexScheduler, RoFeatures};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli;
+use meilisearch_types::milli::query_rules::BoostClause;
 use meilisearch_types::serde_cs::vec::CS;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::debug;
 
 use crate::analytics::{Analytics, SearchAggregator};
@@ -19,11 +24,13 @@ use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::metrics::MEILISEARCH_DEGRADED_SEARCH_REQUESTS;
 use crate::search::{
-    add_search_rules, perform_search, HybridQuery, MatchingStrategy, RankingScoreThreshold,
-    RetrieveVectors, SearchKind, SearchQuery, SemanticRatio, DEFAULT_CROP_LENGTH,
+    add_search_rules, perform_explain, perform_search, CollapseQuery, HybridFusion, HybridQuery,
+    MatchingStrategy, RankingScoreThreshold, RerankQuery, RetrieveVectors, SearchExport,
+    SearchExportQuery, SearchKind, SearchQuery, SearchResult, SemanticRatio, DEFAULT_CROP_LENGTH,
     DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG,
-    DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
+    DEFAULT_RERANK_TOP_K, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
 };
+use crate::search_cache::{CacheKey, SearchCache};
 use crate::search_queue::SearchQueue;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -31,11 +38,13 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         web::resource("")
             .route(web::get().to(SeqHandler(search_with_url_query)))
             .route(web::post().to(SeqHandler(search_with_post))),
-    );
+    )
+    .service(web::resource("/export").route(web::post().to(SeqHandler(export_search))))
+    .service(web::resource("/explain").route(web::post().to(SeqHandler(explain_search))));
 }
 
 #[derive(Debug, deserr::Deserr)]
-#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields, validate = validate_search_query_get -> DeserrQueryParamError<InvalidSearchCollapse>)]
 pub struct SearchQueryGet {
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchQ>)]
     q: Option<String>,
@@ -65,6 +74,22 @@ pub struct SearchQueryGet {
     sort: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchDistinct>)]
     distinct: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchCollapse>)]
+    collapse_attribute: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchCollapse>)]
+    collapse_inner_hits: Option<Param<usize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRerank>)]
+    reranker: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRerank>)]
+    rerank_top_k: Option<Param<usize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchBoost>)]
+    boost_filter: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchBoost>)]
+    boost_weight: Option<Param<i32>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchBoostPosition>)]
+    boost_position: Option<Param<usize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSearchAfter>)]
+    search_after: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowMatchesPosition>)]
     show_matches_position: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScore>)]
@@ -125,6 +150,23 @@ impl std::ops::Deref for SemanticRatioGet {
     }
 }
 
+fn validate_search_query_get<E: DeserializeError>(
+    query: SearchQueryGet,
+    location: ValuePointerRef,
+) -> Result<SearchQueryGet, E> {
+    if query.collapse_inner_hits.is_some() && query.collapse_attribute.is_none() {
+        Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: "`collapseInnerHits` requires a `collapseAttribute`.".to_string(),
+            },
+            location.push_key("collapseInnerHits"),
+        )))
+    } else {
+        Ok(query)
+    }
+}
+
 impl From<SearchQueryGet> for SearchQuery {
     fn from(other: SearchQueryGet) -> Self {
         let filter = match other.filter {
@@ -138,20 +180,44 @@ impl From<SearchQueryGet> for SearchQuery {
         let hybrid = match (other.hybrid_embedder, other.hybrid_semantic_ratio) {
             (None, None) => None,
             (None, Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: None })
+                Some(HybridQuery { semantic_ratio: *semantic_ratio, ..Default::default() })
             }
             (Some(embedder), None) => Some(HybridQuery {
                 semantic_ratio: DEFAULT_SEMANTIC_RATIO(),
                 embedder: Some(embedder),
+                ..Default::default()
+            }),
+            (Some(embedder), Some(semantic_ratio)) => Some(HybridQuery {
+                semantic_ratio: *semantic_ratio,
+                embedder: Some(embedder),
+                ..Default::default()
+            }),
+        };
+
+        let collapse = other.collapse_attribute.map(|attribute| CollapseQuery {
+            attribute,
+            inner_hits: other.collapse_inner_hits.as_deref().copied().unwrap_or_default(),
+        });
+
+        let rerank = match (other.reranker, other.rerank_top_k) {
+            (None, None) => None,
+            (reranker, top_k) => Some(RerankQuery {
+                reranker,
+                top_k: top_k.as_deref().copied().unwrap_or_else(DEFAULT_RERANK_TOP_K),
             }),
-            (Some(embedder), Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: Some(embedder) })
-            }
         };
 
+        let boost = other.boost_filter.map(|filter| {
+            vec![BoostClause {
+                filter,
+                weight: other.boost_weight.as_deref().copied().unwrap_or(1),
+            }]
+        });
+
         Self {
             q: other.q,
             vector: other.vector.map(CS::into_inner),
+            sparse_vector: None,
             offset: other.offset.0,
             limit: other.limit.0,
             page: other.page.as_deref().copied(),
@@ -164,6 +230,11 @@ impl From<SearchQueryGet> for SearchQuery {
             filter,
             sort: other.sort.map(|attr| fix_sort_query_parameters(&attr)),
             distinct: other.distinct,
+            collapse,
+            rerank,
+            boost,
+            boost_position: other.boost_position.as_deref().copied(),
+            search_after: other.search_after,
             show_matches_position: other.show_matches_position.0,
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
@@ -207,6 +278,7 @@ fn fix_sort_query_parameters(sort_query: &str) -> Vec<String> {
 pub async fn search_with_url_query(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: web::Data<SearchQueue>,
+    search_cache: web::Data<SearchCache>,
     index_uid: web::Path<String>,
     params: AwebQueryParameter<SearchQueryGet, DeserrQueryParamError>,
     req: HttpRequest,
@@ -229,10 +301,15 @@ pub async fn search_with_url_query(
 
     let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
     let retrieve_vector = RetrieveVectors::new(query.retrieve_vectors, features)?;
-    let _permit = search_queue.try_get_search_permit().await?;
-    let search_result = tokio::task::spawn_blocking(move || {
-        perform_search(&index, query, search_kind, retrieve_vector, index_scheduler.features())
-    })
+    let cache_key = search_cache.key(&index_uid, &query, features.runtime_features());
+    let search_result = cached_search(
+        &search_cache,
+        &search_queue,
+        &index_scheduler,
+        &index_uid,
+        cache_key,
+        move || perform_search(&index, query, search_kind, retrieve_vector, features),
+    )
     .await?;
     if let Ok(ref search_result) = search_result {
         aggregate.succeed(search_result);
@@ -248,6 +325,7 @@ pub async fn search_with_url_query(
 pub async fn search_with_post(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: web::Data<SearchQueue>,
+    search_cache: web::Data<SearchCache>,
     index_uid: web::Path<String>,
     params: AwebJson<SearchQuery, DeserrJsonError>,
     req: HttpRequest,
@@ -272,10 +350,15 @@ pub async fn search_with_post(
     let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
     let retrieve_vectors = RetrieveVectors::new(query.retrieve_vectors, features)?;
 
-    let _permit = search_queue.try_get_search_permit().await?;
-    let search_result = tokio::task::spawn_blocking(move || {
-        perform_search(&index, query, search_kind, retrieve_vectors, index_scheduler.features())
-    })
+    let cache_key = search_cache.key(&index_uid, &query, features.runtime_features());
+    let search_result = cached_search(
+        &search_cache,
+        &search_queue,
+        &index_scheduler,
+        &index_uid,
+        cache_key,
+        move || perform_search(&index, query, search_kind, retrieve_vectors, features),
+    )
     .await?;
     if let Ok(ref search_result) = search_result {
         aggregate.succeed(search_result);
@@ -291,6 +374,141 @@ pub async fn search_with_post(
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+pub async fn explain_search(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    search_queue: web::Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SearchQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let mut query = params.into_inner();
+    debug!(parameters = ?query, "Search explain");
+
+    // Tenant token search_rules.
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        add_search_rules(&mut query.filter, search_rules);
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    let features = index_scheduler.features();
+
+    let _permit = search_queue.try_get_search_permit().await?;
+    let explanation =
+        tokio::task::spawn_blocking(move || perform_explain(&index, query, features)).await??;
+
+    debug!(returns = ?explanation, "Search explain");
+    Ok(HttpResponse::Ok().json(explanation))
+}
+
+pub async fn export_search(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    search_queue: web::Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SearchExportQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let mut query: SearchQuery = params.into_inner().into();
+    debug!(parameters = ?query, "Search export");
+
+    analytics.publish(
+        "Documents Exported POST".to_string(),
+        json!({
+            "q": query.q.is_some(),
+            "filter": query.filter.is_some(),
+            "sort": query.sort.as_ref().map_or(0, Vec::len),
+            "distinct": query.distinct.is_some(),
+            "retrieve_vectors": query.retrieve_vectors,
+        }),
+        Some(&req),
+    );
+
+    // Tenant token search_rules.
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        add_search_rules(&mut query.filter, search_rules);
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    let features = index_scheduler.features();
+    let retrieve_vectors = RetrieveVectors::new(query.retrieve_vectors, features)?;
+
+    // the first chunk is ranked before answering, so that an invalid search returns an error
+    let permit = search_queue.try_get_search_permit().await?;
+    let (export, first_chunk) = tokio::task::spawn_blocking(move || {
+        let mut export = SearchExport::new(index, query, retrieve_vectors, features)?;
+        let first_chunk = export.next_chunk()?;
+        Ok::<_, ResponseError>((export, first_chunk))
+    })
+    .await??;
+    drop(permit);
+
+    let first_chunk = futures_util::stream::iter(first_chunk.map(|chunk| Ok(Bytes::from(chunk))));
+    let next_chunks = futures_util::stream::unfold(Some(export), move |export| {
+        let search_queue = search_queue.clone();
+        async move {
+            let mut export = export?;
+            // each chunk waits for its turn among the other searches
+            let permit = match search_queue.try_get_search_permit().await {
+                Ok(permit) => permit,
+                Err(error) => return Some((Err(error.into()), None)),
+            };
+            let chunk = tokio::task::spawn_blocking(move || {
+                export.next_chunk().map(|chunk| chunk.map(|chunk| (chunk, export)))
+            })
+            .await;
+            drop(permit);
+
+            match chunk {
+                Ok(Ok(Some((chunk, export)))) => Some((Ok(Bytes::from(chunk)), Some(export))),
+                Ok(Ok(None)) => None,
+                // the stream is stopped after an error
+                Ok(Err(error)) => Some((Err(error), None)),
+                Err(error) => Some((Err(error.into()), None)),
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(futures_util::StreamExt::chain(first_chunk, next_chunks)))
+}
+
+/// Returns the result of the search from the search cache, or performs it with `search`
+/// while holding a search permit and caches its result.
+///
+/// The outer error is returned when the search couldn't be performed, the inner one when it failed.
+async fn cached_search(
+    search_cache: &SearchCache,
+    search_queue: &SearchQueue,
+    index_scheduler: &IndexScheduler,
+    index_uid: &IndexUid,
+    cache_key: Option<CacheKey>,
+    search: impl FnOnce() -> Result<SearchResult, ResponseError> + Send + 'static,
+) -> Result<Result<SearchResult, ResponseError>, ResponseError> {
+    let before_search = Instant::now();
+    // The generation must be read before searching, see the `search_cache` module.
+    let generation = index_scheduler.index_generation(index_uid);
+    if let Some(mut search_result) =
+        cache_key.as_ref().and_then(|key| search_cache.get(key, generation))
+    {
+        search_result.processing_time_ms = before_search.elapsed().as_millis();
+        return Ok(Ok(search_result));
+    }
+
+    let _permit = search_queue.try_get_search_permit().await?;
+    let search_result = tokio::task::spawn_blocking(search).await?;
+    if let (Some(key), Ok(search_result)) = (cache_key, &search_result) {
+        // A degraded search may return more hits when made again
+        if !search_result.degraded {
+            search_cache.insert(key, generation, search_result);
+        }
+    }
+    Ok(search_result)
+}
+
 pub fn search_kind(
     query: &SearchQuery,
     index_scheduler: &IndexScheduler,
@@ -300,12 +518,32 @@ pub fn search_kind(
     if query.vector.is_some() {
         features.check_vector("Passing `vector` as a parameter")?;
     }
+    if query.sparse_vector.is_some() {
+        features.check_vector("Passing `sparseVector` as a parameter")?;
+    }
     if query.hybrid.is_some() {
         features.check_vector("Passing `hybrid` as a parameter")?;
     }
+    if query.rerank.is_some() {
+        features.check_vector("Passing `rerank` as a parameter")?;
+    }
+
+    if query.vector.is_some() && query.sparse_vector.is_some() {
+        return Err(ResponseError::from_msg(
+            "`vector` and `sparseVector` cannot be used together in the same search.".to_string(),
+            Code::InvalidSearchSparseVector,
+        ));
+    }
+    let has_vector = query.vector.is_some() || query.sparse_vector.is_some();
+
+    if let Some(HybridQuery { fusion: HybridFusion::Score, rank_constant: Some(_), .. }) =
+        &query.hybrid
+    {
+        return Err(MeilisearchHttpError::HybridRankConstantWithoutRrf.into());
+    }
 
     // regardless of anything, always do a keyword search when we don't have a vector and the query is whitespace or missing
-    if query.vector.is_none() {
+    if !has_vector {
         match &query.q {
             Some(q) if q.trim().is_empty() => return Ok(SearchKind::KeywordOnly),
             None => return Ok(SearchKind::KeywordOnly),
@@ -314,7 +552,7 @@ pub fn search_kind(
     }
 
     match &query.hybrid {
-        Some(HybridQuery { semantic_ratio, embedder }) if **semantic_ratio == 1.0 => {
+        Some(HybridQuery { semantic_ratio, embedder, .. }) if **semantic_ratio == 1.0 => {
             Ok(SearchKind::semantic(
                 index_scheduler,
                 index,
@@ -322,25 +560,25 @@ pub fn search_kind(
                 query.vector.as_ref().map(Vec::len),
             )?)
         }
-        Some(HybridQuery { semantic_ratio, embedder: _ }) if **semantic_ratio == 0.0 => {
+        Some(HybridQuery { semantic_ratio, .. }) if **semantic_ratio == 0.0 => {
             Ok(SearchKind::KeywordOnly)
         }
-        Some(HybridQuery { semantic_ratio, embedder }) => Ok(SearchKind::hybrid(
+        Some(HybridQuery { semantic_ratio, embedder, .. }) => Ok(SearchKind::hybrid(
             index_scheduler,
             index,
             embedder.as_deref(),
             **semantic_ratio,
             query.vector.as_ref().map(Vec::len),
         )?),
-        None => match (query.q.as_deref(), query.vector.as_deref()) {
-            (_query, None) => Ok(SearchKind::KeywordOnly),
-            (None, Some(_vector)) => Ok(SearchKind::semantic(
+        None => match (query.q.as_deref(), has_vector) {
+            (_query, false) => Ok(SearchKind::KeywordOnly),
+            (None, true) => Ok(SearchKind::semantic(
                 index_scheduler,
                 index,
                 None,
                 query.vector.as_ref().map(Vec::len),
             )?),
-            (Some(_), Some(_)) => Err(MeilisearchHttpError::MissingSearchHybrid.into()),
+            (Some(_), true) => Err(MeilisearchHttpError::MissingSearchHybrid.into()),
         },
     }
 }
@@ -1,12 +1,15 @@
+use std::path::Path;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
 use index_scheduler::IndexScheduler;
 use meilisearch_types::deserr::DeserrJsonError;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::facet_values_sort::FacetValuesSort;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::Setting;
+use meilisearch_types::milli::vector::settings::{EmbedderSource, EmbeddingSettings};
 use meilisearch_types::settings::{settings, RankingRuleView, SecretPolicy, Settings, Unchecked};
 use meilisearch_types::tasks::KindWithContent;
 use serde_json::json;
@@ -101,6 +104,7 @@ macro_rules! make_setting_route {
                 let new_settings = $crate::routes::indexes::settings::validate_settings(
                     new_settings,
                     &index_scheduler,
+                    &opt,
                 )?;
 
                 let allow_index_creation =
@@ -614,11 +618,59 @@ fn embedder_analytics(
             .any(|config| config.document_template.set().is_some())
     });
 
+    let binary_quantization_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.binary_quantized.set().is_some())
+    });
+
+    let chunking_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.chunking.set().is_some())
+    });
+
+    let search_embedder_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.search_embedder.set().is_some())
+    });
+
+    let on_error_skip_vectors_used = setting.as_ref().map(|map| {
+        map.values().filter_map(|config| config.clone().set()).any(|config| {
+            config.on_error.set()
+                == Some(meilisearch_types::milli::vector::OnEmbeddingError::SkipVectors)
+        })
+    });
+
+    let sparse_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.sparse.set() == Some(true))
+    });
+
+    let mut distances = std::collections::HashSet::new();
+    if let Some(s) = &setting {
+        let distances_ = s
+            .values()
+            .filter_map(|config| config.clone().set())
+            .filter_map(|config| config.distance.set());
+        for distance in distances_ {
+            distances.insert(distance.to_string());
+        }
+    };
+
     json!(
         {
             "total": setting.as_ref().map(|s| s.len()),
             "sources": sources,
             "document_template_used": document_template_used,
+            "binary_quantization_used": binary_quantization_used,
+            "distances": distances,
+            "chunking_used": chunking_used,
+            "search_embedder_used": search_embedder_used,
+            "on_error_skip_vectors_used": on_error_skip_vectors_used,
+            "sparse_used": sparse_used,
         }
     )
 }
@@ -642,6 +694,56 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/rules",
+    put,
+    Vec<meilisearch_types::milli::QueryRule>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsRules,
+    >,
+    rules,
+    "rules",
+    analytics,
+    |setting: &Option<Vec<meilisearch_types::milli::QueryRule>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "Rules Updated".to_string(),
+            json!({
+                "rules": {
+                    "total": setting.as_ref().map(|rules| rules.len()),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/rerankers",
+    put,
+    std::collections::BTreeMap<String, meilisearch_types::milli::RerankerSettings>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsRerankers,
+    >,
+    rerankers,
+    "rerankers",
+    analytics,
+    |setting: &Option<std::collections::BTreeMap<String, meilisearch_types::milli::RerankerSettings>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "Rerankers Updated".to_string(),
+            json!({
+                "rerankers": {
+                    "total": setting.as_ref().map(|rerankers| rerankers.len()),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
 macro_rules! generate_configure {
     ($($mod:ident),*) => {
         pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -673,7 +775,9 @@ generate_configure!(
     pagination,
     faceting,
     embedders,
-    search_cutoff_ms
+    search_cutoff_ms,
+    rules,
+    rerankers
 );
 
 pub async fn update_all(
@@ -688,7 +792,7 @@ pub async fn update_all(
 
     let new_settings = body.into_inner();
     debug!(parameters = ?new_settings, "Update all settings");
-    let new_settings = validate_settings(new_settings, &index_scheduler)?;
+    let new_settings = validate_settings(new_settings, &index_scheduler, &opt)?;
 
     analytics.publish(
         "Settings Updated".to_string(),
@@ -786,6 +890,12 @@ pub async fn update_all(
             },
             "embedders": crate::routes::indexes::settings::embedder_analytics(new_settings.embedders.as_ref().set()),
             "search_cutoff_ms": new_settings.search_cutoff_ms.as_ref().set(),
+            "rules": {
+                "total": new_settings.rules.as_ref().set().map(|rules| rules.len()),
+            },
+            "rerankers": {
+                "total": new_settings.rerankers.as_ref().set().map(|rerankers| rerankers.len()),
+            },
         }),
         Some(&req),
     );
@@ -854,9 +964,47 @@ pub async fn delete_all(
 fn validate_settings(
     settings: Settings<Unchecked>,
     index_scheduler: &IndexScheduler,
+    opt: &Opt,
 ) -> Result<Settings<Unchecked>, ResponseError> {
-    if matches!(settings.embedders, Setting::Set(_)) {
-        index_scheduler.features().check_vector("Passing `embedders` in settings")?
+    if let Setting::Set(embedders) = &settings.embedders {
+        index_scheduler.features().check_vector("Passing `embedders` in settings")?;
+        for (name, embedder) in embedders {
+            let Setting::Set(EmbeddingSettings { source, model_path, .. }) = embedder else {
+                continue;
+            };
+            // other sources reject `modelPath` with a dedicated error when the task is processed
+            if matches!(source, Setting::Set(source) if *source != EmbedderSource::HuggingFace) {
+                continue;
+            }
+            if let Setting::Set(model_path) = model_path {
+                validate_model_path(name, model_path, opt)?;
+            }
+        }
+    }
+    if matches!(settings.rerankers, Setting::Set(_)) {
+        index_scheduler.features().check_vector("Passing `rerankers` in settings")?
     }
     Ok(settings.validate()?)
 }
+
+/// Checks that a local model is loaded from the directory allowed by `--experimental-local-models-dir`.
+///
+/// The same error is returned whether the path exists or not, so that it can't be used to probe
+/// the files of the server.
+fn validate_model_path(name: &str, model_path: &str, opt: &Opt) -> Result<(), ResponseError> {
+    let Some(models_dir) = &opt.experimental_local_models_dir else {
+        return Err(ResponseError::from_msg(
+            format!("`.embedders.{name}`: `modelPath` requires launching Meilisearch with `--experimental-local-models-dir`."),
+            Code::InvalidSettingsEmbedders,
+        ));
+    };
+    let models_dir = models_dir.canonicalize().ok();
+    let model_path = Path::new(model_path).canonicalize().ok();
+    match (models_dir, model_path) {
+        (Some(models_dir), Some(model_path)) if model_path.starts_with(models_dir) => Ok(()),
+        _ => Err(ResponseError::from_msg(
+            format!("`.embedders.{name}`: `modelPath` must be an existing directory inside the directory passed to `--experimental-local-models-dir`."),
+            Code::InvalidSettingsEmbedders,
+        )),
+    }
+}
@@ -5,7 +5,7 @@ use index_scheduler::IndexScheduler;
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::{ErrorCode as _, ResponseError};
+use meilisearch_types::error::{Code, ErrorCode as _, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::keys::actions;
 use meilisearch_types::serde_cs::vec::CS;
@@ -17,8 +17,9 @@ use crate::analytics::{Analytics, SimilarAggregator};
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::search::{
-    add_search_rules, perform_similar, RankingScoreThresholdSimilar, RetrieveVectors, SearchKind,
-    SimilarQuery, SimilarResult, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
+    add_search_rules, perform_similar, ExternalDocumentId, RankingScoreThresholdSimilar,
+    RetrieveVectors, SearchKind, SimilarQuery, SimilarResult, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET,
 };
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -91,8 +92,6 @@ async fn similar(
 ) -> Result<SimilarResult, ResponseError> {
     let features = index_scheduler.features();
 
-    features.check_vector("Using the similar API")?;
-
     let retrieve_vectors = RetrieveVectors::new(query.retrieve_vectors, features)?;
 
     // Tenant token search_rules.
@@ -102,18 +101,17 @@ async fn similar(
 
     let index = index_scheduler.index(&index_uid)?;
 
-    let (embedder_name, embedder) =
-        SearchKind::embedder(&index_scheduler, &index, query.embedder.as_deref(), None)?;
+    // the indexes without embedders search for documents sharing the same words
+    let has_embedders = !index.embedding_configs(&index.read_txn()?)?.is_empty();
+    let embedder = if query.embedder.is_some() || has_embedders {
+        features.check_vector("Using the similar API with an embedder")?;
+        Some(SearchKind::embedder(&index_scheduler, &index, query.embedder.as_deref(), None)?)
+    } else {
+        None
+    };
 
     tokio::task::spawn_blocking(move || {
-        perform_similar(
-            &index,
-            query,
-            embedder_name,
-            embedder,
-            retrieve_vectors,
-            index_scheduler.features(),
-        )
+        perform_similar(&index, query, embedder, retrieve_vectors, index_scheduler.features())
     })
     .await?
 }
@@ -121,8 +119,12 @@ async fn similar(
 #[derive(Debug, deserr::Deserr)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SimilarQueryGet {
-    #[deserr(error = DeserrQueryParamError<InvalidSimilarId>)]
-    id: Param<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarId>)]
+    id: Option<Param<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarPositiveIds>)]
+    positive_ids: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarNegativeIds>)]
+    negative_ids: Option<CS<String>>,
     #[deserr(default = Param(DEFAULT_SEARCH_OFFSET()), error = DeserrQueryParamError<InvalidSimilarOffset>)]
     offset: Param<usize>,
     #[deserr(default = Param(DEFAULT_SEARCH_LIMIT()), error = DeserrQueryParamError<InvalidSimilarLimit>)]
@@ -162,6 +164,8 @@ impl TryFrom<SimilarQueryGet> for SimilarQuery {
     fn try_from(
         SimilarQueryGet {
             id,
+            positive_ids,
+            negative_ids,
             offset,
             limit,
             attributes_to_retrieve,
@@ -181,10 +185,27 @@ impl TryFrom<SimilarQueryGet> for SimilarQuery {
             None => None,
         };
 
+        let external_ids = |ids: Option<CS<String>>, code: Code| {
+            ids.into_iter()
+                .flatten()
+                .map(|id| {
+                    id.try_into().map_err(|error: InvalidSimilarId| {
+                        ResponseError::from_msg(error.to_string(), code)
+                    })
+                })
+                .collect::<Result<Vec<ExternalDocumentId>, _>>()
+        };
+
         Ok(SimilarQuery {
-            id: id.0.try_into().map_err(|code: InvalidSimilarId| {
-                ResponseError::from_msg(code.to_string(), code.error_code())
-            })?,
+            id: id
+                .map(|id| {
+                    id.0.try_into().map_err(|code: InvalidSimilarId| {
+                        ResponseError::from_msg(code.to_string(), code.error_code())
+                    })
+                })
+                .transpose()?,
+            positive_ids: external_ids(positive_ids, Code::InvalidSimilarPositiveIds)?,
+            negative_ids: external_ids(negative_ids, Code::InvalidSimilarNegativeIds)?,
             offset: offset.0,
             limit: limit.0,
             filter,
@@ -0,0 +1,204 @@
+use std::collections::{BTreeSet, HashSet};
+use std::time::{Duration, Instant};
+
+use index_scheduler::RoFeatures;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::heed::RoTxn;
+use meilisearch_types::milli::{self, SearchCursor, TimeBudget};
+use serde_json::Value;
+
+use super::{
+    make_hits, prepare_search, AttributesFormat, MatchingStrategy, RetrieveVectors, SearchKind,
+    SearchQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
+    DEFAULT_HIGHLIGHT_PRE_TAG,
+};
+use crate::error::MeilisearchHttpError;
+
+/// The number of hits formatted at once by an export.
+const EXPORT_CHUNK_SIZE: usize = 1000;
+
+/// The time after which an export stops, releasing its read transaction.
+///
+/// The read transaction of an export keeps the pages of the version of the index it reads from
+/// alive, so the database grows with the updates made to the index until the export ends.
+const EXPORT_MAX_DURATION: Duration = Duration::from_secs(15 * 60);
+
+/// The parameters of a search export, a keyword search whose hits are all returned
+/// regardless of the `maxTotalHits` of the index.
+#[derive(Debug, Clone, Default, PartialEq, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchExportQuery {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
+    pub q: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
+    pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
+    pub sort: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
+    pub matching_strategy: MatchingStrategy,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
+    pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToRetrieve>)]
+    pub attributes_to_retrieve: Option<BTreeSet<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRetrieveVectors>)]
+    pub retrieve_vectors: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToCrop>)]
+    pub attributes_to_crop: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCropLength>, default = DEFAULT_CROP_LENGTH())]
+    pub crop_length: usize,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToHighlight>)]
+    pub attributes_to_highlight: Option<HashSet<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchesPosition>, default)]
+    pub show_matches_position: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
+    pub highlight_pre_tag: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
+    pub highlight_post_tag: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
+    pub crop_marker: String,
+}
+
+impl From<SearchExportQuery> for SearchQuery {
+    fn from(other: SearchExportQuery) -> Self {
+        let SearchExportQuery {
+            q,
+            filter,
+            sort,
+            distinct,
+            matching_strategy,
+            attributes_to_search_on,
+            attributes_to_retrieve,
+            retrieve_vectors,
+            attributes_to_crop,
+            crop_length,
+            attributes_to_highlight,
+            show_matches_position,
+            highlight_pre_tag,
+            highlight_post_tag,
+            crop_marker,
+        } = other;
+
+        SearchQuery {
+            q,
+            filter,
+            sort,
+            distinct,
+            matching_strategy,
+            attributes_to_search_on,
+            attributes_to_retrieve,
+            retrieve_vectors,
+            attributes_to_crop,
+            crop_length,
+            attributes_to_highlight,
+            show_matches_position,
+            highlight_pre_tag,
+            highlight_post_tag,
+            crop_marker,
+            ..Default::default()
+        }
+    }
+}
+
+/// Walks all the hits of a search, in ranking order, chunk by chunk.
+///
+/// Each chunk is a page of the search resumed after the cursor of the previous one, so that the
+/// hits of the export are never all ranked at once. All the chunks are read from the same read
+/// transaction, so that the export is consistent even if the index is updated in the meantime.
+pub struct SearchExport {
+    index: milli::Index,
+    rtxn: RoTxn<'static>,
+    query: SearchQuery,
+    retrieve_vectors: RetrieveVectors,
+    features: RoFeatures,
+    /// The position after the last hit of the previous chunk, `None` once all the hits were read.
+    cursor: Option<SearchCursor>,
+    started_at: Instant,
+}
+
+impl SearchExport {
+    pub fn new(
+        index: milli::Index,
+        query: SearchQuery,
+        retrieve_vectors: RetrieveVectors,
+        features: RoFeatures,
+    ) -> Result<Self, ResponseError> {
+        let started_at = Instant::now();
+        let rtxn = index.static_read_txn()?;
+
+        Ok(SearchExport {
+            index,
+            rtxn,
+            query,
+            retrieve_vectors,
+            features,
+            cursor: Some(SearchCursor::default()),
+            started_at,
+        })
+    }
+
+    /// Returns the next hits of the export as NDJSON, or `None` when there are no hits left.
+    pub fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, ResponseError> {
+        let Some(cursor) = self.cursor.take() else { return Ok(None) };
+        if self.started_at.elapsed() > EXPORT_MAX_DURATION {
+            return Err(
+                MeilisearchHttpError::SearchExportTimeout(EXPORT_MAX_DURATION.as_secs()).into()
+            );
+        }
+
+        let (mut search, _, _, _) = prepare_search(
+            &self.index,
+            &self.rtxn,
+            &self.query,
+            &SearchKind::KeywordOnly,
+            TimeBudget::max(),
+            self.features,
+        )?;
+        // the export isn't bounded by the `maxTotalHits` of the index
+        search.offset(0);
+        search.limit(EXPORT_CHUNK_SIZE);
+        search.search_after(cursor);
+
+        let milli::SearchResult { documents_ids, matching_words, document_scores, cursor, .. } =
+            search.execute()?;
+        if documents_ids.is_empty() {
+            return Ok(None);
+        }
+        // the last page isn't full
+        self.cursor = cursor.filter(|_| documents_ids.len() == EXPORT_CHUNK_SIZE);
+
+        let format = AttributesFormat {
+            attributes_to_retrieve: self.query.attributes_to_retrieve.clone(),
+            retrieve_vectors: self.retrieve_vectors,
+            attributes_to_highlight: self.query.attributes_to_highlight.clone(),
+            attributes_to_crop: self.query.attributes_to_crop.clone(),
+            crop_length: self.query.crop_length,
+            crop_marker: self.query.crop_marker.clone(),
+            highlight_pre_tag: self.query.highlight_pre_tag.clone(),
+            highlight_post_tag: self.query.highlight_post_tag.clone(),
+            show_matches_position: self.query.show_matches_position,
+            sort: self.query.sort.clone(),
+            show_ranking_score: false,
+            show_ranking_score_details: false,
+        };
+
+        let hits = make_hits(
+            &self.index,
+            &self.rtxn,
+            format,
+            matching_words,
+            documents_ids.iter().copied().zip(&document_scores),
+            &[],
+        )?;
+
+        let mut bytes = Vec::new();
+        for hit in hits {
+            serde_json::to_writer(&mut bytes, &hit).map_err(MeilisearchHttpError::from)?;
+            bytes.push(b'\n');
+        }
+        Ok(Some(bytes))
+    }
+}
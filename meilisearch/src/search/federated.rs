@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::iter::Zip;
 use std::rc::Rc;
@@ -9,25 +10,29 @@ use std::vec::{IntoIter, Vec};
 
 use actix_http::StatusCode;
 use index_scheduler::{IndexScheduler, RoFeatures};
+use indexmap::IndexMap;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::{
-    InvalidMultiSearchWeight, InvalidSearchLimit, InvalidSearchOffset,
+    InvalidMultiSearchMaxValuesPerFacet, InvalidMultiSearchMergeFacets,
+    InvalidMultiSearchMergeStrategy, InvalidMultiSearchRankConstant, InvalidMultiSearchWeight,
+    InvalidSearchLimit, InvalidSearchOffset,
 };
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoreValue};
-use meilisearch_types::milli::{self, DocumentId, TimeBudget};
+use meilisearch_types::milli::{self, DocumentId, OrderBy, TimeBudget, DEFAULT_VALUES_PER_FACET};
 use roaring::RoaringBitmap;
 use serde::Serialize;
 
 use super::ranking_rules::{self, RankingRules};
 use super::{
-    prepare_search, AttributesFormat, HitMaker, HitsInfo, RetrieveVectors, SearchHit, SearchKind,
-    SearchQuery, SearchQueryWithIndex,
+    compute_facets, prepare_search, AttributesFormat, ComputedFacets, FacetStats, HitMaker,
+    HitsInfo, RetrieveVectors, SearchHit, SearchKind, SearchQuery, SearchQueryWithIndex,
 };
 use crate::error::MeilisearchHttpError;
 use crate::routes::indexes::search::search_kind;
 
 pub const DEFAULT_FEDERATED_WEIGHT: f64 = 1.0;
+pub const DEFAULT_RRF_RANK_CONSTANT: usize = 60;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, deserr::Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -73,6 +78,33 @@ pub struct Federation {
     pub limit: usize,
     #[deserr(default = super::DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
     pub offset: usize,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchMergeFacets>)]
+    pub merge_facets: Option<MergeFacets>,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchMergeStrategy>)]
+    pub merge_strategy: MergeStrategy,
+    /// The `k` of the reciprocal rank fusion, only used with the `rrf` merge strategy.
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchRankConstant>)]
+    pub rank_constant: Option<usize>,
+}
+
+/// How the hits of the queries are merged into a single list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, deserr::Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum MergeStrategy {
+    /// Sort the hits by their weighted ranking score.
+    #[default]
+    Score,
+    /// Sort the hits by the sum of `weight / (rankConstant + rank)` over the queries that
+    /// returned them, their rank being their position in the hits of the query, starting at 1.
+    Rrf,
+}
+
+/// Merges the facets requested by the queries of all the indexes, instead of returning them by index.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, deserr::Deserr)]
+#[deserr(error = DeserrJsonError<InvalidMultiSearchMergeFacets>, rename_all = camelCase, deny_unknown_fields)]
+pub struct MergeFacets {
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchMaxValuesPerFacet>)]
+    pub max_values_per_facet: Option<usize>,
 }
 
 #[derive(Debug, deserr::Deserr)]
@@ -93,6 +125,19 @@ pub struct FederatedSearchResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
 
+    /// The facets of all the indexes, when `mergeFacets` is used.
+    ///
+    /// Like the facets of each index, they are computed on the documents matching
+    /// any of the queries of an index, not query by query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    /// The facets of each index, when `mergeFacets` isn't used, computed on the documents
+    /// matching any of the queries of the index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets_by_index: Option<BTreeMap<String, ComputedFacets>>,
+
     // These fields are only used for analytics purposes
     #[serde(skip)]
     pub degraded: bool,
@@ -107,6 +152,9 @@ impl fmt::Debug for FederatedSearchResult {
             processing_time_ms,
             hits_info,
             semantic_hit_count,
+            facet_distribution,
+            facet_stats,
+            facets_by_index,
             degraded,
             used_negative_operator,
         } = self;
@@ -125,6 +173,15 @@ impl fmt::Debug for FederatedSearchResult {
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
+        if let Some(facet_distribution) = facet_distribution {
+            debug.field("facet_distribution", &facet_distribution);
+        }
+        if let Some(facet_stats) = facet_stats {
+            debug.field("facet_stats", &facet_stats);
+        }
+        if let Some(facets_by_index) = facets_by_index {
+            debug.field("facets_by_index", &facets_by_index);
+        }
 
         debug.finish()
     }
@@ -270,16 +327,74 @@ fn merge_index_local_results(
     )
 }
 
+/// The documents of a single index, sorted by their reciprocal rank fusion score.
+struct FusedDocument<'a> {
+    /// The document as returned by the query where it has the best rank
+    item: SearchResultByQueryIterItem<'a>,
+    /// The rank of the document in the hits of each query that returned it, starting at 1
+    ranks: BTreeMap<usize, usize>,
+    rrf_score: f64,
+}
+
+fn fuse_index_local_results(
+    results_by_query: Vec<SearchResultByQuery<'_>>,
+    rank_constant: usize,
+) -> Vec<FusedDocument<'_>> {
+    let mut documents: HashMap<DocumentId, FusedDocument> = HashMap::new();
+    for result_by_query in results_by_query {
+        let weight = *result_by_query.federation_options.weight;
+        for (rank, item) in SearchResultByQueryIter::new(result_by_query).enumerate() {
+            let rank = rank + 1;
+            let rrf_score = weight / (rank_constant + rank) as f64;
+            match documents.entry(item.docid) {
+                Entry::Occupied(mut entry) => {
+                    let document = entry.get_mut();
+                    document.rrf_score += rrf_score;
+                    document.ranks.insert(item.query_index, rank);
+                    // the queries are iterated in order, so ties keep the first query
+                    if rank < document.ranks[&document.item.query_index] {
+                        document.item = item;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    let ranks = BTreeMap::from([(item.query_index, rank)]);
+                    entry.insert(FusedDocument { item, ranks, rrf_score });
+                }
+            }
+        }
+    }
+
+    let mut documents: Vec<_> = documents.into_values().collect();
+    documents.sort_by(|left, right| {
+        right
+            .rrf_score
+            .total_cmp(&left.rrf_score)
+            // break ties using query index, then document id
+            .then_with(|| left.item.query_index.cmp(&right.item.query_index))
+            .then_with(|| left.item.docid.cmp(&right.item.docid))
+    });
+    documents
+}
+
 fn merge_index_global_results(
     results_by_index: Vec<SearchResultByIndex>,
+    merge_strategy: MergeStrategy,
 ) -> impl Iterator<Item = SearchHitByIndex> {
     itertools::kmerge_by(
         results_by_index.into_iter().map(|result_by_index| result_by_index.hits.into_iter()),
-        |left: &SearchHitByIndex, right: &SearchHitByIndex| {
-            let left_score = WeightedScore::new(&left.score, *left.federation_options.weight);
-            let right_score = WeightedScore::new(&right.score, *right.federation_options.weight);
+        move |left: &SearchHitByIndex, right: &SearchHitByIndex| {
+            let order = match merge_strategy {
+                MergeStrategy::Score => {
+                    let left_score =
+                        WeightedScore::new(&left.score, *left.federation_options.weight);
+                    let right_score =
+                        WeightedScore::new(&right.score, *right.federation_options.weight);
+                    left_score.compare(&right_score)
+                }
+                MergeStrategy::Rrf => left.rrf_score.total_cmp(&right.rrf_score),
+            };
 
-            match left_score.compare(&right_score) {
+            match order {
                 // the biggest score goes first
                 Ordering::Greater => true,
                 // break ties using query index
@@ -310,15 +425,25 @@ struct SearchHitByIndex {
     score: Vec<ScoreDetails>,
     federation_options: FederationOptions,
     query_index: usize,
+    /// Only computed with the `rrf` merge strategy.
+    rrf_score: f64,
 }
 
 struct SearchResultByIndex {
+    index_uid: String,
     hits: Vec<SearchHitByIndex>,
     candidates: RoaringBitmap,
+    facets: Option<FacetsOfIndex>,
     degraded: bool,
     used_negative_operator: bool,
 }
 
+struct FacetsOfIndex {
+    facets: ComputedFacets,
+    /// How the values of each facet are sorted in the index
+    order_by: BTreeMap<String, OrderBy>,
+}
+
 pub fn perform_federated_search(
     index_scheduler: &IndexScheduler,
     queries: Vec<SearchQueryWithIndex>,
@@ -327,6 +452,14 @@ pub fn perform_federated_search(
 ) -> Result<FederatedSearchResult, ResponseError> {
     let before_search = std::time::Instant::now();
 
+    let rank_constant = match (federation.merge_strategy, federation.rank_constant) {
+        (MergeStrategy::Rrf, rank_constant) => rank_constant.unwrap_or(DEFAULT_RRF_RANK_CONSTANT),
+        (MergeStrategy::Score, None) => DEFAULT_RRF_RANK_CONSTANT,
+        (MergeStrategy::Score, Some(_)) => {
+            return Err(MeilisearchHttpError::RankConstantWithoutRrf.into())
+        }
+    };
+
     // this implementation partition the queries by index to guarantee an important property:
     // - all the queries to a particular index use the same read transaction.
     // This is an important property, otherwise we cannot guarantee the self-consistency of the results.
@@ -342,6 +475,14 @@ pub fn perform_federated_search(
             .into());
         }
 
+        if federated_query.collapse.is_some() {
+            return Err(MeilisearchHttpError::CollapseInFederatedQuery(query_index).into());
+        }
+
+        if federated_query.rerank.is_some() {
+            return Err(MeilisearchHttpError::RerankInFederatedQuery(query_index).into());
+        }
+
         let (index_uid, query, federation_options) = federated_query.into_index_query_federation();
 
         queries_by_index.entry(index_uid.into_inner()).or_default().push(QueryByIndex {
@@ -378,6 +519,16 @@ pub fn perform_federated_search(
         // Important: this is the only transaction we'll use for this index during this federated search
         let rtxn = index.read_txn()?;
 
+        // the facets are computed once for all the queries of the index, on the documents
+        // matching any of them, with the facets requested by any of them
+        let mut facets: Option<(BTreeSet<String>, usize)> = None;
+        for QueryByIndex { query, query_index, .. } in &queries {
+            if let Some(fields) = &query.facets {
+                let (facets, _) = facets.get_or_insert_with(|| (BTreeSet::new(), *query_index));
+                facets.extend(fields.iter().cloned());
+            }
+        }
+
         let criteria = index.criteria(&rtxn)?;
 
         // stuff we need for the hitmaker
@@ -440,15 +591,20 @@ pub fn perform_federated_search(
                 if let Some((previous_ranking_rules, previous_query_index, previous_index_uid)) =
                     previous_query_data.take()
                 {
-                    if let Err(error) = ranking_rules.is_compatible_with(&previous_ranking_rules) {
-                        return Err(error.to_response_error(
-                            &ranking_rules,
-                            &previous_ranking_rules,
-                            query_index,
-                            previous_query_index,
-                            &index_uid,
-                            &previous_index_uid,
-                        ));
+                    // the ranking scores are never compared when fusing the ranks of the hits
+                    if federation.merge_strategy == MergeStrategy::Score {
+                        if let Err(error) =
+                            ranking_rules.is_compatible_with(&previous_ranking_rules)
+                        {
+                            return Err(error.to_response_error(
+                                &ranking_rules,
+                                &previous_ranking_rules,
+                                query_index,
+                                previous_query_index,
+                                &index_uid,
+                                &previous_index_uid,
+                            ));
+                        }
                     }
                     previous_query_data = if previous_ranking_rules.constraint_count()
                         > ranking_rules.constraint_count()
@@ -501,6 +657,10 @@ pub fn perform_federated_search(
                     candidates: query_candidates,
                     documents_ids,
                     document_scores,
+                    collapsed_groups: _,
+                    applied_rules: _,
+                    pinned_documents: _,
+                    cursor: _,
                     degraded: query_degraded,
                     used_negative_operator: query_used_negative_operator,
                 } = result;
@@ -534,43 +694,102 @@ pub fn perform_federated_search(
                 return Err(error);
             }
         }
+
+        // 2.3 make hits, see 2.2
+        let make_hit = |SearchResultByQueryIterItem {
+                            docid,
+                            score,
+                            federation_options,
+                            hit_maker,
+                            query_index,
+                        },
+                        fused: Option<(BTreeMap<usize, usize>, f64)>|
+         -> Result<SearchHitByIndex, ResponseError> {
+            let mut hit = hit_maker.make_hit(docid, &score)?;
+            let weighted_score =
+                ScoreDetails::global_score(score.iter()) * (*federation_options.weight);
+
+            let mut _federation = serde_json::json!(
+                {
+                    "indexUid": index_uid,
+                    "queriesPosition": query_index,
+                    "weightedRankingScore": weighted_score,
+                }
+            );
+            let rrf_score = match fused {
+                Some((ranks, rrf_score)) => {
+                    _federation["queriesRanks"] = serde_json::json!(ranks);
+                    _federation["rrfScore"] = serde_json::json!(rrf_score);
+                    rrf_score
+                }
+                None => 0.0,
+            };
+            hit.document.insert("_federation".to_string(), _federation);
+            Ok(SearchHitByIndex { hit, score, federation_options, query_index, rrf_score })
+        };
+
         // 2.2. merge inside index
-        let mut documents_seen = RoaringBitmap::new();
-        let merged_result: Result<Vec<_>, ResponseError> =
-            merge_index_local_results(results_by_query)
-                // skip documents we've already seen & mark that we saw the current document
-                .filter(|SearchResultByQueryIterItem { docid, .. }| documents_seen.insert(*docid))
+        let merged_result: Result<Vec<_>, ResponseError> = match federation.merge_strategy {
+            MergeStrategy::Score => {
+                let mut documents_seen = RoaringBitmap::new();
+                merge_index_local_results(results_by_query)
+                    // skip documents we've already seen & mark that we saw the current document
+                    .filter(|SearchResultByQueryIterItem { docid, .. }| {
+                        documents_seen.insert(*docid)
+                    })
+                    .take(required_hit_count)
+                    .map(|item| make_hit(item, None))
+                    .collect()
+            }
+            MergeStrategy::Rrf => fuse_index_local_results(results_by_query, rank_constant)
+                .into_iter()
                 .take(required_hit_count)
-                // 2.3 make hits
-                .map(
-                    |SearchResultByQueryIterItem {
-                         docid,
-                         score,
-                         federation_options,
-                         hit_maker,
-                         query_index,
-                     }| {
-                        let mut hit = hit_maker.make_hit(docid, &score)?;
-                        let weighted_score =
-                            ScoreDetails::global_score(score.iter()) * (*federation_options.weight);
-
-                        let _federation = serde_json::json!(
-                            {
-                                "indexUid": index_uid,
-                                "queriesPosition": query_index,
-                                "weightedRankingScore": weighted_score,
-                            }
-                        );
-                        hit.document.insert("_federation".to_string(), _federation);
-                        Ok(SearchHitByIndex { hit, score, federation_options, query_index })
-                    },
-                )
-                .collect();
+                .map(|FusedDocument { item, ranks, rrf_score }| {
+                    make_hit(item, Some((ranks, rrf_score)))
+                })
+                .collect(),
+        };
 
         let merged_result = merged_result?;
+
+        // 2.4 compute the facets on the candidates of all the queries
+        let facets = match facets {
+            Some((fields, query_index)) => {
+                let fields: Vec<_> = fields.into_iter().collect();
+                // the values of a facet can only be counted and sorted across the indexes
+                // when none of them is missing, the merged facets are truncated afterward
+                let max_values_per_facet = federation.merge_facets.map(|_| usize::MAX);
+                let facets = match compute_facets(
+                    &index,
+                    &rtxn,
+                    candidates.clone(),
+                    &fields,
+                    max_values_per_facet,
+                ) {
+                    Ok(facets) => facets,
+                    Err(mut error) => {
+                        error.message =
+                            format!("Inside `.queries[{query_index}]`: {}", error.message);
+                        return Err(error);
+                    }
+                };
+                let sort_facet_values_by =
+                    index.sort_facet_values_by(&rtxn).map_err(milli::Error::from)?;
+                let order_by = facets
+                    .distribution
+                    .keys()
+                    .map(|facet| (facet.clone(), sort_facet_values_by.get(facet)))
+                    .collect();
+                Some(FacetsOfIndex { facets, order_by })
+            }
+            None => None,
+        };
+
         results_by_index.push(SearchResultByIndex {
+            index_uid,
             hits: merged_result,
             candidates,
+            facets,
             degraded,
             used_negative_operator,
         });
@@ -584,8 +803,10 @@ pub fn perform_federated_search(
         let mut used_negative_operator = false;
 
         for SearchResultByIndex {
+            index_uid: _,
             hits: _,
             candidates,
+            facets: _,
             degraded: degraded_by_index,
             used_negative_operator: used_negative_operator_by_index,
         } in &results_by_index
@@ -598,19 +819,51 @@ pub fn perform_federated_search(
         (estimated_total_hits, degraded, used_negative_operator)
     };
 
-    // 3.2 merge hits
-    let merged_hits: Vec<_> = merge_index_global_results(results_by_index)
-        .skip(federation.offset)
-        .take(federation.limit)
-        .inspect(|hit| {
-            if let Some(semantic_hit_count) = &mut semantic_hit_count {
-                if hit.score.iter().any(|score| matches!(&score, ScoreDetails::Vector(_))) {
-                    *semantic_hit_count += 1;
-                }
+    // 3.2 merge facets
+    let facets_by_index: Option<BTreeMap<_, _>> = {
+        let mut facets_by_index = None;
+        for result_by_index in &mut results_by_index {
+            if let Some(facets) = result_by_index.facets.take() {
+                facets_by_index
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert(result_by_index.index_uid.clone(), facets);
             }
-        })
-        .map(|hit| hit.hit)
-        .collect();
+        }
+        facets_by_index
+    };
+    let (facet_distribution, facet_stats, facets_by_index) =
+        match (federation.merge_facets, facets_by_index) {
+            (Some(merge_facets), Some(facets_by_index)) => {
+                let max_values_per_facet =
+                    merge_facets.max_values_per_facet.unwrap_or(DEFAULT_VALUES_PER_FACET);
+                let ComputedFacets { distribution, stats } =
+                    merge_facets_of_indexes(facets_by_index.into_values(), max_values_per_facet);
+                (Some(distribution), Some(stats), None)
+            }
+            (None, Some(facets_by_index)) => {
+                let facets_by_index = facets_by_index
+                    .into_iter()
+                    .map(|(index_uid, FacetsOfIndex { facets, order_by: _ })| (index_uid, facets))
+                    .collect();
+                (None, None, Some(facets_by_index))
+            }
+            (_, None) => (None, None, None),
+        };
+
+    // 3.3 merge hits
+    let merged_hits: Vec<_> =
+        merge_index_global_results(results_by_index, federation.merge_strategy)
+            .skip(federation.offset)
+            .take(federation.limit)
+            .inspect(|hit| {
+                if let Some(semantic_hit_count) = &mut semantic_hit_count {
+                    if hit.score.iter().any(|score| matches!(&score, ScoreDetails::Vector(_))) {
+                        *semantic_hit_count += 1;
+                    }
+                }
+            })
+            .map(|hit| hit.hit)
+            .collect();
 
     let search_result = FederatedSearchResult {
         hits: merged_hits,
@@ -621,9 +874,73 @@ pub fn perform_federated_search(
             estimated_total_hits,
         },
         semantic_hit_count,
+        facet_distribution,
+        facet_stats,
+        facets_by_index,
         degraded,
         used_negative_operator,
     };
 
     Ok(search_result)
 }
+
+/// Sums the number of documents of each facet value and keeps the extremums of the facet stats.
+///
+/// The values of a facet are sorted by count if all the indexes sort them this way,
+/// and in lexicographic order otherwise.
+fn merge_facets_of_indexes(
+    facets_by_index: impl Iterator<Item = FacetsOfIndex>,
+    max_values_per_facet: usize,
+) -> ComputedFacets {
+    let mut distribution: BTreeMap<String, IndexMap<String, u64>> = BTreeMap::new();
+    let mut stats: BTreeMap<String, FacetStats> = BTreeMap::new();
+    let mut order_by: BTreeMap<String, OrderBy> = BTreeMap::new();
+
+    for FacetsOfIndex { facets, order_by: order_by_of_index } in facets_by_index {
+        for (facet, values) in facets.distribution {
+            let merged_values = distribution.entry(facet).or_default();
+            for (value, count) in values {
+                *merged_values.entry(value).or_default() += count;
+            }
+        }
+        for (facet, FacetStats { min, max }) in facets.stats {
+            stats
+                .entry(facet)
+                .and_modify(|stats| {
+                    stats.min = stats.min.min(min);
+                    stats.max = stats.max.max(max);
+                })
+                .or_insert(FacetStats { min, max });
+        }
+        for (facet, order_by_of_index) in order_by_of_index {
+            let order_by = order_by.entry(facet).or_insert(order_by_of_index);
+            if *order_by != order_by_of_index {
+                *order_by = OrderBy::Lexicographic;
+            }
+        }
+    }
+
+    for (facet, values) in &mut distribution {
+        match order_by.get(facet).copied().unwrap_or_default() {
+            OrderBy::Lexicographic => {
+                values.sort_by(|left, _, right, _| compare_facet_values(left, right))
+            }
+            OrderBy::Count => values.sort_by(|left, left_count, right, right_count| {
+                right_count.cmp(left_count).then_with(|| compare_facet_values(left, right))
+            }),
+        }
+        values.truncate(max_values_per_facet);
+    }
+
+    ComputedFacets { distribution, stats }
+}
+
+/// Sorts the numbers before the strings, like the facet distribution of an index.
+fn compare_facet_values(left: &str, right: &str) -> Ordering {
+    match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.total_cmp(&right),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => left.cmp(right),
+    }
+}
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -17,23 +17,32 @@ use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
 use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
+use meilisearch_types::milli::vector::sparse::SparseVector;
 use meilisearch_types::milli::vector::Embedder;
 use meilisearch_types::milli::{FacetValueHit, OrderBy, SearchForFacetValues, TimeBudget};
 use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
 use meilisearch_types::{milli, Document};
+use milli::query_rules::BoostClause;
 use milli::tokenizer::TokenizerBuilder;
 use milli::{
     AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, MatchBounds, MatcherBuilder,
-    SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    Reranker, SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
 use regex::Regex;
+use roaring::RoaringBitmap;
 use serde::Serialize;
 use serde_json::{json, Value};
 
 use crate::error::MeilisearchHttpError;
 
+mod export;
+pub use export::{SearchExport, SearchExportQuery};
+
 mod federated;
-pub use federated::{perform_federated_search, FederatedSearch, Federation, FederationOptions};
+pub use federated::{
+    perform_federated_search, FederatedSearch, Federation, FederationOptions, MergeFacets,
+    MergeStrategy, DEFAULT_RRF_RANK_CONSTANT,
+};
 
 mod ranking_rules;
 
@@ -46,6 +55,11 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_RERANK_TOP_K: fn() -> usize = || 20;
+/// Maximum number of documents returned by a collapsed search, counting the inner hits.
+///
+/// The inner hits of each group are ranked by a search of their own.
+pub const MAX_COLLAPSED_HITS: usize = 1000;
 
 #[derive(Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -54,6 +68,8 @@ pub struct SearchQuery {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSparseVector>)]
+    pub sparse_vector: Option<SparseVector>,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -86,6 +102,16 @@ pub struct SearchQuery {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCollapse>)]
+    pub collapse: Option<CollapseQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    pub rerank: Option<RerankQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoost>)]
+    pub boost: Option<Vec<BoostClause>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoostPosition>)]
+    pub boost_position: Option<usize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
@@ -146,6 +172,7 @@ impl fmt::Debug for SearchQuery {
         let Self {
             q,
             vector,
+            sparse_vector,
             hybrid,
             offset,
             limit,
@@ -162,6 +189,11 @@ impl fmt::Debug for SearchQuery {
             filter,
             sort,
             distinct,
+            collapse,
+            rerank,
+            boost,
+            boost_position,
+            search_after,
             facets,
             highlight_pre_tag,
             highlight_post_tag,
@@ -199,6 +231,9 @@ impl fmt::Debug for SearchQuery {
                 );
             }
         }
+        if let Some(sparse_vector) = sparse_vector {
+            debug.field("sparse_vector", &sparse_vector);
+        }
         if let Some(hybrid) = hybrid {
             debug.field("hybrid", &hybrid);
         }
@@ -214,6 +249,21 @@ impl fmt::Debug for SearchQuery {
         if let Some(distinct) = distinct {
             debug.field("distinct", &distinct);
         }
+        if let Some(collapse) = collapse {
+            debug.field("collapse", &collapse);
+        }
+        if let Some(rerank) = rerank {
+            debug.field("rerank", &rerank);
+        }
+        if let Some(boost) = boost {
+            debug.field("boost", &boost);
+        }
+        if let Some(boost_position) = boost_position {
+            debug.field("boost_position", &boost_position);
+        }
+        if let Some(search_after) = search_after {
+            debug.field("search_after", &search_after);
+        }
         if let Some(facets) = facets {
             debug.field("facets", &facets);
         }
@@ -254,6 +304,28 @@ impl fmt::Debug for SearchQuery {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchCollapse>, rename_all = camelCase, deny_unknown_fields)]
+pub struct CollapseQuery {
+    /// The filterable attribute on which to group the hits.
+    #[deserr(error = DeserrJsonError<InvalidSearchCollapse>)]
+    pub attribute: String,
+    /// The number of additional hits to return inside each group.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCollapse>)]
+    pub inner_hits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchRerank>, rename_all = camelCase, deny_unknown_fields)]
+pub struct RerankQuery {
+    /// The reranker to use, optional when the index has a single reranker.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    pub reranker: Option<String>,
+    /// The number of hits, from the top of the results, to rerank.
+    #[deserr(default = DEFAULT_RERANK_TOP_K(), error = DeserrJsonError<InvalidSearchRerank>)]
+    pub top_k: usize,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError<InvalidHybridQuery>, rename_all = camelCase, deny_unknown_fields)]
 pub struct HybridQuery {
@@ -261,6 +333,23 @@ pub struct HybridQuery {
     pub semantic_ratio: SemanticRatio,
     #[deserr(default, error = DeserrJsonError<InvalidEmbedder>, default)]
     pub embedder: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFusion>)]
+    pub fusion: HybridFusion,
+    /// The `k` of the reciprocal rank fusion, only used with the `rrf` fusion.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankConstant>)]
+    pub rank_constant: Option<usize>,
+}
+
+/// How the keyword and semantic results of a hybrid search are merged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum HybridFusion {
+    /// Sort the hits by their ranking score, weighted by the semantic ratio.
+    #[default]
+    Score,
+    /// Sort the hits by the sum of `weight / (rankConstant + rank)` over the keyword and semantic
+    /// results, the weight of the semantic results being the semantic ratio.
+    Rrf,
 }
 
 #[derive(Clone)]
@@ -313,6 +402,12 @@ impl SearchKind {
             .0;
 
         if let Some(vector_len) = vector_len {
+            if embedder.is_sparse() {
+                return Err(ResponseError::from_msg(
+                    format!("`vector` cannot be used with the sparse embedder `{embedder_name}`.\n - Hint: Use `sparseVector` instead."),
+                    Code::InvalidSearchVector,
+                ));
+            }
             if vector_len != embedder.dimensions() {
                 return Err(meilisearch_types::milli::Error::UserError(
                     meilisearch_types::milli::UserError::InvalidVectorDimensions {
@@ -379,6 +474,8 @@ pub struct SearchQueryWithIndex {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSparseVector>)]
+    pub sparse_vector: Option<SparseVector>,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchOffset>)]
@@ -411,6 +508,16 @@ pub struct SearchQueryWithIndex {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCollapse>)]
+    pub collapse: Option<CollapseQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    pub rerank: Option<RerankQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoost>)]
+    pub boost: Option<Vec<BoostClause>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchBoostPosition>)]
+    pub boost_position: Option<usize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
@@ -443,6 +550,8 @@ impl SearchQueryWithIndex {
             Some("page")
         } else if self.hits_per_page.is_some() {
             Some("hitsPerPage")
+        } else if self.search_after.is_some() {
+            Some("searchAfter")
         } else {
             None
         }
@@ -454,6 +563,7 @@ impl SearchQueryWithIndex {
             federation_options,
             q,
             vector,
+            sparse_vector,
             offset,
             limit,
             page,
@@ -469,6 +579,11 @@ impl SearchQueryWithIndex {
             filter,
             sort,
             distinct,
+            collapse,
+            rerank,
+            boost,
+            boost_position,
+            search_after,
             facets,
             highlight_pre_tag,
             highlight_post_tag,
@@ -483,6 +598,7 @@ impl SearchQueryWithIndex {
             SearchQuery {
                 q,
                 vector,
+                sparse_vector,
                 offset: offset.unwrap_or(DEFAULT_SEARCH_OFFSET()),
                 limit: limit.unwrap_or(DEFAULT_SEARCH_LIMIT()),
                 page,
@@ -498,6 +614,11 @@ impl SearchQueryWithIndex {
                 filter,
                 sort,
                 distinct,
+                collapse,
+                rerank,
+                boost,
+                boost_position,
+                search_after,
                 facets,
                 highlight_pre_tag,
                 highlight_post_tag,
@@ -517,8 +638,12 @@ impl SearchQueryWithIndex {
 #[derive(Debug, Clone, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SimilarQuery {
-    #[deserr(error = DeserrJsonError<InvalidSimilarId>)]
-    pub id: ExternalDocumentId,
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarId>)]
+    pub id: Option<ExternalDocumentId>,
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarPositiveIds>)]
+    pub positive_ids: Vec<ExternalDocumentId>,
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarNegativeIds>)]
+    pub negative_ids: Vec<ExternalDocumentId>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSimilarOffset>)]
     pub offset: usize,
     #[deserr(default = DEFAULT_SEARCH_LIMIT(), error = DeserrJsonError<InvalidSimilarLimit>)]
@@ -630,6 +755,15 @@ pub struct SearchHit {
     pub ranking_score: Option<f64>,
     #[serde(rename = "_rankingScoreDetails", skip_serializing_if = "Option::is_none")]
     pub ranking_score_details: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "_collapse", skip_serializing_if = "Option::is_none")]
+    pub collapse: Option<CollapsedHits>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollapsedHits {
+    pub inner_hits: Vec<SearchHit>,
+    pub total_hits: u64,
 }
 
 #[derive(Serialize, Clone, PartialEq)]
@@ -647,6 +781,13 @@ pub struct SearchResult {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
+    /// The ids of the query rules of the index that were applied to the search.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub applied_rules: Vec<String>,
+    /// The cursor to pass as `searchAfter` to get the next hits, `null` once all the hits were
+    /// returned. Only present when the search was made with `searchAfter`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Option<String>>,
 
     // These fields are only used for analytics purposes
     #[serde(skip)]
@@ -665,6 +806,8 @@ impl fmt::Debug for SearchResult {
             facet_distribution,
             facet_stats,
             semantic_hit_count,
+            applied_rules,
+            next_cursor,
             degraded,
             used_negative_operator,
         } = self;
@@ -690,6 +833,12 @@ impl fmt::Debug for SearchResult {
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
+        if !applied_rules.is_empty() {
+            debug.field("applied_rules", &applied_rules);
+        }
+        if let Some(next_cursor) = next_cursor {
+            debug.field("next_cursor", &next_cursor);
+        }
 
         debug.finish()
     }
@@ -699,7 +848,8 @@ impl fmt::Debug for SearchResult {
 #[serde(rename_all = "camelCase")]
 pub struct SimilarResult {
     pub hits: Vec<SearchHit>,
-    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub processing_time_ms: u128,
     #[serde(flatten)]
     pub hits_info: HitsInfo,
@@ -728,6 +878,13 @@ pub struct FacetStats {
     pub max: f64,
 }
 
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputedFacets {
+    pub distribution: BTreeMap<String, IndexMap<String, u64>>,
+    pub stats: BTreeMap<String, FacetStats>,
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FacetSearchResult {
@@ -774,34 +931,103 @@ fn prepare_search<'t>(
         search.distinct(distinct.clone());
     }
 
+    if let Some(collapse) = &query.collapse {
+        if query.distinct.is_some() {
+            return Err(ResponseError::from_msg(
+                "`distinct` and `collapse` cannot be used together in the same search.".to_string(),
+                Code::InvalidSearchCollapse,
+            ));
+        }
+        search.collapse(collapse.attribute.clone(), collapse.inner_hits);
+    }
+
+    if let Some(boost) = &query.boost {
+        let position = query.boost_position.unwrap_or_default();
+        let ranking_rules = index.criteria(rtxn)?.len();
+        if position > ranking_rules {
+            return Err(ResponseError::from_msg(
+                format!(
+                    "Invalid value for `boostPosition`: {position} is greater than the number of ranking rules of the index ({ranking_rules})."
+                ),
+                Code::InvalidSearchBoostPosition,
+            ));
+        }
+        search.boost(boost.clone(), position);
+    }
+
+    if let Some(search_after) = &query.search_after {
+        if query.offset != 0 || query.is_finite_pagination() {
+            return Err(ResponseError::from_msg(
+                "`searchAfter` cannot be used together with `offset`, `page` or `hitsPerPage`."
+                    .to_string(),
+                Code::InvalidSearchSearchAfter,
+            ));
+        }
+        if !matches!(search_kind, SearchKind::KeywordOnly) {
+            return Err(ResponseError::from_msg(
+                "`searchAfter` cannot be used with a vector or hybrid search.".to_string(),
+                Code::InvalidSearchSearchAfter,
+            ));
+        }
+        // an empty cursor starts the pagination from the first hit
+        search.search_after(search_after.parse().map_err(milli::Error::from)?);
+    }
+
     match search_kind {
         SearchKind::KeywordOnly => {
             if let Some(q) = &query.q {
                 search.query(q);
             }
         }
-        SearchKind::SemanticOnly { embedder_name, embedder } => {
-            let vector = match query.vector.clone() {
-                Some(vector) => vector,
-                None => {
-                    let span = tracing::trace_span!(target: "search::vector", "embed_one");
-                    let _entered = span.enter();
-
-                    embedder
-                        .embed_one(query.q.clone().unwrap())
-                        .map_err(milli::vector::Error::from)
-                        .map_err(milli::Error::from)?
-                }
-            };
-
-            search.semantic(embedder_name.clone(), embedder.clone(), Some(vector));
+        SearchKind::SemanticOnly { embedder_name, embedder }
+        | SearchKind::Hybrid { embedder_name, embedder, .. }
+            if query.sparse_vector.is_some() && !embedder.is_sparse() =>
+        {
+            return Err(ResponseError::from_msg(
+                format!("`sparseVector` cannot be used with the embedder `{embedder_name}`, which is not sparse."),
+                Code::InvalidSearchSparseVector,
+            ));
         }
+        SearchKind::SemanticOnly { embedder_name, embedder } => match query.sparse_vector.clone() {
+            Some(sparse_vector) => {
+                search.sparse_semantic(embedder_name.clone(), embedder.clone(), sparse_vector);
+            }
+            None => {
+                let vector = match query.vector.clone() {
+                    Some(vector) => vector,
+                    None => {
+                        let span = tracing::trace_span!(target: "search::vector", "embed_one");
+                        let _entered = span.enter();
+
+                        embedder
+                            .embed_search(query.q.clone().unwrap())
+                            .map_err(milli::vector::Error::from)
+                            .map_err(milli::Error::from)?
+                    }
+                };
+
+                search.semantic(embedder_name.clone(), embedder.clone(), Some(vector));
+            }
+        },
         SearchKind::Hybrid { embedder_name, embedder, semantic_ratio: _ } => {
             if let Some(q) = &query.q {
                 search.query(q);
             }
-            // will be embedded in hybrid search if necessary
-            search.semantic(embedder_name.clone(), embedder.clone(), query.vector.clone());
+            if let Some(HybridQuery { fusion: HybridFusion::Rrf, rank_constant, .. }) =
+                &query.hybrid
+            {
+                let rank_constant = rank_constant.unwrap_or(DEFAULT_RRF_RANK_CONSTANT);
+                search.hybrid_fusion(milli::HybridFusion::Rrf { rank_constant });
+            }
+            match query.sparse_vector.clone() {
+                Some(sparse_vector) => {
+                    search.sparse_semantic(embedder_name.clone(), embedder.clone(), sparse_vector)
+                }
+                // will be embedded in hybrid search if necessary
+                None => {
+                    search.semantic(embedder_name.clone(), embedder.clone(), query.vector.clone())
+                }
+            };
         }
     }
 
@@ -830,25 +1056,31 @@ fn prepare_search<'t>(
         },
     );
 
-    // compute the offset on the limit depending on the pagination mode.
-    let (offset, limit) = if is_finite_pagination {
-        let limit = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
-        let page = query.page.unwrap_or(1);
-
-        // page 0 gives a limit of 0 forcing Meilisearch to return no document.
-        page.checked_sub(1).map_or((0, 0), |p| (limit * p, limit))
-    } else {
-        (query.offset, query.limit)
-    };
-
-    // Make sure that a user can't get more documents than the hard limit,
-    // we align that on the offset too.
-    let offset = min(offset, max_total_hits);
-    let limit = min(limit, max_total_hits.saturating_sub(offset));
-
+    let (offset, limit) = pagination_window(query, max_total_hits);
     search.offset(offset);
     search.limit(limit);
 
+    if let Some(collapse) = &query.collapse {
+        // all the reranked hits are collapsed, not only the requested page
+        let (limit, hint) = match &query.rerank {
+            Some(rerank) => (
+                rerank_limit(rerank.top_k, offset, limit, max_total_hits),
+                "`limit`, `rerank.topK` or `collapse.innerHits`",
+            ),
+            None => (limit, "`limit` or `collapse.innerHits`"),
+        };
+        let collapsed_hits = limit.saturating_mul(collapse.inner_hits.saturating_add(1));
+        if collapsed_hits > MAX_COLLAPSED_HITS {
+            return Err(ResponseError::from_msg(
+                format!(
+                    "Collapsing {limit} hits with {} inner hits each returns {collapsed_hits} documents, the maximum is {MAX_COLLAPSED_HITS}.\n - Hint: reduce {hint}.",
+                    collapse.inner_hits
+                ),
+                Code::InvalidSearchCollapse,
+            ));
+        }
+    }
+
     if let Some(ref filter) = query.filter {
         if let Some(facets) = parse_filter(filter, Code::InvalidSearchFilter, features)? {
             search.filter(facets);
@@ -869,6 +1101,135 @@ fn prepare_search<'t>(
     Ok((search, is_finite_pagination, max_total_hits, offset))
 }
 
+/// Computes the offset and the limit of the requested hits, depending on the pagination mode.
+fn pagination_window(query: &SearchQuery, max_total_hits: usize) -> (usize, usize) {
+    let (offset, limit) = if query.is_finite_pagination() {
+        let limit = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
+        let page = query.page.unwrap_or(1);
+
+        // page 0 gives a limit of 0 forcing Meilisearch to return no document.
+        page.checked_sub(1).map_or((0, 0), |p| (limit * p, limit))
+    } else {
+        (query.offset, query.limit)
+    };
+
+    // Make sure that a user can't get more documents than the hard limit,
+    // we align that on the offset too.
+    let offset = min(offset, max_total_hits);
+    let limit = min(limit, max_total_hits.saturating_sub(offset));
+    (offset, limit)
+}
+
+/// Returns the reranker of the query along with the number of hits to rerank.
+fn prepare_rerank(
+    index: &Index,
+    rtxn: &RoTxn,
+    query: &SearchQuery,
+) -> Result<Option<(Reranker, usize)>, ResponseError> {
+    let Some(RerankQuery { reranker, top_k }) = &query.rerank else { return Ok(None) };
+
+    if query.q.is_none() {
+        return Err(ResponseError::from_msg(
+            "`rerank` requires a `q` to score the hits against.".to_string(),
+            Code::InvalidSearchRerank,
+        ));
+    }
+    if query.search_after.is_some() {
+        return Err(ResponseError::from_msg(
+            "`rerank` cannot be used together with `searchAfter`.".to_string(),
+            Code::InvalidSearchRerank,
+        ));
+    }
+    if *top_k == 0 {
+        return Err(ResponseError::from_msg(
+            "`rerank.topK` must be greater than 0.".to_string(),
+            Code::InvalidSearchRerank,
+        ));
+    }
+
+    let mut rerankers = index.rerankers(rtxn)?;
+    let name = match reranker {
+        Some(name) => name.clone(),
+        None if rerankers.len() == 1 => rerankers.keys().next().unwrap().clone(),
+        None => {
+            return Err(ResponseError::from_msg(
+                format!(
+                    "`rerank.reranker` is required when the index has {} rerankers.",
+                    rerankers.len()
+                ),
+                Code::InvalidSearchRerank,
+            ))
+        }
+    };
+    let settings = rerankers
+        .remove(&name)
+        .ok_or_else(|| milli::Error::from(milli::UserError::InvalidRerankerName(name.clone())))?;
+
+    Ok(Some((Reranker::new(name, settings)?, *top_k)))
+}
+
+/// Returns the number of hits to retrieve for a reranked search, from the first one.
+fn rerank_limit(top_k: usize, offset: usize, limit: usize, max_total_hits: usize) -> usize {
+    min(max(top_k, offset + limit), max_total_hits)
+}
+
+/// Reorders the `top_k` first hits by the scores of the reranker, keeping the retrieval order on ties.
+///
+/// The documents pinned by the query rules keep their positions, the other hits are reordered
+/// among the remaining positions.
+fn rerank_hits(
+    index: &Index,
+    rtxn: &RoTxn,
+    reranker: &Reranker,
+    top_k: usize,
+    q: &str,
+    result: &mut milli::SearchResult,
+) -> Result<(), ResponseError> {
+    let top_k = min(top_k, result.documents_ids.len());
+    let positions: Vec<usize> = (0..top_k)
+        .filter(|&i| !result.pinned_documents.contains(result.documents_ids[i]))
+        .collect();
+    let docids: Vec<_> = positions.iter().map(|&i| result.documents_ids[i]).collect();
+    let scores = reranker.rerank(index, rtxn, q, &docids)?;
+
+    let mut order: Vec<usize> = (0..positions.len()).collect();
+    order.sort_by(|&left, &right| scores[right].total_cmp(&scores[left]));
+
+    let document_scores: Vec<_> = order
+        .iter()
+        .map(|&rank| {
+            let i = positions[rank];
+            let mut details = std::mem::take(&mut result.document_scores[i]);
+            details.insert(
+                0,
+                ScoreDetails::Rerank(milli::score_details::Rerank {
+                    retrieval_rank: i as u32 + 1,
+                    score: scores[rank],
+                }),
+            );
+            details
+        })
+        .collect();
+    let collapsed_groups: Vec<_> = if result.collapsed_groups.is_empty() {
+        Vec::new()
+    } else {
+        order
+            .iter()
+            .map(|&rank| std::mem::take(&mut result.collapsed_groups[positions[rank]]))
+            .collect()
+    };
+
+    for ((&position, &rank), details) in positions.iter().zip(&order).zip(document_scores) {
+        result.documents_ids[position] = docids[rank];
+        result.document_scores[position] = details;
+    }
+    for (&position, group) in positions.iter().zip(collapsed_groups) {
+        result.collapsed_groups[position] = group;
+    }
+
+    Ok(())
+}
+
 pub fn perform_search(
     index: &Index,
     query: SearchQuery,
@@ -883,20 +1244,42 @@ pub fn perform_search(
         None => TimeBudget::default(),
     };
 
-    let (search, is_finite_pagination, max_total_hits, offset) =
+    let reranker = prepare_rerank(index, &rtxn, &query)?;
+    let (mut search, is_finite_pagination, max_total_hits, offset) =
         prepare_search(index, &rtxn, &query, &search_kind, time_budget, features)?;
 
-    let (
-        milli::SearchResult {
-            documents_ids,
-            matching_words,
-            candidates,
-            document_scores,
-            degraded,
-            used_negative_operator,
-        },
-        semantic_hit_count,
-    ) = search_from_kind(search_kind, search)?;
+    let (_, limit) = pagination_window(&query, max_total_hits);
+    if let Some((_, top_k)) = &reranker {
+        // retrieve the hits from the first one, the requested page is cut after reranking
+        search.offset(0);
+        search.limit(rerank_limit(*top_k, offset, limit, max_total_hits));
+    }
+
+    let (mut result, semantic_hit_count) = search_from_kind(search_kind, search)?;
+
+    if let Some((reranker, top_k)) = reranker {
+        let q = query.q.as_deref().unwrap_or_default();
+        rerank_hits(index, &rtxn, &reranker, top_k, q, &mut result)?;
+
+        result.documents_ids = result.documents_ids.into_iter().skip(offset).take(limit).collect();
+        result.document_scores =
+            result.document_scores.into_iter().skip(offset).take(limit).collect();
+        result.collapsed_groups =
+            result.collapsed_groups.into_iter().skip(offset).take(limit).collect();
+    }
+
+    let milli::SearchResult {
+        documents_ids,
+        matching_words,
+        candidates,
+        document_scores,
+        collapsed_groups,
+        applied_rules,
+        pinned_documents: _,
+        cursor,
+        degraded,
+        used_negative_operator,
+    } = result;
 
     let SearchQuery {
         q,
@@ -919,6 +1302,7 @@ pub fn perform_search(
         crop_marker,
         // already used in prepare_search
         vector: _,
+        sparse_vector: _,
         hybrid: _,
         offset: _,
         ranking_score_threshold: _,
@@ -926,6 +1310,11 @@ pub fn perform_search(
         attributes_to_search_on: _,
         filter: _,
         distinct: _,
+        collapse: _,
+        rerank: _,
+        boost: _,
+        boost_position: _,
+        search_after,
     } = query;
 
     let format = AttributesFormat {
@@ -949,8 +1338,15 @@ pub fn perform_search(
         format,
         matching_words,
         documents_ids.iter().copied().zip(document_scores.iter()),
+        &collapsed_groups,
     )?;
 
+    // all the hits were returned when the page isn't full
+    let next_cursor = search_after.map(|_| {
+        let limit = min(limit, max_total_hits);
+        cursor.filter(|_| documents.len() == limit && limit != 0).map(|cursor| cursor.to_string())
+    });
+
     let number_of_hits = min(candidates.len() as usize, max_total_hits);
     let hits_info = if is_finite_pagination {
         let hits_per_page = hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
@@ -971,38 +1367,13 @@ pub fn perform_search(
 
     let (facet_distribution, facet_stats) = match facets {
         Some(ref fields) => {
-            let mut facet_distribution = index.facets_distribution(&rtxn);
-
-            let max_values_by_facet = index
-                .max_values_per_facet(&rtxn)
-                .map_err(milli::Error::from)?
-                .map(|x| x as usize)
-                .unwrap_or(DEFAULT_VALUES_PER_FACET);
-            facet_distribution.max_values_per_facet(max_values_by_facet);
-
-            let sort_facet_values_by =
-                index.sort_facet_values_by(&rtxn).map_err(milli::Error::from)?;
-
-            if fields.iter().all(|f| f != "*") {
-                let fields: Vec<_> =
-                    fields.iter().map(|n| (n, sort_facet_values_by.get(n))).collect();
-                facet_distribution.facets(fields);
-            }
-
-            let distribution = facet_distribution
-                .candidates(candidates)
-                .default_order_by(sort_facet_values_by.get("*"))
-                .execute()?;
-            let stats = facet_distribution.compute_stats()?;
+            let ComputedFacets { distribution, stats } =
+                compute_facets(index, &rtxn, candidates, fields, None)?;
             (Some(distribution), Some(stats))
         }
         None => (None, None),
     };
 
-    let facet_stats = facet_stats.map(|stats| {
-        stats.into_iter().map(|(k, (min, max))| (k, FacetStats { min, max })).collect()
-    });
-
     let result = SearchResult {
         hits: documents,
         hits_info,
@@ -1013,10 +1384,89 @@ pub fn perform_search(
         degraded,
         used_negative_operator,
         semantic_hit_count,
+        applied_rules,
+        next_cursor,
     };
     Ok(result)
 }
 
+/// Computes the distribution and the stats of the given facets among the candidates.
+/// Computes the facet distribution and stats of the candidates.
+///
+/// The number of values of each facet is bounded by `max_values_per_facet`,
+/// or by the `maxValuesPerFacet` of the index when it is `None`.
+fn compute_facets(
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: RoaringBitmap,
+    fields: &[String],
+    max_values_per_facet: Option<usize>,
+) -> Result<ComputedFacets, ResponseError> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+
+    let max_values_by_facet = match max_values_per_facet {
+        Some(max_values_per_facet) => max_values_per_facet,
+        None => index
+            .max_values_per_facet(rtxn)
+            .map_err(milli::Error::from)?
+            .map(|x| x as usize)
+            .unwrap_or(DEFAULT_VALUES_PER_FACET),
+    };
+    facet_distribution.max_values_per_facet(max_values_by_facet);
+
+    let sort_facet_values_by = index.sort_facet_values_by(rtxn).map_err(milli::Error::from)?;
+
+    if fields.iter().all(|f| f != "*") {
+        let fields: Vec<_> = fields.iter().map(|n| (n, sort_facet_values_by.get(n))).collect();
+        facet_distribution.facets(fields);
+    }
+
+    let distribution = facet_distribution
+        .candidates(candidates)
+        .default_order_by(sort_facet_values_by.get("*"))
+        .execute()?;
+    let stats = facet_distribution.compute_stats()?;
+    let stats = stats.into_iter().map(|(k, (min, max))| (k, FacetStats { min, max })).collect();
+    Ok(ComputedFacets { distribution, stats })
+}
+
+/// Runs the keyword part of the search and describes how the documents were ranked.
+pub fn perform_explain(
+    index: &Index,
+    query: SearchQuery,
+    features: RoFeatures,
+) -> Result<milli::SearchExplanation, ResponseError> {
+    if query.hybrid.is_some() {
+        return Err(ResponseError::from_msg(
+            "Only keyword searches can be explained, `hybrid` cannot be used.".to_string(),
+            Code::InvalidHybridQuery,
+        ));
+    }
+    if query.vector.is_some() {
+        return Err(ResponseError::from_msg(
+            "Only keyword searches can be explained, `vector` cannot be used.".to_string(),
+            Code::InvalidSearchVector,
+        ));
+    }
+    if query.sparse_vector.is_some() {
+        return Err(ResponseError::from_msg(
+            "Only keyword searches can be explained, `sparseVector` cannot be used.".to_string(),
+            Code::InvalidSearchSparseVector,
+        ));
+    }
+
+    let rtxn = index.read_txn()?;
+    let time_budget = match index.search_cutoff(&rtxn)? {
+        Some(cutoff) => TimeBudget::new(Duration::from_millis(cutoff)),
+        None => TimeBudget::default(),
+    };
+
+    let (search, _, _, _) =
+        prepare_search(index, &rtxn, &query, &SearchKind::KeywordOnly, time_budget, features)?;
+    let (_, explanation) = search.explain()?;
+    Ok(explanation)
+}
+
 pub fn search_from_kind(
     search_kind: SearchKind,
     search: milli::Search<'_>,
@@ -1297,10 +1747,25 @@ impl<'a> HitMaker<'a> {
             matches_position,
             ranking_score_details,
             ranking_score,
+            collapse: None,
         };
 
         Ok(hit)
     }
+
+    pub fn make_collapsed_hits(
+        &self,
+        group: &milli::CollapsedGroup,
+    ) -> Result<CollapsedHits, MeilisearchHttpError> {
+        let inner_hits = group
+            .inner_hits
+            .iter()
+            .zip(&group.inner_hits_scores)
+            .map(|(&id, score)| self.make_hit(id, score))
+            .collect::<Result<_, _>>()?;
+
+        Ok(CollapsedHits { inner_hits, total_hits: group.total_hits })
+    }
 }
 
 fn make_hits<'a>(
@@ -1309,6 +1774,7 @@ fn make_hits<'a>(
     format: AttributesFormat,
     matching_words: milli::MatchingWords,
     documents_ids_scores: impl Iterator<Item = (u32, &'a Vec<ScoreDetails>)> + 'a,
+    collapsed_groups: &[milli::CollapsedGroup],
 ) -> Result<Vec<SearchHit>, MeilisearchHttpError> {
     let mut documents = Vec::new();
 
@@ -1328,8 +1794,13 @@ fn make_hits<'a>(
 
     let hit_maker = HitMaker::new(index, rtxn, format, formatter_builder)?;
 
+    let mut collapsed_groups = collapsed_groups.iter();
     for (id, score) in documents_ids_scores {
-        documents.push(hit_maker.make_hit(id, score)?);
+        let mut hit = hit_maker.make_hit(id, score)?;
+        if let Some(group) = collapsed_groups.next() {
+            hit.collapse = Some(hit_maker.make_collapsed_hits(group)?);
+        }
+        documents.push(hit);
     }
     Ok(documents)
 }
@@ -1373,8 +1844,7 @@ pub fn perform_facet_search(
 pub fn perform_similar(
     index: &Index,
     query: SimilarQuery,
-    embedder_name: String,
-    embedder: Arc<Embedder>,
+    embedder: Option<(String, Arc<Embedder>)>,
     retrieve_vectors: RetrieveVectors,
     features: RoFeatures,
 ) -> Result<SimilarResult, ResponseError> {
@@ -1383,6 +1853,8 @@ pub fn perform_similar(
 
     let SimilarQuery {
         id,
+        positive_ids,
+        negative_ids,
         offset,
         limit,
         filter: _,
@@ -1394,36 +1866,70 @@ pub fn perform_similar(
         ranking_score_threshold,
     } = query;
 
-    // using let-else rather than `?` so that the borrow checker identifies we're always returning here,
-    // preventing a use-after-move
-    let Some(internal_id) = index.external_documents_ids().get(&rtxn, &id)? else {
+    if id.is_none() && positive_ids.is_empty() {
         return Err(ResponseError::from_msg(
-            MeilisearchHttpError::DocumentNotFound(id.into_inner()).to_string(),
-            Code::NotFoundSimilarId,
+            "Missing field `id`: at least one of `id` or `positiveIds` must be given.".to_string(),
+            Code::InvalidSimilarId,
         ));
+    }
+
+    let external_documents_ids = index.external_documents_ids();
+    let internal_ids = |ids: &mut dyn Iterator<Item = &ExternalDocumentId>| {
+        ids.map(|id| match external_documents_ids.get(&rtxn, id)? {
+            Some(internal_id) => Ok(internal_id),
+            None => Err(ResponseError::from_msg(
+                MeilisearchHttpError::DocumentNotFound(id.as_ref().to_owned()).to_string(),
+                Code::NotFoundSimilarId,
+            )),
+        })
+        .collect::<Result<Vec<_>, ResponseError>>()
     };
+    let positive = internal_ids(&mut id.iter().chain(&positive_ids))?;
+    let negative = internal_ids(&mut negative_ids.iter())?;
 
-    let mut similar =
-        milli::Similar::new(internal_id, offset, limit, index, &rtxn, embedder_name, embedder);
+    let filter = match &query.filter {
+        Some(filter) => parse_filter(filter, Code::InvalidSimilarFilter, features)?,
+        None => None,
+    };
 
-    if let Some(ref filter) = query.filter {
-        if let Some(facets) = parse_filter(filter, Code::InvalidSimilarFilter, features)? {
-            similar.filter(facets);
+    let result = match embedder {
+        Some((embedder_name, embedder)) => {
+            let mut similar =
+                milli::Similar::new(positive, offset, limit, index, &rtxn, embedder_name, embedder);
+            similar.negative(negative);
+            if let Some(filter) = filter {
+                similar.filter(filter);
+            }
+            if let Some(ranking_score_threshold) = ranking_score_threshold {
+                similar.ranking_score_threshold(ranking_score_threshold.0);
+            }
+            similar.execute()
         }
-    }
-
-    if let Some(ranking_score_threshold) = ranking_score_threshold {
-        similar.ranking_score_threshold(ranking_score_threshold.0);
-    }
+        None => {
+            let mut similar = milli::SimilarKeywords::new(positive, offset, limit, index, &rtxn);
+            similar.negative(negative);
+            if let Some(filter) = filter {
+                similar.filter(filter);
+            }
+            if let Some(ranking_score_threshold) = ranking_score_threshold {
+                similar.ranking_score_threshold(ranking_score_threshold.0);
+            }
+            similar.execute()
+        }
+    };
 
     let milli::SearchResult {
         documents_ids,
         matching_words: _,
         candidates,
         document_scores,
+        collapsed_groups: _,
+        applied_rules: _,
+        pinned_documents: _,
+        cursor: _,
         degraded: _,
         used_negative_operator: _,
-    } = similar.execute().map_err(|err| match err {
+    } = result.map_err(|err| match err {
         milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
             ResponseError::from_msg(err.to_string(), Code::InvalidSimilarFilter)
         }
@@ -1451,6 +1957,7 @@ pub fn perform_similar(
         format,
         Default::default(),
         documents_ids.iter().copied().zip(document_scores.iter()),
+        &[],
     )?;
 
     let max_total_hits = index
@@ -1465,7 +1972,7 @@ pub fn perform_similar(
     let result = SimilarResult {
         hits,
         hits_info,
-        id: id.into_inner(),
+        id: id.map(ExternalDocumentId::into_inner),
         processing_time_ms: before_search.elapsed().as_millis(),
     };
     Ok(result)
@@ -1646,31 +2153,44 @@ fn format_fields(
     // select the attributes to retrieve
     let displayable_names =
         displayable_ids.iter().map(|&fid| field_ids_map.name(fid).expect("Missing field name"));
-    permissive_json_pointer::map_leaf_values(&mut document, displayable_names, |key, value| {
-        // To get the formatting option of each key we need to see all the rules that applies
-        // to the value and merge them together. eg. If a user said he wanted to highlight `doggo`
-        // and crop `doggo.name`. `doggo.name` needs to be highlighted + cropped while `doggo.age` is only
-        // highlighted.
-        // Warn: The time to compute the format list scales with the number of fields to format;
-        // cumulated with map_leaf_values that iterates over all the nested fields, it gives a quadratic complexity:
-        // d*f where d is the total number of fields to display and f is the total number of fields to format.
-        let format = formatting_fields_options
-            .iter()
-            .filter(|(name, _option)| {
-                milli::is_faceted_by(name, key) || milli::is_faceted_by(key, name)
-            })
-            .map(|(_, option)| **option)
-            .reduce(|acc, option| acc.merge(option));
-        let mut infos = Vec::new();
-
-        *value = format_value(std::mem::take(value), builder, format, &mut infos, compute_matches);
-
-        if let Some(matches) = matches_position.as_mut() {
-            if !infos.is_empty() {
-                matches.insert(key.to_owned(), infos);
+    permissive_json_pointer::map_leaf_values(
+        &mut document,
+        displayable_names,
+        |key, array_indices, value| {
+            // To get the formatting option of each key we need to see all the rules that applies
+            // to the value and merge them together. eg. If a user said he wanted to highlight `doggo`
+            // and crop `doggo.name`. `doggo.name` needs to be highlighted + cropped while `doggo.age` is only
+            // highlighted.
+            // Warn: The time to compute the format list scales with the number of fields to format;
+            // cumulated with map_leaf_values that iterates over all the nested fields, it gives a quadratic complexity:
+            // d*f where d is the total number of fields to display and f is the total number of fields to format.
+            let format = formatting_fields_options
+                .iter()
+                .filter(|(name, _option)| {
+                    milli::is_faceted_by(name, key) || milli::is_faceted_by(key, name)
+                })
+                .map(|(_, option)| **option)
+                .reduce(|acc, option| acc.merge(option));
+            let mut infos = Vec::new();
+
+            *value = format_value(
+                std::mem::take(value),
+                builder,
+                format,
+                &mut infos,
+                compute_matches,
+                array_indices,
+            );
+
+            if let Some(matches) = matches_position.as_mut() {
+                // the values of an array are mapped one by one, we must not override
+                // the matches of the previous ones
+                if !infos.is_empty() {
+                    matches.entry(key.to_owned()).or_default().extend(infos);
+                }
             }
-        }
-    });
+        },
+    );
 
     let selectors = formatted_options
         .keys()
@@ -1688,12 +2208,13 @@ fn format_value(
     format_options: Option<FormatOptions>,
     infos: &mut Vec<MatchBounds>,
     compute_matches: bool,
+    array_indices: &[usize],
 ) -> Value {
     match value {
         Value::String(old_string) => {
             let mut matcher = builder.build(&old_string);
             if compute_matches {
-                let matches = matcher.matches();
+                let matches = matcher.matches(array_indices);
                 infos.extend_from_slice(&matches[..]);
             }
 
@@ -1708,7 +2229,10 @@ fn format_value(
         Value::Array(values) => Value::Array(
             values
                 .into_iter()
-                .map(|v| {
+                .enumerate()
+                .map(|(i, v)| {
+                    let array_indices: Vec<_> =
+                        array_indices.iter().copied().chain(std::iter::once(i)).collect();
                     format_value(
                         v,
                         builder,
@@ -1718,6 +2242,7 @@ fn format_value(
                         }),
                         infos,
                         compute_matches,
+                        &array_indices,
                     )
                 })
                 .collect(),
@@ -1737,6 +2262,7 @@ fn format_value(
                             }),
                             infos,
                             compute_matches,
+                            array_indices,
                         ),
                     )
                 })
@@ -1747,7 +2273,7 @@ fn format_value(
 
             let mut matcher = builder.build(&s);
             if compute_matches {
-                let matches = matcher.matches();
+                let matches = matcher.matches(array_indices);
                 infos.extend_from_slice(&matches[..]);
             }
 
@@ -0,0 +1,212 @@
+//! This file implements an in-memory cache of the search results.
+//! Searching the same thing twice on an index that wasn't updated in the meantime gives the same result,
+//! thus we can answer the second search without computing it again.
+//!
+//! The results are identified by the uid of the index, the normalized search query and the enabled
+//! experimental features, since toggling a feature such as the vector store changes how a query is answered.
+//! The tenant token search rules must be applied to the filter of the query before using the cache,
+//! so that two API keys with different search rules never share a result.
+//!
+//! ### Invalidation
+//!
+//! Every result is stored along with the generation of its index, as returned by
+//! [`IndexScheduler::index_generation`](index_scheduler::IndexScheduler::index_generation).
+//! The generation must be read **before** the search is performed: if the index is updated while searching,
+//! the generation changes and the result is discarded the next time it's looked up.
+//! The results are also discarded once they're older than the time-to-live of the cache, and the least
+//! recently used results are evicted when the cache is full.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use meilisearch_types::features::RuntimeTogglableFeatures;
+
+use crate::metrics::{MEILISEARCH_SEARCH_CACHE_HITS, MEILISEARCH_SEARCH_CACHE_MISSES};
+use crate::search::{SearchQuery, SearchResult};
+
+#[derive(Debug)]
+pub struct SearchCache {
+    /// The maximum number of bytes used by the cached results, `0` disables the cache.
+    max_size: usize,
+    time_to_live: Duration,
+    inner: Mutex<CacheInner>,
+}
+
+/// Identifies a search result in the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    index_uid: String,
+    query: String,
+    features: RuntimeTogglableFeatures,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// The keys of the entries, from the least to the most recently used.
+    recently_used: BTreeMap<u64, CacheKey>,
+    /// Incremented each time an entry is used.
+    clock: u64,
+    /// The estimated number of bytes used by the entries.
+    size: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    generation: u64,
+    inserted_at: Instant,
+    last_used: u64,
+    size: usize,
+    result: SearchResult,
+}
+
+impl SearchCache {
+    pub fn new(max_size: usize, time_to_live: Duration) -> Self {
+        Self { max_size, time_to_live, inner: Mutex::default() }
+    }
+
+    /// Returns the key of the search in the cache, or `None` if the cache is disabled.
+    pub fn key(
+        &self,
+        index_uid: &str,
+        query: &SearchQuery,
+        features: RuntimeTogglableFeatures,
+    ) -> Option<CacheKey> {
+        if self.max_size == 0 {
+            return None;
+        }
+        Some(CacheKey { index_uid: index_uid.to_string(), query: normalize_query(query), features })
+    }
+
+    /// Returns the cached result of the search if it was computed on the given generation of the index.
+    pub fn get(&self, key: &CacheKey, generation: u64) -> Option<SearchResult> {
+        let mut inner = self.inner.lock().unwrap();
+        let result = inner.get(key, generation, self.time_to_live);
+        match result {
+            Some(_) => MEILISEARCH_SEARCH_CACHE_HITS.inc(),
+            None => MEILISEARCH_SEARCH_CACHE_MISSES.inc(),
+        }
+        result
+    }
+
+    /// Stores the result of the search, computed on the given generation of the index.
+    pub fn insert(&self, key: CacheKey, generation: u64, result: &SearchResult) {
+        // We estimate the memory used by a result with the size of its JSON representation.
+        let Ok(json) = serde_json::to_vec(result) else { return };
+        let size = key.index_uid.len() + key.query.len() + json.len();
+        if size > self.max_size {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(&key);
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.recently_used.insert(last_used, key.clone());
+        inner.entries.insert(
+            key,
+            CacheEntry {
+                generation,
+                inserted_at: Instant::now(),
+                last_used,
+                size,
+                result: result.clone(),
+            },
+        );
+        inner.size += size;
+
+        while inner.size > self.max_size {
+            let Some((_, key)) = inner.recently_used.pop_first() else { break };
+            inner.remove(&key);
+        }
+    }
+}
+
+impl CacheInner {
+    fn get(
+        &mut self,
+        key: &CacheKey,
+        generation: u64,
+        time_to_live: Duration,
+    ) -> Option<SearchResult> {
+        let entry = self.entries.get(key)?;
+        if entry.generation != generation || entry.inserted_at.elapsed() > time_to_live {
+            self.remove(key);
+            return None;
+        }
+
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.last_used, clock);
+        let result = entry.result.clone();
+        if let Some(key) = self.recently_used.remove(&previous) {
+            self.recently_used.insert(clock, key);
+        }
+        Some(result)
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recently_used.remove(&entry.last_used);
+            self.size -= entry.size;
+        }
+    }
+}
+
+/// Returns a representation of the query that is the same for all the queries returning the same result.
+fn normalize_query(query: &SearchQuery) -> String {
+    // We wants to decompose the whole struct by hand to be sure we don't forget
+    // to add a parameter of the search in the key of the cache.
+    let SearchQuery {
+        q,
+        vector,
+        sparse_vector,
+        hybrid,
+        offset,
+        limit,
+        page,
+        hits_per_page,
+        attributes_to_retrieve,
+        retrieve_vectors,
+        attributes_to_crop,
+        crop_length,
+        attributes_to_highlight,
+        show_matches_position,
+        show_ranking_score,
+        show_ranking_score_details,
+        filter,
+        sort,
+        distinct,
+        collapse,
+        rerank,
+        boost,
+        boost_position,
+        search_after,
+        facets,
+        highlight_pre_tag,
+        highlight_post_tag,
+        crop_marker,
+        matching_strategy,
+        attributes_to_search_on,
+        ranking_score_threshold,
+    } = query;
+
+    // The iteration order of a `HashSet` isn't deterministic.
+    let attributes_to_highlight: Option<BTreeSet<_>> =
+        attributes_to_highlight.as_ref().map(|attributes| attributes.iter().collect());
+
+    format!(
+        "{:?}",
+        (
+            (q, vector, sparse_vector, hybrid, matching_strategy, attributes_to_search_on),
+            (offset, limit, page, hits_per_page, search_after),
+            (filter, sort, distinct, collapse, rerank, boost, boost_position, facets),
+            (attributes_to_retrieve, retrieve_vectors, attributes_to_crop, crop_length),
+            (attributes_to_highlight, highlight_pre_tag, highlight_post_tag, crop_marker),
+            (show_matches_position, show_ranking_score, show_ranking_score_details),
+            ranking_score_threshold,
+        )
+    )
+}
@@ -3,6 +3,7 @@ use std::panic::{catch_unwind, resume_unwind, UnwindSafe};
 use std::time::Duration;
 
 use actix_web::http::StatusCode;
+use actix_web::test;
 use tokio::time::sleep;
 use urlencoding::encode as urlencode;
 
@@ -332,6 +333,11 @@ impl Index<'_> {
         self.service.get(url).await
     }
 
+    pub async fn clear_embedding_cache(&self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/embedding-cache", urlencode(self.uid.as_ref()));
+        self.service.delete(url).await
+    }
+
     /// Performs both GET and POST search queries
     pub async fn search(
         &self,
@@ -364,6 +370,22 @@ impl Index<'_> {
         self.service.post_encoded(url, query, self.encoder).await
     }
 
+    /// Returns the hits of the export, one per line, or the error returned by the route.
+    pub async fn search_export(&self, query: Value) -> (Result<Vec<Value>, Value>, StatusCode) {
+        let url = format!("/indexes/{}/search/export", urlencode(self.uid.as_ref()));
+        let req = test::TestRequest::post().uri(&url).set_json(query);
+        let (body, code) = self.service.request_raw(req).await;
+        if !code.is_success() {
+            return (Err(serde_json::from_slice(&body).unwrap_or_default()), code);
+        }
+        let hits = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        (Ok(hits), code)
+    }
+
     pub async fn search_get(&self, query: &str) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/search{}", urlencode(self.uid.as_ref()), query);
         self.service.get(url).await
@@ -406,6 +428,11 @@ impl Index<'_> {
         self.service.get(url).await
     }
 
+    pub async fn render(&self, query: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/render", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
     pub async fn facet_search(&self, query: Value) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/facet-search", urlencode(self.uid.as_ref()));
         self.service.post_encoded(url, query, self.encoder).await
@@ -4,6 +4,7 @@ use actix_web::http::header::ContentType;
 use actix_web::http::StatusCode;
 use actix_web::test;
 use actix_web::test::TestRequest;
+use actix_web::web::Bytes;
 use index_scheduler::IndexScheduler;
 use meilisearch::{analytics, create_app, Opt, SubscriberForSecondLayer};
 use meilisearch_auth::AuthController;
@@ -106,7 +107,14 @@ impl Service {
         self.request(req).await
     }
 
-    pub async fn request(&self, mut req: test::TestRequest) -> (Value, StatusCode) {
+    pub async fn request(&self, req: test::TestRequest) -> (Value, StatusCode) {
+        let (body, status_code) = self.request_raw(req).await;
+        let response = serde_json::from_slice(&body).unwrap_or_default();
+        (response, status_code)
+    }
+
+    /// Sends the request and returns the body as is, for the routes that don't return JSON.
+    pub async fn request_raw(&self, mut req: test::TestRequest) -> (Bytes, StatusCode) {
         let (_route_layer, route_layer_handle) =
             tracing_subscriber::reload::Layer::new(None.with_filter(
                 tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
@@ -138,8 +146,7 @@ impl Service {
         let status_code = res.status();
 
         let body = test::read_body(res).await;
-        let response = serde_json::from_slice(&body).unwrap_or_default();
-        (response, status_code)
+        (body, status_code)
     }
 
     fn encode(&self, req: TestRequest, body: Value, encoder: Encoder) -> TestRequest {
@@ -78,7 +78,9 @@ async fn import_dump_v1_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -240,7 +242,9 @@ async fn import_dump_v1_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -388,7 +392,9 @@ async fn import_dump_v1_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -522,7 +528,9 @@ async fn import_dump_v2_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -668,7 +676,9 @@ async fn import_dump_v2_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -813,7 +823,9 @@ async fn import_dump_v2_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -947,7 +959,9 @@ async fn import_dump_v3_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1093,7 +1107,9 @@ async fn import_dump_v3_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1238,7 +1254,9 @@ async fn import_dump_v3_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1372,7 +1390,9 @@ async fn import_dump_v4_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1518,7 +1538,9 @@ async fn import_dump_v4_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1663,7 +1685,9 @@ async fn import_dump_v4_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###
     );
@@ -1909,7 +1933,9 @@ async fn import_dump_v6_containing_experimental_features() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###);
 
@@ -2087,7 +2113,9 @@ async fn generate_and_import_dump_containing_vectors() {
           "documentTemplate": "{{doc.doggo}}"
         }
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###);
 
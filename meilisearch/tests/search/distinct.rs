@@ -304,3 +304,124 @@ async fn distinct_at_search_time() {
     snapshot!(response["totalPages"], @"1");
     snapshot!(response["totalHits"], @"3");
 }
+
+#[actix_rt::test]
+async fn collapse_at_search_time() {
+    let server = Server::new().await;
+    let index = server.index("tamo");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, Some(DOCUMENT_PRIMARY_KEY)).await;
+    let (task, _) = index.update_settings_filterable_attributes(json!(["product_id"])).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    fn get_groups(response: &Value) -> Vec<(String, Vec<String>, String)> {
+        let hits_array = response["hits"]
+            .as_array()
+            .unwrap_or_else(|| panic!("{}", &serde_json::to_string_pretty(&response).unwrap()));
+        hits_array
+            .iter()
+            .map(|h| {
+                let inner_hits = h["_collapse"]["innerHits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|h| h[DOCUMENT_PRIMARY_KEY].as_number().unwrap().to_string())
+                    .collect();
+                (
+                    h[DOCUMENT_PRIMARY_KEY].as_number().unwrap().to_string(),
+                    inner_hits,
+                    h["_collapse"]["totalHits"].as_number().unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    }
+
+    let (response, code) = index
+        .search_post(json!({
+            "page": 1,
+            "hitsPerPage": 3,
+            "collapse": { "attribute": "product_id", "innerHits": 1 }
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_groups(&response)), @r###"[("1", ["2"], "3"), ("4", ["5"], "2"), ("6", ["7"], "2")]"###);
+    snapshot!(response["totalPages"], @"2");
+    snapshot!(response["totalHits"], @"6");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "sweater",
+            "collapse": { "attribute": "product_id", "innerHits": 5 }
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_groups(&response)), @r###"[("9", ["10", "11"], "3")]"###);
+
+    // without inner hits, only the sizes of the groups are computed
+    let (response, code) = index
+        .search_post(json!({
+            "page": 1,
+            "hitsPerPage": 3,
+            "collapse": { "attribute": "product_id" }
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_groups(&response)), @r###"[("1", [], "3"), ("4", [], "2"), ("6", [], "2")]"###);
+
+    let (response, code) = index
+        .search_post(json!({ "q": "sweater", "collapse": { "attribute": "product_id" } }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_groups(&response)), @r###"[("9", [], "3")]"###);
+
+    let (response, code) = index.search_get("?collapseInnerHits=2").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value in parameter `collapseInnerHits`: `collapseInnerHits` requires a `collapseAttribute`.",
+      "code": "invalid_search_collapse",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_collapse"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({ "collapse": { "attribute": "product_id" }, "distinct": "product_id" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`distinct` and `collapse` cannot be used together in the same search.",
+      "code": "invalid_search_collapse",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_collapse"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({ "limit": 100, "collapse": { "attribute": "product_id", "innerHits": 10 } }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Collapsing 100 hits with 10 inner hits each returns 1100 documents, the maximum is 1000.\n - Hint: reduce `limit` or `collapse.innerHits`.",
+      "code": "invalid_search_collapse",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_collapse"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({ "collapse": { "attribute": "color" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Attribute `color` is not filterable and thus, cannot be used to collapse the search results. Available filterable attributes are: `product_id`.",
+      "code": "invalid_search_collapse",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_collapse"
+    }
+    "###);
+}
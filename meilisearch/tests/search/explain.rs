@@ -0,0 +1,75 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+use crate::search::DOCUMENTS;
+
+#[actix_rt::test]
+async fn explain_search() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let url = format!("/indexes/{}/search/explain", index.uid);
+    let (response, code) = server.service.post(&url, json!({ "q": "capitain marv" })).await;
+    snapshot!(code, @"200 OK");
+
+    let terms: Vec<_> = response["queryTerms"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|term| (term["original"].as_str().unwrap(), term["isPrefix"].as_bool().unwrap()))
+        .collect();
+    assert!(terms.contains(&("capitain", false)), "{terms:?}");
+    assert!(terms.contains(&("marv", true)), "{terms:?}");
+    let capitain =
+        response["queryTerms"].as_array().unwrap().iter().find(|t| t["original"] == "capitain");
+    snapshot!(json_string!(capitain.unwrap()["oneTypo"]), @r###"
+    [
+      "captain"
+    ]
+    "###);
+
+    let nodes = response["queryGraph"].as_array().unwrap();
+    assert!(nodes.iter().any(|node| node["type"] == "start"));
+    assert!(nodes.iter().any(|node| node["type"] == "end"));
+
+    let words = &response["rankingRules"][0];
+    snapshot!(words["name"], @r###""words""###);
+    snapshot!(json_string!(words["buckets"][0]), @r###"
+    {
+      "universe": 1,
+      "size": 1,
+      "firstHits": [
+        "299537"
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn explain_errors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let url = format!("/indexes/{}/search/explain", index.uid);
+    let (response, code) = server.service.post(&url, json!({ "vector": [1.0, 2.0, 3.0] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Only keyword searches can be explained, `vector` cannot be used.",
+      "code": "invalid_search_vector",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_vector"
+    }
+    "###);
+
+    let (response, code) = server.service.post(&url, json!({ "sort": ["title:asc"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_sort""###);
+}
@@ -0,0 +1,109 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+#[actix_rt::test]
+async fn export_all_hits() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "sortableAttributes": ["rank"],
+            "filterableAttributes": ["even"],
+            "pagination": { "maxTotalHits": 10 },
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    // more documents than in a single chunk of the export
+    let documents: Vec<_> = (0..2500)
+        .map(|id| json!({ "id": id, "rank": (id * 7) % 2500, "even": id % 2 == 0 }))
+        .collect();
+    let (task, _code) = index.add_documents(Value::from(documents), Some("id")).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (hits, code) =
+        index.search_export(json!({ "filter": "even = true", "sort": ["rank:desc"] })).await;
+    snapshot!(code, @"200 OK");
+    let hits = hits.unwrap();
+    assert_eq!(hits.len(), 1250);
+    assert!(hits.iter().all(|hit| hit["even"] == json!(true)));
+    let ranks: Vec<_> = hits.iter().map(|hit| hit["rank"].as_u64().unwrap()).collect();
+    assert!(ranks.windows(2).all(|w| w[0] > w[1]), "the hits must follow the sort");
+
+    // the chunks are pages resumed after each other, without any gap or duplicate
+    let (hits, code) = index.search_export(json!({ "sort": ["rank:asc"] })).await;
+    snapshot!(code, @"200 OK");
+    let ranks: Vec<_> = hits.unwrap().iter().map(|hit| hit["rank"].as_u64().unwrap()).collect();
+    assert_eq!(ranks, (0..2500).collect::<Vec<_>>());
+
+    let (hits, code) =
+        index.search_export(json!({ "filter": "even = true AND even = false" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(hits.unwrap().len(), @"0");
+}
+
+#[actix_rt::test]
+async fn export_formatted_hits() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                { "id": 0, "title": "The quick brown fox" },
+                { "id": 1, "title": "The lazy dog" },
+                { "id": 2, "title": "A quick dog" },
+            ]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (hits, code) = index
+        .search_export(json!({
+            "q": "quick",
+            "attributesToRetrieve": ["id"],
+            "attributesToHighlight": ["title"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits = hits.unwrap();
+    let ids: Vec<_> = hits.iter().map(|hit| hit["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, [0, 2]);
+    snapshot!(hits[0]["_formatted"]["title"], @r###""The <em>quick</em> brown fox""###);
+    snapshot!(hits[1]["_formatted"]["title"], @r###""A <em>quick</em> dog""###);
+}
+
+#[actix_rt::test]
+async fn export_errors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.add_documents(json!([{ "id": 0 }]), Some("id")).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_export(json!({ "sort": ["id:asc"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response.unwrap_err()), @r###"
+    {
+      "message": "Attribute `id` is not sortable. This index does not have configured sortable attributes.",
+      "code": "invalid_search_sort",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sort"
+    }
+    "###);
+
+    let (response, code) = index.search_export(json!({ "limit": 10 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response.unwrap_err()), @r###"
+    {
+      "message": "Unknown field `limit`: expected one of `q`, `filter`, `sort`, `distinct`, `matchingStrategy`, `attributesToSearchOn`, `attributesToRetrieve`, `retrieveVectors`, `attributesToCrop`, `cropLength`, `attributesToHighlight`, `showMatchesPosition`, `highlightPreTag`, `highlightPostTag`, `cropMarker`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+}
@@ -213,7 +213,10 @@ async fn format_nested() {
                         "doggos.name": [
                           {
                             "start": 0,
-                            "length": 5
+                            "length": 5,
+                            "indices": [
+                              0
+                            ]
                           }
                         ]
                       }
@@ -568,3 +571,51 @@ async fn test_cjk_highlight() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn matches_position_array_indices() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "tags": ["red", "blue", "red"], "paragraphs": [["a dog"], ["a cat", "a red dog"]] },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(json!({"q": "red", "showMatchesPosition": true }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_json_snapshot!(response["hits"][0]["_matchesPosition"], @r###"
+            {
+              "paragraphs": [
+                {
+                  "start": 2,
+                  "length": 3,
+                  "indices": [
+                    1,
+                    1
+                  ]
+                }
+              ],
+              "tags": [
+                {
+                  "start": 0,
+                  "length": 3,
+                  "indices": [
+                    0
+                  ]
+                },
+                {
+                  "start": 0,
+                  "length": 3,
+                  "indices": [
+                    2
+                  ]
+                }
+              ]
+            }
+            "###);
+        })
+        .await;
+}
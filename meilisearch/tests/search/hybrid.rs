@@ -597,3 +597,99 @@ async fn retrieve_vectors() {
     ]
     "###);
 }
+
+#[actix_rt::test]
+async fn rrf_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+    let ids = |response: &Value| -> Vec<String> {
+        response["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // the keyword results are [2, 3, 1] and the semantic results are [3, 2, 1]
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.2, "fusion": "rrf"}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(ids(&response), ["2", "3", "1"]);
+    snapshot!(response["semanticHitCount"], @"0");
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.8, "fusion": "rrf"}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(ids(&response), ["3", "2", "1"]);
+    snapshot!(response["semanticHitCount"], @"3");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "Captain",
+            "vector": [1.0, 1.0],
+            "hybrid": {"semanticRatio": 0.8, "fusion": "rrf", "rankConstant": 0},
+            "showRankingScore": true,
+            "showRankingScoreDetails": true,
+            "offset": 1,
+            "limit": 1,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(ids(&response), ["2"]);
+    let hit = &response["hits"][0];
+    let fusion = &hit["_rankingScoreDetails"]["rankFusion"];
+    snapshot!(fusion["order"], @"0");
+    snapshot!(fusion["keywordRank"], @"1");
+    snapshot!(fusion["semanticRank"], @"2");
+    snapshot!(fusion["rankConstant"], @"0");
+    // the ranking score is the normalized fused score
+    assert_eq!(hit["_rankingScore"], fusion["score"]);
+    let score = fusion["score"].as_f64().unwrap();
+    assert!((score - 0.6).abs() < 1e-6, "{score}");
+    // along with the details of both searches
+    assert!(hit["_rankingScoreDetails"]["words"].is_object());
+    assert!(hit["_rankingScoreDetails"]["vectorSort"].is_object());
+}
+
+#[actix_rt::test]
+async fn invalid_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.5, "fusion": "doggo"}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Unknown value `doggo` at `.hybrid.fusion`: expected one of `score`, `rrf`",
+      "code": "invalid_search_fusion",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_fusion"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.5, "rankConstant": 10}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Using `hybrid.rankConstant` is only allowed with the `rrf` fusion.\n Hint: remove `rankConstant` or add `fusion: \"rrf\"` to `hybrid`",
+      "code": "invalid_search_rank_constant",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rank_constant"
+    }
+    "###);
+}
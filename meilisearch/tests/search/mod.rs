@@ -3,6 +3,8 @@
 
 mod distinct;
 mod errors;
+mod explain;
+mod export;
 mod facet_search;
 mod formatted;
 mod geo;
@@ -10,7 +12,9 @@ mod hybrid;
 mod matching_strategy;
 mod multi;
 mod pagination;
+mod query_rules;
 mod restrict_searchable;
+mod search_cache;
 mod search_queue;
 
 use meilisearch::Opt;
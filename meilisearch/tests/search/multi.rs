@@ -4321,3 +4321,353 @@ async fn federation_vector_two_indexes() {
     }
     "###);
 }
+
+async fn facets_indexes(server: &Server) {
+    let movies = server.index("movies");
+    let (value, _) = movies
+        .add_documents(
+            json!([
+                { "id": 1, "genre": "action", "year": 2000 },
+                { "id": 2, "genre": "drama", "year": 2010 },
+                { "id": 3, "genre": "action", "year": 2020 },
+            ]),
+            None,
+        )
+        .await;
+    movies.wait_task(value.uid()).await.succeeded();
+    let (value, _) =
+        movies.update_settings(json!({"filterableAttributes": ["genre", "year"]})).await;
+    movies.wait_task(value.uid()).await.succeeded();
+
+    let books = server.index("books");
+    let (value, _) = books
+        .add_documents(
+            json!([
+                { "id": 1, "genre": "drama", "year": 1990 },
+                { "id": 2, "genre": "poetry", "year": 2005 },
+            ]),
+            None,
+        )
+        .await;
+    books.wait_task(value.uid()).await.succeeded();
+    let (value, _) =
+        books.update_settings(json!({"filterableAttributes": ["genre", "year"]})).await;
+    books.wait_task(value.uid()).await.succeeded();
+}
+
+#[actix_rt::test]
+async fn federation_facets_by_index() {
+    let server = Server::new().await;
+    facets_indexes(&server).await;
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+        {"indexUid": "movies", "facets": ["genre"]},
+        {"indexUid": "movies", "q": "action", "facets": ["year"]},
+        {"indexUid": "books", "facets": ["genre"]},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert!(response.get("facetDistribution").is_none());
+    snapshot!(json_string!(response["facetsByIndex"]), @r###"
+    {
+      "books": {
+        "distribution": {
+          "genre": {
+            "drama": 1,
+            "poetry": 1
+          }
+        },
+        "stats": {}
+      },
+      "movies": {
+        "distribution": {
+          "genre": {
+            "action": 2,
+            "drama": 1
+          },
+          "year": {
+            "2000": 1,
+            "2010": 1,
+            "2020": 1
+          }
+        },
+        "stats": {
+          "year": {
+            "min": 2000.0,
+            "max": 2020.0
+          }
+        }
+      }
+    }
+    "###);
+
+    // no facets are returned when none are requested
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+        {"indexUid": "movies"},
+        {"indexUid": "books"},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert!(response.get("facetsByIndex").is_none());
+}
+
+#[actix_rt::test]
+async fn federation_merge_facets() {
+    let server = Server::new().await;
+    facets_indexes(&server).await;
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeFacets": {}}, "queries": [
+        {"indexUid": "movies", "facets": ["genre", "year"]},
+        {"indexUid": "books", "facets": ["genre", "year"]},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert!(response.get("facetsByIndex").is_none());
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genre": {
+        "action": 2,
+        "drama": 2,
+        "poetry": 1
+      },
+      "year": {
+        "1990": 1,
+        "2000": 1,
+        "2005": 1,
+        "2010": 1,
+        "2020": 1
+      }
+    }
+    "###);
+    snapshot!(json_string!(response["facetStats"]), @r###"
+    {
+      "year": {
+        "min": 1990.0,
+        "max": 2020.0
+      }
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeFacets": {"maxValuesPerFacet": 2}}, "queries": [
+        {"indexUid": "movies", "facets": ["genre", "year"]},
+        {"indexUid": "books", "facets": ["genre", "year"]},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genre": {
+        "action": 2,
+        "drama": 2
+      },
+      "year": {
+        "1990": 1,
+        "2000": 1
+      }
+    }
+    "###);
+
+    // the values are merged before being truncated, regardless of the `maxValuesPerFacet` of the indexes
+    let index = server.index("movies");
+    let (task, _code) = index.update_settings(json!({"faceting": {"maxValuesPerFacet": 1}})).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeFacets": {"maxValuesPerFacet": 3}}, "queries": [
+        {"indexUid": "movies", "facets": ["genre"]},
+        {"indexUid": "books", "facets": ["genre"]},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genre": {
+        "action": 2,
+        "drama": 2,
+        "poetry": 1
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn federation_facets_errors() {
+    let server = Server::new().await;
+    facets_indexes(&server).await;
+
+    let (response, code) = server
+        .multi_search(
+            json!({"federation": {"mergeFacets": {"maxValuesPerFacet": "doggo"}}, "queries": [
+            {"indexUid": "movies", "facets": ["genre"]},
+            ]}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.federation.mergeFacets.maxValuesPerFacet`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_multi_search_max_values_per_facet",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_max_values_per_facet"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+        {"indexUid": "movies", "facets": ["genre"]},
+        {"indexUid": "books", "facets": ["doggo"]},
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[1]`: Invalid facet distribution, attribute `doggo` is not filterable. The available filterable attributes are `genre, year`.",
+      "code": "invalid_search_facets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facets"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn federation_rrf() {
+    let server = Server::new().await;
+    facets_indexes(&server).await;
+    let index = server.index("movies");
+    let (value, _) = index.update_settings(json!({"sortableAttributes": ["year"]})).await;
+    index.wait_task(value.uid()).await.succeeded();
+
+    let queries = json!([
+      {"indexUid": "movies", "sort": ["year:desc"]},
+      {"indexUid": "movies", "sort": ["year:asc"]},
+    ]);
+
+    // the scores of sorts in opposite directions can't be compared
+    let (_response, code) =
+        server.multi_search(json!({"federation": {}, "queries": queries.clone()})).await;
+    snapshot!(code, @"400 Bad Request");
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeStrategy": "rrf"}, "queries": queries.clone()}))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| {
+            json!({
+                "id": hit["id"],
+                "queriesPosition": hit["_federation"]["queriesPosition"],
+                "queriesRanks": hit["_federation"]["queriesRanks"],
+                "rrfScore": hit["_federation"]["rrfScore"],
+            })
+        })
+        .collect();
+    snapshot!(json_string!(hits), @r###"
+    [
+      {
+        "id": 3,
+        "queriesPosition": 0,
+        "queriesRanks": {
+          "0": 1,
+          "1": 3
+        },
+        "rrfScore": 0.032266458495966696
+      },
+      {
+        "id": 1,
+        "queriesPosition": 1,
+        "queriesRanks": {
+          "0": 3,
+          "1": 1
+        },
+        "rrfScore": 0.032266458495966696
+      },
+      {
+        "id": 2,
+        "queriesPosition": 0,
+        "queriesRanks": {
+          "0": 2,
+          "1": 2
+        },
+        "rrfScore": 0.03225806451612903
+      }
+    ]
+    "###);
+
+    // the weights multiply the reciprocal ranks
+    let (response, code) = server
+        .multi_search(
+            json!({"federation": {"mergeStrategy": "rrf", "rankConstant": 0}, "queries": [
+              {"indexUid": "movies", "sort": ["year:desc"]},
+              {"indexUid": "movies", "sort": ["year:asc"], "federationOptions": {"weight": 2.0}},
+            ]}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, [1, 3, 2]);
+    snapshot!(response["hits"][0]["_federation"]["rrfScore"], @"2.3333333333333335");
+
+    // the hits of different indexes are fused too
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeStrategy": "rrf", "limit": 2}, "queries": [
+          {"indexUid": "movies", "sort": ["year:desc"]},
+          {"indexUid": "books", "federationOptions": {"weight": 0.5}},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| (hit["_federation"]["indexUid"].as_str().unwrap(), hit["id"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(hits, [("movies", 3), ("movies", 2)]);
+}
+
+#[actix_rt::test]
+async fn federation_rrf_errors() {
+    let server = Server::new().await;
+    facets_indexes(&server).await;
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"mergeStrategy": "doggo"}, "queries": [
+          {"indexUid": "movies"},
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.federation.mergeStrategy`: expected one of `score`, `rrf`",
+      "code": "invalid_multi_search_merge_strategy",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_merge_strategy"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {"rankConstant": 10}, "queries": [
+          {"indexUid": "movies"},
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using `federation.rankConstant` is only allowed with the `rrf` merge strategy.\n Hint: remove `rankConstant` or add `mergeStrategy: \"rrf\"` to the federation",
+      "code": "invalid_multi_search_rank_constant",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_rank_constant"
+    }
+    "###);
+}
@@ -1,3 +1,5 @@
+use meili_snap::{json_string, snapshot};
+
 use crate::common::Server;
 use crate::json;
 use crate::search::DOCUMENTS;
@@ -163,3 +165,78 @@ async fn ensure_placeholder_search_hit_count_valid() {
             .await;
     }
 }
+
+#[actix_rt::test]
+async fn search_after_cursor() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents: Vec<_> = (0..10).map(|id| json!({ "id": id, "rank": id % 4 })).collect();
+    index.add_documents(json!(documents), Some("id")).await;
+    let (task, _code) = index
+        .update_settings(json!({
+            "sortableAttributes": ["rank"],
+            "pagination": { "maxTotalHits": 5 },
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    // the cursor allows to go past `maxTotalHits`
+    let mut ids = Vec::new();
+    let mut cursor = String::new();
+    let mut pages = 0;
+    loop {
+        let (response, code) = index
+            .search_post(json!({ "sort": ["rank:desc"], "limit": 3, "searchAfter": cursor }))
+            .await;
+        assert_eq!(code, 200, "{}", response);
+        pages += 1;
+        for hit in response["hits"].as_array().unwrap() {
+            ids.push(hit["id"].as_u64().unwrap());
+        }
+        match response["nextCursor"].as_str() {
+            Some(next_cursor) => cursor = next_cursor.to_string(),
+            None => {
+                assert!(response["nextCursor"].is_null(), "{}", response);
+                break;
+            }
+        }
+    }
+    snapshot!(format!("{ids:?}"), @"[3, 7, 2, 6, 1, 5, 9, 0, 4, 8]");
+    snapshot!(pages, @"4");
+
+    // the cursor is only returned when requested
+    let (response, _code) = index.search_post(json!({ "sort": ["rank:desc"] })).await;
+    assert!(response.get("nextCursor").is_none(), "{}", response);
+}
+
+#[actix_rt::test]
+async fn search_after_errors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(json!([{ "id": 0 }, { "id": 1 }]), Some("id")).await;
+    index.wait_task(0).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "searchAfter": "", "offset": 1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`searchAfter` cannot be used together with `offset`, `page` or `hitsPerPage`.",
+      "code": "invalid_search_search_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_search_after"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({ "searchAfter": "not a cursor" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid search cursor `not a cursor`. The cursor must be the `nextCursor` returned by a previous search.",
+      "code": "invalid_search_search_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_search_after"
+    }
+    "###);
+}
@@ -0,0 +1,267 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "iPhone 15", "refurbished": false },
+        { "id": 2, "title": "iPhone 15 case", "refurbished": false },
+        { "id": 3, "title": "iPhone 14", "refurbished": true },
+        { "id": 4, "title": "Galaxy S24", "refurbished": false },
+        { "id": 5, "title": "iPhone 13", "refurbished": true },
+    ])
+});
+
+fn ids(response: &Value) -> Vec<String> {
+    response["hits"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{}", &serde_json::to_string_pretty(&response).unwrap()))
+        .iter()
+        .map(|hit| hit["id"].as_number().unwrap().to_string())
+        .collect()
+}
+
+#[actix_rt::test]
+async fn pin_hide_and_boost() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    let (task, code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["refurbished"],
+            "rules": [
+                {
+                    "id": "iphone-merchandising",
+                    "conditions": { "query": { "pattern": "iphone" } },
+                    "actions": {
+                        "pin": [{ "id": "4", "position": 1 }],
+                        "hide": ["5"],
+                        "boost": [{ "filter": "refurbished = true", "weight": -1 }]
+                    }
+                }
+            ]
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "q": "iphone" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "4", "2", "3"]"###);
+    snapshot!(json_string!(response["appliedRules"]), @r###"
+    [
+      "iphone-merchandising"
+    ]
+    "###);
+    snapshot!(response["estimatedTotalHits"], @"4");
+
+    // the pinned document stays at its position across pages
+    let (response, code) =
+        index.search_post(json!({ "q": "iphone", "offset": 1, "limit": 2 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["4", "2"]"###);
+
+    // the pinned document must match the filter of the search
+    let (response, code) =
+        index.search_post(json!({ "q": "iphone", "filter": "refurbished = false" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "4", "2"]"###);
+
+    // the pinned documents are ranked like the others when paginating with a cursor
+    let (response, code) = index.search_post(json!({ "q": "iphone", "searchAfter": "" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "2", "3"]"###);
+
+    // the rule doesn't apply to the other queries
+    let (response, code) = index.search_post(json!({ "q": "galaxy" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["4"]"###);
+    snapshot!(json_string!(response["appliedRules"]), @"null");
+}
+
+#[actix_rt::test]
+async fn rule_on_filter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["refurbished"],
+            "rules": [
+                {
+                    "id": "refurbished-iphone-first",
+                    "conditions": { "filter": "refurbished = true" },
+                    "actions": { "pin": [{ "id": "3", "position": 0 }] }
+                }
+            ]
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "filter": "refurbished = true" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["3", "5"]"###);
+    snapshot!(json_string!(response["appliedRules"]), @r###"
+    [
+      "refurbished-iphone-first"
+    ]
+    "###);
+
+    let (response, code) = index.search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "2", "3", "4", "5"]"###);
+}
+
+#[actix_rt::test]
+async fn invalid_rules() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "rules": [{ "id": "" }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid query rule ``: The `id` of a rule cannot be empty.",
+      "code": "invalid_settings_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "rules": [{ "id": "pin", "actions": { "pin": ["1"] } }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_rules""###);
+}
+
+#[actix_rt::test]
+async fn boost_at_search_time() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    let (task, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["id", "refurbished"] })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "iphone",
+            "boost": [{ "filter": "refurbished = true" }],
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["3", "5", "1", "2"]"###);
+    snapshot!(json_string!(response["hits"][0]["_rankingScoreDetails"]["boost"]), @r###"
+    {
+      "order": 0,
+      "weight": 1,
+      "score": 1.0
+    }
+    "###);
+    snapshot!(json_string!(response["hits"][2]["_rankingScoreDetails"]["boost"]), @r###"
+    {
+      "order": 0,
+      "weight": 0,
+      "score": 0.5
+    }
+    "###);
+
+    // the boost is applied after the `words` ranking rule
+    let (response, code) = index
+        .search_post(json!({
+            "q": "iphone 15",
+            "matchingStrategy": "last",
+            "boost": [{ "filter": "refurbished = true", "weight": 2 }],
+            "boostPosition": 1,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "2", "3", "5"]"###);
+
+    // the weights of the matching clauses are summed
+    let (response, code) = index
+        .search_post(json!({
+            "boost": [
+                { "filter": "refurbished = true", "weight": -1 },
+                { "filter": "id = 5", "weight": 1 },
+            ],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @r###"["1", "2", "4", "5", "3"]"###);
+}
+
+#[actix_rt::test]
+async fn invalid_boost() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    let (task, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["refurbished"] })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.search_post(json!({ "boost": [{ "filter": "title = iphone" }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_boost""###);
+
+    let (response, code) = index
+        .search_post(json!({ "boost": [{ "filter": "refurbished = true" }], "boostPosition": 42 }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value for `boostPosition`: 42 is greater than the number of ranking rules of the index (6).",
+      "code": "invalid_search_boost_position",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_boost_position"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn boost_rules_on_non_filterable_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+            "rules": [{ "id": "refurbished", "actions": { "boost": [{ "filter": "refurbished = true" }] } }],
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "Invalid query rule `refurbished`: Invalid `actions.boost.filter`: Attribute `refurbished` is not filterable.",
+      "code": "invalid_settings_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["refurbished"],
+            "rules": [{ "id": "refurbished", "actions": { "boost": [{ "filter": "refurbished = true" }] } }],
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await.succeeded();
+
+    // the rules are checked again when the filterable attributes change
+    let (response, code) = index.update_settings(json!({ "filterableAttributes": [] })).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["code"], @r###""invalid_settings_rules""###);
+}
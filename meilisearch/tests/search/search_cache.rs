@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use byte_unit::Byte;
+use meili_snap::snapshot;
+use meilisearch::metrics::MEILISEARCH_SEARCH_CACHE_HITS;
+use meilisearch::search::{HitsInfo, SearchQuery, SearchResult};
+use meilisearch::search_cache::SearchCache;
+use meilisearch::Opt;
+use meilisearch_types::features::RuntimeTogglableFeatures;
+use tempfile::TempDir;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+fn search_result(query: &str) -> SearchResult {
+    SearchResult {
+        hits: Vec::new(),
+        query: query.to_string(),
+        processing_time_ms: 0,
+        hits_info: HitsInfo::OffsetLimit { limit: 20, offset: 0, estimated_total_hits: 0 },
+        facet_distribution: None,
+        facet_stats: None,
+        semantic_hit_count: None,
+        applied_rules: Vec::new(),
+        next_cursor: None,
+        degraded: false,
+        used_negative_operator: false,
+    }
+}
+
+fn features() -> RuntimeTogglableFeatures {
+    RuntimeTogglableFeatures::default()
+}
+
+fn search_query(q: &str) -> SearchQuery {
+    SearchQuery { q: Some(q.to_string()), ..Default::default() }
+}
+
+#[test]
+fn search_cache_disabled() {
+    let cache = SearchCache::new(0, Duration::from_secs(60));
+    assert!(cache.key("movies", &search_query("fox"), features()).is_none());
+}
+
+#[test]
+fn search_cache_keys() {
+    let cache = SearchCache::new(1024 * 1024, Duration::from_secs(60));
+
+    let key = cache.key("movies", &search_query("fox"), features()).unwrap();
+    assert_eq!(key, cache.key("movies", &search_query("fox"), features()).unwrap());
+    assert_ne!(key, cache.key("books", &search_query("fox"), features()).unwrap());
+    assert_ne!(key, cache.key("movies", &search_query("dog"), features()).unwrap());
+
+    // the tenant token search rules are part of the filter
+    let filtered =
+        SearchQuery { filter: Some(serde_json::json!("genre = horror")), ..search_query("fox") };
+    assert_ne!(key, cache.key("movies", &filtered, features()).unwrap());
+
+    // the experimental features can change the result of a search
+    let vector_store = RuntimeTogglableFeatures { vector_store: true, ..features() };
+    assert_ne!(key, cache.key("movies", &search_query("fox"), vector_store).unwrap());
+
+    // the order of the attributes to highlight doesn't matter
+    let highlight = |attributes: &[&str]| SearchQuery {
+        attributes_to_highlight: Some(attributes.iter().map(|s| s.to_string()).collect()),
+        ..search_query("fox")
+    };
+    let attributes: Vec<_> = (0..20).map(|i| format!("attribute{i}")).collect();
+    let mut attributes: Vec<_> = attributes.iter().map(String::as_str).collect();
+    let expected = cache.key("movies", &highlight(&attributes), features()).unwrap();
+    attributes.reverse();
+    assert_eq!(expected, cache.key("movies", &highlight(&attributes), features()).unwrap());
+}
+
+#[test]
+fn search_cache_invalidation() {
+    let cache = SearchCache::new(1024 * 1024, Duration::from_secs(60));
+
+    let key = cache.key("movies", &search_query("fox"), features()).unwrap();
+    assert!(cache.get(&key, 0).is_none());
+    cache.insert(key.clone(), 0, &search_result("fox"));
+    snapshot!(cache.get(&key, 0).unwrap().query, @"fox");
+
+    // the index was updated in the meantime
+    assert!(cache.get(&key, 1).is_none());
+    // and the outdated result was removed
+    assert!(cache.get(&key, 0).is_none());
+}
+
+#[test]
+fn search_cache_time_to_live() {
+    let cache = SearchCache::new(1024 * 1024, Duration::from_millis(10));
+
+    let key = cache.key("movies", &search_query("fox"), features()).unwrap();
+    cache.insert(key.clone(), 0, &search_result("fox"));
+    assert!(cache.get(&key, 0).is_some());
+
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(cache.get(&key, 0).is_none());
+}
+
+#[test]
+fn search_cache_evicts_least_recently_used() {
+    // large results, so that the size of the keys is negligible
+    let result = |q: &str| search_result(&q.repeat(1000));
+    let size = serde_json::to_vec(&result("fox")).unwrap().len();
+    // there is only room for two results
+    let cache = SearchCache::new(size * 5 / 2, Duration::from_secs(60));
+
+    let fox = cache.key("movies", &search_query("fox"), features()).unwrap();
+    let dog = cache.key("movies", &search_query("dog"), features()).unwrap();
+    let cat = cache.key("movies", &search_query("cat"), features()).unwrap();
+
+    cache.insert(fox.clone(), 0, &result("fox"));
+    cache.insert(dog.clone(), 0, &result("dog"));
+    // fox is now the most recently used
+    assert!(cache.get(&fox, 0).is_some());
+    cache.insert(cat.clone(), 0, &result("cat"));
+
+    assert!(cache.get(&fox, 0).is_some());
+    assert!(cache.get(&dog, 0).is_none());
+    assert!(cache.get(&cat, 0).is_some());
+
+    // a result larger than the whole cache is never stored
+    let mouse = cache.key("movies", &search_query("mouse"), features()).unwrap();
+    cache.insert(mouse.clone(), 0, &search_result(&"mouse".repeat(2000)));
+    assert!(cache.get(&mouse, 0).is_none());
+    assert!(cache.get(&fox, 0).is_some());
+}
+
+#[actix_rt::test]
+async fn search_cache_is_invalidated_by_index_updates() {
+    let temp = TempDir::new().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_search_cache_size: Byte::from_u64(1024 * 1024),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("test");
+
+    let (task, _code) =
+        index.add_documents(json!([{ "id": 0, "title": "The quick brown fox" }]), Some("id")).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "q": "fox" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"].as_array().unwrap().len(), @"1");
+
+    let hits_before = MEILISEARCH_SEARCH_CACHE_HITS.get();
+    let (cached, code) = index.search_post(json!({ "q": "fox" })).await;
+    snapshot!(code, @"200 OK");
+    assert!(MEILISEARCH_SEARCH_CACHE_HITS.get() > hits_before);
+    assert_eq!(cached["hits"], response["hits"]);
+
+    let (task, _code) =
+        index.add_documents(json!([{ "id": 1, "title": "The lazy fox" }]), Some("id")).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "q": "fox" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"].as_array().unwrap().len(), @"2");
+}
@@ -36,6 +36,8 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
         }),
     );
     map.insert("search_cutoff_ms", json!(null));
+    map.insert("rules", json!([]));
+    map.insert("rerankers", json!({}));
     map
 });
 
@@ -55,7 +57,7 @@ async fn get_settings() {
     let (response, code) = index.settings().await;
     assert_eq!(code, 200);
     let settings = response.as_object().unwrap();
-    assert_eq!(settings.keys().len(), 16);
+    assert_eq!(settings.keys().len(), 18);
     assert_eq!(settings["displayedAttributes"], json!(["*"]));
     assert_eq!(settings["searchableAttributes"], json!(["*"]));
     assert_eq!(settings["filterableAttributes"], json!([]));
@@ -86,6 +88,8 @@ async fn get_settings() {
     );
     assert_eq!(settings["proximityPrecision"], json!("byWord"));
     assert_eq!(settings["searchCutoffMs"], json!(null));
+    assert_eq!(settings["rules"], json!([]));
+    assert_eq!(settings["rerankers"], json!({}));
 }
 
 #[actix_rt::test]
@@ -202,7 +206,9 @@ async fn secrets_are_hidden_in_settings() {
           "inputType": "text"
         }
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "rules": [],
+      "rerankers": {}
     }
     "###);
 
@@ -422,7 +428,9 @@ test_setting_routes!(
     synonyms put,
     pagination patch,
     faceting patch,
-    search_cutoff_ms put
+    search_cutoff_ms put,
+    rules put,
+    rerankers put
 );
 
 #[actix_rt::test]
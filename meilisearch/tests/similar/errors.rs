@@ -43,12 +43,14 @@ async fn similar_unexisting_parameter() {
 async fn similar_feature_not_enabled() {
     let server = Server::new().await;
     let index = server.index("test");
+    let (value, _code) = index.create(None).await;
+    index.wait_task(value.uid()).await.succeeded();
 
-    let (response, code) = index.similar_post(json!({"id": 287947})).await;
+    let (response, code) = index.similar_post(json!({"id": 287947, "embedder": "manual"})).await;
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Using the similar API requires enabling the `vector store` experimental feature. See https://github.com/meilisearch/product/discussions/677",
+      "message": "Using the similar API with an embedder requires enabling the `vector store` experimental feature. See https://github.com/meilisearch/product/discussions/677",
       "code": "feature_not_enabled",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
@@ -211,6 +213,97 @@ async fn similar_not_found_id() {
     "###);
 }
 
+#[actix_rt::test]
+async fn similar_missing_id() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    server.set_features(json!({"vectorStore": true})).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+        "embedders": {
+            "manual": {
+                "source": "userProvided",
+                "dimensions": 3,
+            }
+        },
+        "filterableAttributes": ["title"]}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await;
+
+    let (response, code) = index.similar_post(json!({"negativeIds": [287947]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `id`: at least one of `id` or `positiveIds` must be given.",
+      "code": "invalid_similar_id",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_similar_id"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn similar_bad_positive_and_negative_ids() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    server.set_features(json!({"vectorStore": true})).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+        "embedders": {
+            "manual": {
+                "source": "userProvided",
+                "dimensions": 3,
+            }
+        },
+        "filterableAttributes": ["title"]}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await;
+
+    let documents = DOCUMENTS.clone();
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await;
+
+    let (response, code) = index.similar_post(json!({"positiveIds": "287947"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.positiveIds`: expected an array, but found a string: `\"287947\"`",
+      "code": "invalid_similar_positive_ids",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_similar_positive_ids"
+    }
+    "###);
+
+    let (response, code) =
+        index.similar_post(json!({"id": 287947, "negativeIds": ["http://invalid-docid/"]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.negativeIds[0]`: the value of `id` is invalid. A document identifier can be of type integer or string, only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and underscores (_).",
+      "code": "invalid_similar_negative_ids",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_similar_negative_ids"
+    }
+    "###);
+
+    let (response, code) =
+        index.similar_post(json!({"id": 287947, "negativeIds": ["definitely-doesnt-exist"]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Document `definitely-doesnt-exist` not found.",
+      "code": "not_found_similar_id",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#not_found_similar_id"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn similar_bad_offset() {
     let server = Server::new().await;
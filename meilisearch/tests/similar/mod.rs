@@ -737,3 +737,154 @@ async fn limit_and_offset() {
         )
         .await;
 }
+
+#[actix_rt::test]
+async fn positive_and_negative_ids() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+        "embedders": {
+            "manual": {
+                "source": "userProvided",
+                "dimensions": 3,
+            }
+        },
+        "filterableAttributes": ["title"]}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await;
+
+    let documents = DOCUMENTS.clone();
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await;
+
+    index
+        .similar(json!({"id": 287947, "positiveIds": [166428]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "title": "Captain Marvel",
+                "release_year": 2019,
+                "id": "299537"
+              },
+              {
+                "title": "Escape Room",
+                "release_year": 2019,
+                "id": "522681"
+              },
+              {
+                "title": "All Quiet on the Western Front",
+                "release_year": 1930,
+                "id": "143"
+              }
+            ]
+            "###);
+            snapshot!(response["id"], @r###""287947""###);
+        })
+        .await;
+
+    index
+        .similar(json!({"positiveIds": [143], "negativeIds": [522681]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "title": "Captain Marvel",
+                "release_year": 2019,
+                "id": "299537"
+              },
+              {
+                "title": "How to Train Your Dragon: The Hidden World",
+                "release_year": 2019,
+                "id": "166428"
+              },
+              {
+                "title": "Shazam!",
+                "release_year": 2019,
+                "id": "287947"
+              }
+            ]
+            "###);
+            snapshot!(response["id"], @"null");
+            snapshot!(response["estimatedTotalHits"], @"3");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn keywords_without_embedders() {
+    // searching by keywords doesn't require the vector store
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "dragon knight castle" },
+        { "id": 2, "title": "dragon knight castle princess" },
+        { "id": 3, "title": "dragon cooking" },
+        { "id": 4, "title": "cooking recipes" },
+        { "id": 5, "title": "gardening tips" },
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    index
+        .similar(json!({"id": 1}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "title": "dragon knight castle princess"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // the words of the negative documents are never searched
+    index
+        .similar(json!({"id": 3, "negativeIds": [1]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 4,
+                "title": "cooking recipes"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // the query rules don't apply to the terms extracted from the documents
+    let (response, code) = index
+        .update_settings(json!({
+            "rules": [
+                { "id": "everything", "actions": { "pin": [{ "id": "5", "position": 0 }], "hide": ["2"] } }
+            ]
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await.succeeded();
+
+    index
+        .similar(json!({"id": 1}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "title": "dragon knight castle princess"
+              }
+            ]
+            "###);
+        })
+        .await;
+}
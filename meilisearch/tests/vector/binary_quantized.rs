@@ -0,0 +1,113 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{GetAllDocumentsOptions, Server, Value};
+use crate::json;
+use crate::vector::user_provided_index;
+
+fn doggos() -> Value {
+    json!([
+      {"id": 0, "name": "kefir", "_vectors": { "manual": [1, 0.5, 1] }},
+      {"id": 1, "name": "echo", "_vectors": { "manual": [-1, -0.5, -1] }},
+      {"id": 2, "name": "intel", "_vectors": { "manual": [1, -0.5, 1] }},
+    ])
+}
+
+#[actix_rt::test]
+async fn binary_quantize_before_sending_documents() {
+    let server = Server::new().await;
+    let index = user_provided_index(&server, json!({ "binaryQuantized": true }), doggos()).await;
+
+    let (settings, _code) = index.settings().await;
+    snapshot!(json_string!(settings["embedders"]), @r###"
+    {
+      "manual": {
+        "source": "userProvided",
+        "dimensions": 3,
+        "binaryQuantized": true
+      }
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"vector": [1, 1, 1] })).await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"].as_array().unwrap().iter().map(|hit| &hit["id"]).collect();
+    snapshot!(json_string!(ids), @"[0, 2, 1]");
+
+    let (response, code) = index.similar_post(json!({"id": 0, "embedder": "manual"})).await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"].as_array().unwrap().iter().map(|hit| &hit["id"]).collect();
+    snapshot!(json_string!(ids), @"[2, 1]");
+}
+
+#[actix_rt::test]
+async fn binary_quantize_existing_documents() {
+    let server = Server::new().await;
+    let index = user_provided_index(&server, json!({ "binaryQuantized": false }), doggos()).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "binaryQuantized": true,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({"vector": [1, 1, 1] })).await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"].as_array().unwrap().iter().map(|hit| &hit["id"]).collect();
+    snapshot!(json_string!(ids), @"[0, 2, 1]");
+
+    // the documents can still be updated once quantized
+    let (value, code) = index
+        .add_documents(
+            json!([{"id": 1, "name": "echo", "_vectors": { "manual": [1, 1, 1] }}]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    // both documents now have the same quantized embedding
+    let (response, code) = index.search_post(json!({"vector": [1, 1, 1], "limit": 2 })).await;
+    snapshot!(code, @"200 OK");
+    let mut ids: Vec<_> =
+        response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64()).collect();
+    ids.sort();
+    snapshot!(format!("{ids:?}"), @"[Some(0), Some(1)]");
+
+    let (documents, _code) = index
+        .get_all_documents(GetAllDocumentsOptions { retrieve_vectors: true, ..Default::default() })
+        .await;
+    snapshot!(documents["results"].as_array().unwrap().len(), @"3");
+}
+
+#[actix_rt::test]
+async fn try_to_disable_binary_quantization() {
+    let server = Server::new().await;
+    let index = user_provided_index(&server, json!({ "binaryQuantized": true }), doggos()).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "binaryQuantized": false,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.manual.binaryQuantized`: Cannot disable the binary quantization.\n - Note: Binary quantization is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that is non-quantized and regenerate the vectors.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::Ordering;
+
+use meili_snap::{json_string, snapshot};
+
+use super::mock_embedder;
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn resent_documents_hit_the_cache() {
+    let server = Server::new().await;
+    let (url, calls) = mock_embedder().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": url,
+                  "dimensions": 3,
+                  "documentTemplate": "{{doc.name}}",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir"},
+      {"id": 1, "name": "intel"},
+    ]);
+    let (value, code) = index.add_documents(documents.clone(), None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+    snapshot!(calls.load(Ordering::SeqCst), @"2");
+
+    let (stats, code) = index.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(stats["embeddingCache"]), @r###"
+    {
+      "entries": 2,
+      "hits": 0,
+      "misses": 2
+    }
+    "###);
+
+    // the cache outlives the documents, so sending them again doesn't call the embedder
+    let (value, _code) = index.clear_all_documents().await;
+    index.wait_task(value.uid()).await.succeeded();
+    let (value, code) = index.add_documents(documents.clone(), None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+    snapshot!(calls.load(Ordering::SeqCst), @"2");
+
+    let (stats, _code) = index.stats().await;
+    snapshot!(json_string!(stats["embeddingCache"]), @r###"
+    {
+      "entries": 2,
+      "hits": 2,
+      "misses": 2
+    }
+    "###);
+
+    // once the cache is cleared, the embeddings are generated again
+    let (value, code) = index.clear_embedding_cache().await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await;
+    task.succeeded();
+    snapshot!(task["type"], @r###""embeddingCacheClear""###);
+
+    let (stats, _code) = index.stats().await;
+    snapshot!(json_string!(stats["embeddingCache"]), @"null");
+
+    let (value, _code) = index.clear_all_documents().await;
+    index.wait_task(value.uid()).await.succeeded();
+    let (value, _code) = index.add_documents(documents, None).await;
+    index.wait_task(value.uid()).await.succeeded();
+    snapshot!(calls.load(Ordering::SeqCst), @"4");
+}
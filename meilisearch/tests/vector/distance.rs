@@ -0,0 +1,116 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::index::Index;
+use crate::common::{Server, Value};
+use crate::json;
+use crate::vector::user_provided_index;
+
+fn doggos() -> Value {
+    // `big` points in the same direction as the query but is far from it,
+    // `close` points in a slightly different direction but is near it,
+    // `opposite` points in the opposite direction but is nearer than `big`.
+    json!([
+      {"id": 0, "name": "big", "_vectors": { "manual": [10, 10, 10] }},
+      {"id": 1, "name": "close", "_vectors": { "manual": [1, 1, 0.5] }},
+      {"id": 2, "name": "opposite", "_vectors": { "manual": [-1, -1, -1] }},
+    ])
+}
+
+async fn search_ids(index: &Index) -> String {
+    let (response, code) =
+        index.search_post(json!({"vector": [1, 1, 1], "showRankingScore": true })).await;
+    snapshot!(code, @"200 OK");
+    let hits = response["hits"].as_array().unwrap();
+    let scores: Vec<_> = hits.iter().map(|hit| hit["_rankingScore"].as_f64().unwrap()).collect();
+    assert!(scores.iter().all(|score| (0.0..=1.0).contains(score)), "{scores:?}");
+    let ids: Vec<_> = hits.iter().map(|hit| &hit["id"]).collect();
+    json_string!(ids).to_string()
+}
+
+#[actix_rt::test]
+async fn euclidean_distance() {
+    let server = Server::new().await;
+    let index = user_provided_index(&server, json!({ "distance": "euclidean" }), doggos()).await;
+
+    let (settings, _code) = index.settings().await;
+    snapshot!(json_string!(settings["embedders"]), @r###"
+    {
+      "manual": {
+        "source": "userProvided",
+        "dimensions": 3,
+        "distance": "euclidean"
+      }
+    }
+    "###);
+
+    snapshot!(search_ids(&index).await, @"[1, 2, 0]");
+}
+
+#[actix_rt::test]
+async fn change_distance_of_existing_documents() {
+    let server = Server::new().await;
+    let index = user_provided_index(&server, json!({ "distance": "cosine" }), doggos()).await;
+    snapshot!(search_ids(&index).await, @"[0, 1, 2]");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "distance": "manhattan",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+    snapshot!(search_ids(&index).await, @"[1, 2, 0]");
+
+    // the embeddings can still be binary quantized after a distance change
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "distance": "euclidean",
+                  "binaryQuantized": true,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) = index.search_post(json!({"vector": [1, 1, 1] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"].as_array().unwrap().len(), @"3");
+}
+
+#[actix_rt::test]
+async fn binary_quantize_dot_product() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "distance": "dotProduct",
+                  "binaryQuantized": true,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.manual.binaryQuantized`: Cannot binary quantize an embedder using the `dotProduct` distance.\n - Hint: Use the `cosine`, `euclidean` or `manhattan` distance, or disable the binary quantization.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
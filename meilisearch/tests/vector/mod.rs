@@ -1,11 +1,51 @@
+mod binary_quantized;
+mod cache;
+mod distance;
+mod render;
+mod rerank;
 mod settings;
+mod sparse;
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
 use meili_snap::{json_string, snapshot};
 
 use crate::common::index::Index;
-use crate::common::{GetAllDocumentsOptions, Server};
+use crate::common::{GetAllDocumentsOptions, Server, Value};
 use crate::json;
 
+/// Starts a REST embedder embedding each text by its length, and counting the texts it embedded.
+///
+/// The texts containing `fail` are rejected.
+pub async fn mock_embedder() -> (String, Arc<AtomicUsize>) {
+    async fn embed(
+        body: web::Json<serde_json::Value>,
+        calls: web::Data<Arc<AtomicUsize>>,
+    ) -> HttpResponse {
+        calls.fetch_add(1, Ordering::SeqCst);
+        let text = body["input"].as_str().unwrap();
+        if text.contains("fail") {
+            return HttpResponse::BadRequest().json(json!({ "error": "cannot embed this text" }));
+        }
+        let len = text.len() as f32;
+        HttpResponse::Ok().json(json!({ "data": { "embedding": [1.0, len, 0.0] } }))
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let data = web::Data::new(calls.clone());
+    let server = HttpServer::new(move || {
+        App::new().app_data(data.clone()).route("/embed", web::post().to(embed))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let url = format!("http://{}/embed", server.addrs()[0]);
+    actix_rt::spawn(server.run());
+    (url, calls)
+}
+
 #[actix_rt::test]
 async fn add_remove_user_provided() {
     let server = Server::new().await;
@@ -204,6 +244,27 @@ async fn generate_default_user_provided_documents(server: &Server) -> Index {
     index
 }
 
+/// Creates an index with a 3 dimensions `manual` embedder, completed with the `setting` fields,
+/// and sends it the `documents`.
+async fn user_provided_index(server: &Server, setting: Value, documents: Value) -> Index {
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let mut embedder = json!({ "source": "userProvided", "dimensions": 3 });
+    embedder.as_object_mut().unwrap().extend(setting.as_object().unwrap().clone());
+    let (response, code) =
+        index.update_settings(json!({ "embedders": { "manual": embedder } })).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    index
+}
+
 #[actix_rt::test]
 async fn user_provided_embeddings_error() {
     let server = Server::new().await;
@@ -700,3 +761,82 @@ async fn add_remove_one_vector_4588() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn best_matching_embedding() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "_vectors": { "manual": [[1, 0, 0], [0, 1, 0], [0, 0, 1]] }},
+      {"id": 1, "name": "echo", "_vectors": { "manual": [1, 1, 0] }},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({"vector": [0, 0, 1], "limit": 1, "showRankingScoreDetails": true }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 0,
+        "name": "kefir",
+        "_rankingScoreDetails": {
+          "vectorSort": {
+            "order": 0,
+            "similarity": 1.0,
+            "embeddingIndex": 0
+          }
+        }
+      }
+    ]
+    "###);
+
+    // the embeddings are stored sorted, the index is their position in the retrieved vectors
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [0, 0, 1],
+            "limit": 1,
+            "retrieveVectors": true,
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hit = &response["hits"][0];
+    let embedding_index = hit["_rankingScoreDetails"]["vectorSort"]["embeddingIndex"].as_u64();
+    let embedding = &hit["_vectors"]["manual"]["embeddings"][embedding_index.unwrap() as usize];
+    snapshot!(json_string!(embedding), @r###"
+    [
+      0.0,
+      0.0,
+      1.0
+    ]
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"vector": [0, 1, 0], "showRankingScoreDetails": true })).await;
+    snapshot!(code, @"200 OK");
+    let hits = response["hits"].as_array().unwrap();
+    snapshot!(hits[0]["id"], @"0");
+    snapshot!(hits[0]["_rankingScoreDetails"]["vectorSort"]["embeddingIndex"], @"1");
+    snapshot!(hits[1]["id"], @"1");
+    snapshot!(hits[1]["_rankingScoreDetails"]["vectorSort"]["embeddingIndex"], @"0");
+}
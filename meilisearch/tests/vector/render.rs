@@ -0,0 +1,147 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::index::Index;
+use crate::common::Server;
+use crate::json;
+
+async fn render_index(server: &Server) -> Index {
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "breed": { "name": "labrador" }, "_vectors": { "manual": [0, 0, 1] }},
+      {"id": 1, "name": "echo", "_vectors": { "manual": null }},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    index
+}
+
+#[actix_rt::test]
+async fn render_document_template() {
+    let server = Server::new().await;
+    let index = render_index(&server).await;
+
+    let (response, code) = index
+        .render(json!({
+            "ids": [0, "1"],
+            "documentTemplate": "{{doc.name}} is a {{doc.breed.name | default: \"good\"}} dog"
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".prompts[].tokens" => "[tokens]" }), @r###"
+    {
+      "documentTemplate": "{{doc.name}} is a {{doc.breed.name | default: \"good\"}} dog",
+      "prompts": [
+        {
+          "id": "0",
+          "prompt": "kefir is a labrador dog",
+          "tokens": "[tokens]"
+        },
+        {
+          "id": "1",
+          "prompt": "echo is a good dog",
+          "tokens": "[tokens]"
+        }
+      ]
+    }
+    "###);
+    assert!(response["prompts"][0]["tokens"].as_u64().unwrap() > 0);
+
+    // rendering does not enqueue any task
+    let (response, code) = server.tasks().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["total"], @"2");
+}
+
+#[actix_rt::test]
+async fn render_embedder_template() {
+    let server = Server::new().await;
+    let index = render_index(&server).await;
+
+    let (response, code) = index.render(json!({ "ids": [0] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["documentTemplate"], @r###""{% for field in fields %} {{ field.name }}: {{ field.value }}\n{% endfor %}""###);
+    snapshot!(response["prompts"][0]["id"], @r###""0""###);
+
+    let (response, code) = index.render(json!({ "ids": [0], "embedder": "manual" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["prompts"][0]["id"], @r###""0""###);
+}
+
+#[actix_rt::test]
+async fn render_errors() {
+    let server = Server::new().await;
+    let index = render_index(&server).await;
+
+    let (response, code) = index.render(json!({ "documentTemplate": "{{doc.name}}" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `ids`: at least one document id must be given.",
+      "code": "invalid_render_ids",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_render_ids"
+    }
+    "###);
+
+    let (response, code) =
+        index.render(json!({ "ids": [2], "documentTemplate": "{{doc.name}}" })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Document `2` not found.",
+      "code": "document_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#document_not_found"
+    }
+    "###);
+
+    let (response, code) =
+        index.render(json!({ "ids": [0], "documentTemplate": "{{name}}" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_render_document_template""###);
+
+    let (response, code) = index
+        .render(json!({ "ids": [0], "documentTemplate": "{{doc.name}}", "embedder": "manual" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`documentTemplate` and `embedder` cannot be used together.\n - Hint: Remove `embedder` to render the given `documentTemplate`.",
+      "code": "invalid_render_document_template",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_render_document_template"
+    }
+    "###);
+
+    let (response, code) = index.render(json!({ "ids": [0], "embedder": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_embedder""###);
+}
+
+#[actix_rt::test]
+async fn render_feature_disabled() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index.render(json!({ "ids": [0] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""feature_not_enabled""###);
+}
@@ -0,0 +1,276 @@
+use actix_web::{web, App, HttpResponse, HttpServer};
+use meili_snap::{json_string, snapshot};
+
+use crate::common::index::Index;
+use crate::common::Server;
+use crate::json;
+
+/// Starts a reranker scoring each document by the number it is rendered to.
+async fn mock_reranker() -> String {
+    async fn rerank(body: web::Json<serde_json::Value>) -> HttpResponse {
+        let documents = body["documents"].as_array().unwrap();
+        let results: Vec<_> = documents
+            .iter()
+            .enumerate()
+            .map(|(index, document)| {
+                let score: f64 = document.as_str().unwrap().trim().parse().unwrap();
+                json!({ "index": index, "relevance_score": score })
+            })
+            .collect();
+        HttpResponse::Ok().json(json!({ "query": body["query"], "results": results }))
+    }
+
+    let server = HttpServer::new(|| App::new().route("/rerank", web::post().to(rerank)))
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+    let url = format!("http://{}/rerank", server.addrs()[0]);
+    actix_rt::spawn(server.run());
+    url
+}
+
+async fn rerank_index(server: &Server, url: &str) -> Index {
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "sortableAttributes": ["id"],
+          "rerankers": {
+              "cross": {
+                  "url": url,
+                  "documentTemplate": "{{doc.score}}"
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "doggo kefir", "score": 0.1},
+      {"id": 1, "name": "doggo intel", "score": 0.9},
+      {"id": 2, "name": "doggo echo", "score": 0.5},
+      {"id": 3, "name": "doggo max", "score": 0.7},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    index
+}
+
+#[actix_rt::test]
+async fn rerank_top_hits() {
+    let server = Server::new().await;
+    let url = mock_reranker().await;
+    let index = rerank_index(&server, &url).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "doggo",
+            "sort": ["id:asc"],
+            "rerank": { "topK": 3 },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 2
+      },
+      {
+        "id": 0
+      },
+      {
+        "id": 3
+      }
+    ]
+    "###);
+
+    // the requested page is cut from the reranked hits
+    let (response, code) = index
+        .search_post(json!({
+            "q": "doggo",
+            "sort": ["id:asc"],
+            "rerank": { "reranker": "cross", "topK": 3 },
+            "offset": 1,
+            "limit": 2,
+            "attributesToRetrieve": ["id"],
+            "showRankingScore": true,
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"][0]["id"]), @"2");
+    snapshot!(json_string!(response["hits"][1]["id"]), @"0");
+    snapshot!(json_string!(response["hits"][0]["_rankingScore"]), @"0.5");
+    snapshot!(json_string!(response["hits"][0]["_rankingScoreDetails"]["rerank"]), @r###"
+    {
+      "order": 0,
+      "retrievalRank": 3,
+      "score": 0.5
+    }
+    "###);
+    snapshot!(response["offset"], @"1");
+    snapshot!(response["limit"], @"2");
+}
+
+#[actix_rt::test]
+async fn rerank_keeps_pinned_documents() {
+    let server = Server::new().await;
+    let url = mock_reranker().await;
+    let index = rerank_index(&server, &url).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+            "rules": [
+                {
+                    "id": "pin-kefir",
+                    "conditions": { "query": { "pattern": "doggo" } },
+                    "actions": { "pin": [{ "id": "0", "position": 1 }] }
+                }
+            ]
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    // only the organic hits are reranked, the lowest scored document stays pinned
+    let (response, code) = index
+        .search_post(json!({
+            "q": "doggo",
+            "sort": ["id:asc"],
+            "rerank": { "topK": 4 },
+            "attributesToRetrieve": ["id"],
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].as_u64().unwrap())
+        .collect();
+    snapshot!(format!("{ids:?}"), @"[1, 0, 3, 2]");
+    snapshot!(json_string!(response["hits"][1]["_rankingScoreDetails"]["rerank"]), @"null");
+    snapshot!(json_string!(response["hits"][2]["_rankingScoreDetails"]["rerank"]), @r###"
+    {
+      "order": 0,
+      "retrievalRank": 4,
+      "score": 0.7
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn rerank_errors() {
+    let server = Server::new().await;
+    let url = mock_reranker().await;
+    let index = rerank_index(&server, &url).await;
+
+    let (response, code) = index.search_post(json!({ "rerank": {} })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`rerank` requires a `q` to score the hits against.",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "q": "doggo", "rerank": { "reranker": "colbert" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Cannot find reranker with name `colbert`.",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "q": "doggo", "rerank": { "topK": 0 } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""`rerank.topK` must be greater than 0.""###);
+
+    // all the reranked hits are collapsed
+    let (response, code) = index
+        .search_post(json!({
+            "q": "doggo",
+            "rerank": { "topK": 1000 },
+            "collapse": { "attribute": "id", "innerHits": 1 },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Collapsing 1000 hits with 1 inner hits each returns 2000 documents, the maximum is 1000.\n - Hint: reduce `limit`, `rerank.topK` or `collapse.innerHits`.",
+      "code": "invalid_search_collapse",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_collapse"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [{ "indexUid": "doggo", "q": "doggo", "rerank": {} }]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_rerank""###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "rerankers": { "cross": { "url": url, "scoreField": [] } },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`.rerankers.cross`: `scoreField` cannot be empty",
+      "code": "invalid_settings_rerankers",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_rerankers"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn rerank_unreachable_reranker() {
+    let server = Server::new().await;
+    let index = rerank_index(&server, "http://127.0.0.1:1/rerank").await;
+
+    let (response, code) = index.search_post(json!({ "q": "doggo", "rerank": {} })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""reranking_error""###);
+}
+
+#[actix_rt::test]
+async fn rerank_feature_disabled() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (response, code) = index.create(None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .update_settings(json!({ "rerankers": { "cross": { "url": "http://localhost:7777" } } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""feature_not_enabled""###);
+
+    let (response, code) = index.search_post(json!({ "q": "doggo", "rerank": {} })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""feature_not_enabled""###);
+}
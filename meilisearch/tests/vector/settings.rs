@@ -1,8 +1,10 @@
 use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+use tempfile::TempDir;
 
-use crate::common::{GetAllDocumentsOptions, Server};
+use crate::common::{default_settings, GetAllDocumentsOptions, Server};
 use crate::json;
-use crate::vector::generate_default_user_provided_documents;
+use crate::vector::{generate_default_user_provided_documents, mock_embedder};
 
 #[actix_rt::test]
 async fn update_embedder() {
@@ -228,3 +230,367 @@ async fn reset_embedder_documents() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn hugging_face_local_model_without_weights() {
+    let temp = TempDir::new().unwrap();
+    let models_dir = TempDir::new().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_local_models_dir: Some(models_dir.path().to_owned()),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let model_dir = models_dir.path().join("bge");
+    std::fs::create_dir(&model_dir).unwrap();
+    let model_path = model_dir.canonicalize().unwrap().to_str().unwrap().to_owned();
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "local": {
+                  "source": "huggingFace",
+                  "modelPath": model_path,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(ret["error"]["code"], @r###""vector_embedding_error""###);
+    let message = ret["error"]["message"].as_str().unwrap().replace(&model_path, "[path]");
+    snapshot!(message, @"Error while generating embeddings: user error: could not find `model.safetensors` nor `pytorch_model.bin` weights in `[path]`");
+}
+
+#[actix_rt::test]
+async fn hugging_face_local_model_outside_models_dir() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let model_dir = TempDir::new().unwrap();
+    let model_path = model_dir.path().to_str().unwrap().to_owned();
+    let settings = json!({
+      "embedders": { "local": { "source": "huggingFace", "modelPath": model_path } },
+    });
+
+    let (response, code) = index.update_settings(settings.clone()).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`.embedders.local`: `modelPath` requires launching Meilisearch with `--experimental-local-models-dir`.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let temp = TempDir::new().unwrap();
+    let models_dir = TempDir::new().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_local_models_dir: Some(models_dir.path().to_owned()),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index.update_settings(settings).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`.embedders.local`: `modelPath` must be an existing directory inside the directory passed to `--experimental-local-models-dir`.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    // escaping the models directory is rejected too
+    let escaped = models_dir.path().join("..").join("..");
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "local": { "source": "huggingFace", "modelPath": escaped.to_str().unwrap() }
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_embedders""###);
+}
+
+#[actix_rt::test]
+async fn model_path_unavailable_for_other_sources() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "modelPath": "/models/bge",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.manual`: Field `modelPath` unavailable for source `userProvided` (only available for sources: `huggingFace`). Available fields: `source`, `dimensions`, `distribution`, `binaryQuantized`, `distance`, `onError`, `sparse`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn chunking_unavailable_for_user_provided() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "chunking": { "size": 128 },
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.manual`: Field `chunking` unavailable for source `userProvided` (only available for sources: `huggingFace`, `ollama`, `openAi`, `rest`). Available fields: `source`, `dimensions`, `distribution`, `binaryQuantized`, `distance`, `onError`, `sparse`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn chunking_overlap_must_be_smaller_than_size() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": "http://localhost:1337",
+                  "dimensions": 3,
+                  "chunking": { "size": 64, "overlap": 64 },
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.rest.chunking`: Cannot split documents in chunks of `64` tokens overlapping by `64` tokens.\n - Hint: Use a `size` greater than zero and an `overlap` smaller than the `size`.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_embedder_model_unavailable_for_rest() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": "http://localhost:1337",
+                  "dimensions": 3,
+                  "searchEmbedder": { "model": "query-model" },
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.rest`: Field `searchEmbedder.model` unavailable for source `rest` (only available for sources: `huggingFace`, `openAi`, `ollama`). Available fields: `source`, `apiKey`, `dimensions`, `documentTemplate`, `url`, `query`, `inputField`, `pathToEmbeddings`, `embeddingObject`, `inputType`, `headers`, `maxBatchSize`, `maxConcurrentRequests`, `distribution`, `binaryQuantized`, `distance`, `chunking`, `searchEmbedder`, `onError`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn rest_max_batch_size_cannot_be_zero() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": "http://localhost:1337",
+                  "dimensions": 3,
+                  "headers": { "X-Tenant-Id": "doggos" },
+                  "maxBatchSize": 0,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.rest.maxBatchSize`: `maxBatchSize` cannot be zero",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn unknown_on_error_strategy() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "onError": "skipDocuments",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `skipDocuments` at `.embedders.manual.onError`: expected one of `fail`, `skipVectors`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+                  "onError": "skipVectors",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""succeeded""###);
+}
+
+#[actix_rt::test]
+async fn skipped_vectors_are_reported_on_the_tasks() {
+    let server = Server::new().await;
+    let (url, _calls) = mock_embedder().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": url,
+                  "dimensions": 3,
+                  "documentTemplate": "{{doc.name}}",
+                  "onError": "skipVectors",
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir"},
+      {"id": 1, "name": "fail"},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await;
+    task.succeeded();
+    snapshot!(json_string!(task["details"], { ".embeddingFailures[].reason" => "[reason]" }), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 2,
+      "embeddingFailures": [
+        {
+          "embedderName": "rest",
+          "documentId": "1",
+          "reason": "[reason]"
+        }
+      ]
+    }
+    "###);
+
+    // the documents reindexed by a settings update are reported on the settings update
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "rest": { "documentTemplate": "a doggo named {{doc.name}}" } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await;
+    task.succeeded();
+    snapshot!(json_string!(task["details"]["embeddingFailures"], { "[].reason" => "[reason]" }), @r###"
+    [
+      {
+        "embedderName": "rest",
+        "documentId": "1",
+        "reason": "[reason]"
+      }
+    ]
+    "###);
+}
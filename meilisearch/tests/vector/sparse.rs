@@ -0,0 +1,232 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::index::Index;
+use crate::common::Server;
+use crate::json;
+
+async fn sparse_index(server: &Server) -> Index {
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "userProvided",
+                  "sparse": true,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "_vectors": { "sparse": { "dog": 1.0, "cat": 0.5 } }},
+      {"id": 1, "name": "echo", "_vectors": { "sparse": { "cat": 2.0 } }},
+      {"id": 2, "name": "intel", "_vectors": { "sparse": null }},
+    ]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    index
+}
+
+#[actix_rt::test]
+async fn sparse_embedder_settings() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    let (_value, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "userProvided",
+                  "sparse": true,
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.sparse.dimensions`: `dimensions` is unavailable for sparse embedders",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "source": "userProvided",
+                  "sparse": true,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (settings, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(settings["embedders"]), @r###"
+    {
+      "sparse": {
+        "source": "userProvided",
+        "sparse": true
+      }
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "sparse": {
+                  "sparse": false,
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let ret = server.wait_task(response.uid()).await;
+    snapshot!(ret["status"], @r###""failed""###);
+    snapshot!(json_string!(ret["error"]), @r###"
+    {
+      "message": "`.embedders.sparse.sparse`: Cannot change whether an existing embedder is sparse.\n - Hint: Add a new embedder instead.",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn sparse_vectors_error() {
+    let server = Server::new().await;
+    let index = sparse_index(&server).await;
+
+    let documents = json!({"id": 3, "name": "bob", "_vectors": { "sparse": [1, 2] }});
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(json_string!(task["error"]), @r###"
+    {
+      "message": "Bad sparse vector for embedder `sparse` in the document with id: `\"3\"`. Expected an object mapping tokens to their weight.",
+      "code": "invalid_vectors_type",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_vectors_type"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn sparse_vector_search() {
+    let server = Server::new().await;
+    let index = sparse_index(&server).await;
+
+    let (response, code) = index.search_post(json!({"sparseVector": { "cat": 1.0 } })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "echo"
+      },
+      {
+        "id": 0,
+        "name": "kefir"
+      },
+      {
+        "id": 2,
+        "name": "intel"
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "sparseVector": { "dog": 1.0 },
+            "hybrid": { "semanticRatio": 0.5, "embedder": "sparse" }
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"][0]["id"], @"0");
+
+    // the weights of a document are updated
+    let documents = json!({"id": 1, "name": "echo", "_vectors": { "sparse": { "dog": 3.0 } }});
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({"sparseVector": { "cat": 1.0 } })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 0,
+        "name": "kefir"
+      },
+      {
+        "id": 1,
+        "name": "echo"
+      },
+      {
+        "id": 2,
+        "name": "intel"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn sparse_vector_search_error() {
+    let server = Server::new().await;
+    let index = sparse_index(&server).await;
+
+    let (response, code) = index.search_post(json!({"vector": [1, 0, 0] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`vector` cannot be used with the sparse embedder `sparse`.\n - Hint: Use `sparseVector` instead.",
+      "code": "invalid_search_vector",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_vector"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"vector": [1, 0, 0], "sparseVector": { "cat": 1.0 } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`vector` and `sparseVector` cannot be used together in the same search.",
+      "code": "invalid_search_sparse_vector",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sparse_vector"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"sparseVector": { "cat": "a lot" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.sparseVector.cat`: expected a number, but found a string: `\"a lot\"`",
+      "code": "invalid_search_sparse_vector",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sparse_vector"
+    }
+    "###);
+}
@@ -122,8 +122,14 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     InvalidVectorsMapType { document_id: String, value: Value },
     #[error("Bad embedder configuration in the document with id: `{document_id}`. {error}")]
     InvalidVectorsEmbedderConf { document_id: String, error: deserr::errors::JsonError },
+    #[error("Bad sparse vector for embedder `{embedder_name}` in the document with id: `{document_id}`. {reason}")]
+    InvalidSparseVectors { document_id: String, embedder_name: String, reason: String },
     #[error("{0}")]
     InvalidFilter(String),
+    #[error("{0}")]
+    InvalidBoostFilter(String),
+    #[error("Invalid search cursor `{cursor}`. The cursor must be the `nextCursor` returned by a previous search.")]
+    InvalidSearchCursor { cursor: String },
     #[error("Invalid type for filter subexpression: expected: {}, found: {1}.", .0.join(", "))]
     InvalidFilterExpression(&'static [&'static str], Value),
     #[error("Attribute `{}` is not sortable. {}",
@@ -148,6 +154,17 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         }
     )]
     InvalidDistinctAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
+    #[error("Attribute `{}` is not filterable and thus, cannot be used to collapse the search results. {}",
+        .field,
+        match .valid_fields.is_empty() {
+            true => "This index does not have configured filterable attributes.".to_string(),
+            false => format!("Available filterable attributes are: `{}{}`.",
+                    valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+                    .hidden_fields.then_some(", <..hidden-attributes>").unwrap_or(""),
+                ),
+        }
+    )]
+    InvalidCollapseAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
     #[error("Attribute `{}` is not facet-searchable. {}",
         .field,
         match .valid_fields.is_empty() {
@@ -258,6 +275,26 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     },
     #[error("`.embedders.{embedder_name}.dimensions`: `dimensions` cannot be zero")]
     InvalidSettingsDimensions { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.binaryQuantized`: Cannot disable the binary quantization.\n - Note: Binary quantization is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that is non-quantized and regenerate the vectors.")]
+    InvalidDisableBinaryQuantization { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.binaryQuantized`: Cannot binary quantize an embedder using the `{distance}` distance.\n - Hint: Use the `cosine`, `euclidean` or `manhattan` distance, or disable the binary quantization.")]
+    InvalidBinaryQuantizedDistance { embedder_name: String, distance: crate::vector::Distance },
+    #[error("`.embedders.{embedder_name}.chunking`: Cannot split documents in chunks of `{size}` tokens overlapping by `{overlap}` tokens.\n - Hint: Use a `size` greater than zero and an `overlap` smaller than the `size`.")]
+    InvalidChunking { embedder_name: String, size: usize, overlap: usize },
+    #[error("`.embedders.{embedder_name}.{field}`: `{field}` cannot be zero")]
+    InvalidRestRequestLimit { embedder_name: String, field: &'static str },
+    #[error("`.embedders.{embedder_name}.{field}`: `{field}` is unavailable for sparse embedders")]
+    InvalidSparseEmbedderField { embedder_name: String, field: &'static str },
+    #[error("`.embedders.{embedder_name}.sparse`: Cannot change whether an existing embedder is sparse.\n - Hint: Add a new embedder instead.")]
+    InvalidSparseChange { embedder_name: String },
+    #[error("Invalid query rule `{id}`: {reason}")]
+    InvalidQueryRule { id: String, reason: String },
+    #[error("`.rerankers.{reranker_name}`: {reason}")]
+    InvalidReranker { reranker_name: String, reason: String },
+    #[error("Cannot find reranker with name `{0}`.")]
+    InvalidRerankerName(String),
+    #[error("Error while reranking with `{reranker_name}`: {reason}")]
+    RerankingFailed { reranker_name: String, reason: String },
     #[error("`.embedders.{embedder_name}.url`: could not parse `{url}`: {inner_error}")]
     InvalidUrl { embedder_name: String, inner_error: url::ParseError, url: String },
     #[error("Document editions cannot modify a document's primary key")]
@@ -296,7 +333,8 @@ impl From<arroy::Error> for Error {
             | arroy::Error::UnmatchingDistance { .. }
             | arroy::Error::NeedBuild(_)
             | arroy::Error::MissingKey { .. }
-            | arroy::Error::MissingMetadata(_) => {
+            | arroy::Error::MissingMetadata(_)
+            | arroy::Error::CannotDecodeKeyMode { .. } => {
                 Error::InternalError(InternalError::ArroyError(value))
             }
         }
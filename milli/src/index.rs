@@ -24,13 +24,15 @@ use crate::heed_codec::{
 };
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
+use crate::vector::cache::{CachePrefix, EmbeddingCacheStats};
 use crate::vector::parsed_vectors::RESERVED_VECTORS_FIELD_NAME;
-use crate::vector::{Embedding, EmbeddingConfig};
+use crate::vector::sparse::SparseVectorStore;
+use crate::vector::{ArroyWrapper, Embedding, EmbeddingConfig};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldIdWordCountCodec,
-    FieldidsWeightsMap, GeoPoint, ObkvCodec, Result, RoaringBitmapCodec, RoaringBitmapLenCodec,
-    Search, U8StrStrCodec, Weight, BEU16, BEU32, BEU64,
+    FieldidsWeightsMap, GeoPoint, ObkvCodec, QueryRule, RerankerSettings, Result,
+    RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight, BEU16, BEU32, BEU64,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
@@ -73,6 +75,9 @@ pub mod main_key {
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
+    pub const QUERY_RULES: &str = "query_rules";
+    pub const RERANKERS: &str = "rerankers";
+    pub const EMBEDDING_CACHE_STATS: &str = "embedding-cache-stats";
 }
 
 pub mod db_name {
@@ -100,6 +105,8 @@ pub mod db_name {
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
+    pub const EMBEDDING_CACHE: &str = "embedding-cache";
+    pub const SPARSE_VECTOR_POSTINGS: &str = "sparse-vector-postings";
     pub const DOCUMENTS: &str = "documents";
     pub const SCRIPT_LANGUAGE_DOCIDS: &str = "script_language_docids";
 }
@@ -169,7 +176,11 @@ pub struct Index {
     /// Maps an embedder name to its id in the arroy store.
     pub embedder_category_id: Database<Str, U8>,
     /// Vector store based on arroy™.
-    pub vector_arroy: arroy::Database<arroy::distances::Angular>,
+    pub vector_arroy: arroy::Database<arroy::Unspecified>,
+    /// Maps the hash of the options of an embedder and of a rendered prompt to the embeddings of the prompt.
+    pub embedding_cache: Database<Bytes, Bytes>,
+    /// Maps the id of a sparse embedder, a token and a document id to the weight of the token in the document.
+    pub sparse_vector_postings: Database<Bytes, Bytes>,
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
@@ -184,7 +195,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(27);
 
         let env = unsafe { options.open(path) }?;
         let mut wtxn = env.write_txn()?;
@@ -228,6 +239,9 @@ impl Index {
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
+        let embedding_cache = env.create_database(&mut wtxn, Some(EMBEDDING_CACHE))?;
+        let sparse_vector_postings =
+            env.create_database(&mut wtxn, Some(SPARSE_VECTOR_POSTINGS))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
         wtxn.commit()?;
@@ -260,6 +274,8 @@ impl Index {
             field_id_docid_facet_strings,
             vector_arroy,
             embedder_category_id,
+            embedding_cache,
+            sparse_vector_postings,
             documents,
         })
     }
@@ -1632,20 +1648,32 @@ impl Index {
             .unwrap_or_default())
     }
 
-    pub fn arroy_readers<'a>(
-        &'a self,
-        rtxn: &'a RoTxn<'a>,
-        embedder_id: u8,
-    ) -> impl Iterator<Item = Result<arroy::Reader<'a, arroy::distances::Angular>>> + 'a {
-        crate::vector::arroy_db_range_for_embedder(embedder_id).map_while(move |k| {
-            arroy::Reader::open(rtxn, k, self.vector_arroy)
-                .map(Some)
-                .or_else(|e| match e {
-                    arroy::Error::MissingMetadata(_) => Ok(None),
-                    e => Err(e.into()),
-                })
-                .transpose()
-        })
+    /// Returns the [`ArroyWrapper`] giving access to the embeddings of the given embedder.
+    pub fn arroy_wrapper(&self, rtxn: &RoTxn<'_>, embedder_name: &str) -> Result<ArroyWrapper> {
+        let embedder_index = self
+            .embedder_category_id
+            .get(rtxn, embedder_name)?
+            .ok_or_else(|| UserError::InvalidEmbedder(embedder_name.to_owned()))?;
+        let options = self
+            .embedding_configs(rtxn)?
+            .iter()
+            .find(|config| config.name == embedder_name)
+            .map(|config| config.config.arroy_options())
+            .unwrap_or_default();
+        Ok(ArroyWrapper::new(self.vector_arroy, embedder_index, options))
+    }
+
+    /// Returns the [`SparseVectorStore`] giving access to the sparse vectors of the given embedder.
+    pub fn sparse_vector_store(
+        &self,
+        rtxn: &RoTxn<'_>,
+        embedder_name: &str,
+    ) -> Result<SparseVectorStore> {
+        let embedder_index = self
+            .embedder_category_id
+            .get(rtxn, embedder_name)?
+            .ok_or_else(|| UserError::InvalidEmbedder(embedder_name.to_owned()))?;
+        Ok(SparseVectorStore::new(self.sparse_vector_postings, embedder_index))
     }
 
     pub(crate) fn put_search_cutoff(&self, wtxn: &mut RwTxn<'_>, cutoff: u64) -> heed::Result<()> {
@@ -1660,37 +1688,115 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(wtxn, main_key::SEARCH_CUTOFF)
     }
 
+    pub(crate) fn put_query_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rules: Vec<QueryRule>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<Vec<QueryRule>>>().put(
+            wtxn,
+            main_key::QUERY_RULES,
+            &rules,
+        )
+    }
+
+    pub fn query_rules(&self, rtxn: &RoTxn<'_>) -> Result<Vec<QueryRule>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<Vec<QueryRule>>>()
+            .get(rtxn, main_key::QUERY_RULES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_query_rules(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::QUERY_RULES)
+    }
+
+    pub(crate) fn put_rerankers(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rerankers: &BTreeMap<String, RerankerSettings>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeMap<String, RerankerSettings>>>().put(
+            wtxn,
+            main_key::RERANKERS,
+            rerankers,
+        )
+    }
+
+    pub fn rerankers(&self, rtxn: &RoTxn<'_>) -> Result<BTreeMap<String, RerankerSettings>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, RerankerSettings>>>()
+            .get(rtxn, main_key::RERANKERS)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_rerankers(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::RERANKERS)
+    }
+
+    /// Returns the number of entries in the embedding cache and how often it was used while indexing.
+    pub fn embedding_cache_stats(&self, rtxn: &RoTxn<'_>) -> Result<EmbeddingCacheStats> {
+        let mut stats = self
+            .main
+            .remap_types::<Str, SerdeJson<EmbeddingCacheStats>>()
+            .get(rtxn, main_key::EMBEDDING_CACHE_STATS)?
+            .unwrap_or_default();
+        stats.entries = self.embedding_cache.len(rtxn)?;
+        Ok(stats)
+    }
+
+    pub(crate) fn put_embedding_cache_stats(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        stats: &EmbeddingCacheStats,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<EmbeddingCacheStats>>().put(
+            wtxn,
+            main_key::EMBEDDING_CACHE_STATS,
+            stats,
+        )
+    }
+
+    /// Removes all the entries of the embedding cache and resets its statistics.
+    pub fn clear_embedding_cache(&self, wtxn: &mut RwTxn<'_>) -> Result<()> {
+        self.embedding_cache.clear(wtxn)?;
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::EMBEDDING_CACHE_STATS)?;
+        Ok(())
+    }
+
+    /// Removes the entries of the embedding cache generated with the embedder options of the given prefix.
+    pub(crate) fn delete_embedding_cache_prefix(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        prefix: &CachePrefix,
+    ) -> Result<()> {
+        let mut iter = self.embedding_cache.prefix_iter_mut(wtxn, prefix)?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
     pub fn embeddings(
         &self,
         rtxn: &RoTxn<'_>,
         docid: DocumentId,
     ) -> Result<BTreeMap<String, Vec<Embedding>>> {
         let mut res = BTreeMap::new();
+        let embedding_configs = self.embedding_configs(rtxn)?;
         for row in self.embedder_category_id.iter(rtxn)? {
             let (embedder_name, embedder_id) = row?;
-            let embedder_id = (embedder_id as u16) << 8;
-            let mut embeddings = Vec::new();
-            'vectors: for i in 0..=u8::MAX {
-                let reader = arroy::Reader::open(rtxn, embedder_id | (i as u16), self.vector_arroy)
-                    .map(Some)
-                    .or_else(|e| match e {
-                        arroy::Error::MissingMetadata(_) => Ok(None),
-                        e => Err(e),
-                    })
-                    .transpose();
-
-                let Some(reader) = reader else {
-                    break 'vectors;
-                };
-
-                let embedding = reader?.item_vector(rtxn, docid)?;
-                if let Some(embedding) = embedding {
-                    embeddings.push(embedding)
-                } else {
-                    break 'vectors;
-                }
+            let config = embedding_configs.iter().find(|config| config.name == embedder_name);
+            // sparse vectors are kept in the documents
+            if config.map_or(false, |config| config.config.embedder_options.is_sparse()) {
+                continue;
             }
-
+            let options = config.map(|config| config.config.arroy_options()).unwrap_or_default();
+            let embeddings = ArroyWrapper::new(self.vector_arroy, embedder_id, options)
+                .item_vectors(rtxn, docid)?;
             res.insert(embedder_name.to_owned(), embeddings);
         }
         Ok(res)
@@ -2691,6 +2797,10 @@ pub(crate) mod tests {
             candidates: _,
             document_scores: _,
             mut documents_ids,
+            collapsed_groups: _,
+            applied_rules: _,
+            pinned_documents: _,
+            cursor: _,
             degraded: _,
             used_negative_operator: _,
         } = search.execute().unwrap();
@@ -19,6 +19,8 @@ pub mod index;
 pub mod order_by_map;
 pub mod prompt;
 pub mod proximity;
+pub mod query_rules;
+pub mod rerank;
 pub mod score_details;
 mod search;
 mod thread_pool_no_abort;
@@ -35,17 +37,19 @@ use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::hash::BuildHasherDefault;
 
+pub use charabia as tokenizer;
 use charabia::normalizer::{CharNormalizer, CompatibilityDecompositionNormalizer};
 pub use filter_parser::{Condition, FilterCondition, Span, Token};
 use fxhash::{FxHasher32, FxHasher64};
 pub use grenad::CompressionType;
+pub use heed;
+pub use rhai;
 pub use search::new::{
-    execute_search, filtered_universe, DefaultSearchLogger, GeoSortStrategy, SearchContext,
-    SearchLogger, VisualSearchLogger,
+    execute_search, filtered_universe, DefaultSearchLogger, ExplainSearchLogger, GeoSortStrategy,
+    SearchContext, SearchExplanation, SearchLogger, VisualSearchLogger,
 };
 use serde_json::Value;
 pub use thread_pool_no_abort::{PanicCatched, ThreadPoolNoAbort, ThreadPoolNoAbortBuilder};
-pub use {charabia as tokenizer, heed, rhai};
 
 pub use self::asc_desc::{AscDesc, AscDescError, Member, SortError};
 pub use self::criterion::{default_criteria, Criterion, CriterionError};
@@ -62,11 +66,14 @@ pub use self::heed_codec::{
     UncheckedU8StrStrCodec,
 };
 pub use self::index::Index;
+pub use self::query_rules::QueryRule;
+pub use self::rerank::{Reranker, RerankerSettings};
 pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
-pub use self::search::similar::Similar;
+pub use self::search::similar::{Similar, SimilarKeywords};
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    Collapse, CollapsedGroup, FacetDistribution, Filter, FormatOptions, HybridFusion, MatchBounds,
+    MatcherBuilder, MatchingWords, OrderBy, Search, SearchBoost, SearchCursor, SearchResult,
+    SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
 
 pub type Result<T> = std::result::Result<T, error::Error>;
@@ -10,7 +10,7 @@ use error::{NewPromptError, RenderPromptError};
 
 use self::context::Context;
 use self::document::Document;
-use crate::update::del_add::DelAdd;
+use crate::update::del_add::{into_del_add_obkv, DelAdd, DelAddOperation};
 use crate::FieldsIdsMap;
 
 pub struct Prompt {
@@ -100,6 +100,18 @@ impl Prompt {
 
         self.template.render(&context).map_err(RenderPromptError::missing_context)
     }
+
+    /// Renders a document as it is stored in the index, outside of any indexing operation.
+    pub fn render_stored(
+        &self,
+        document: obkv::KvReaderU16<'_>,
+        field_id_map: &FieldsIdsMap,
+    ) -> crate::Result<String> {
+        let mut buffer = Vec::new();
+        into_del_add_obkv(document, DelAddOperation::Addition, &mut buffer)?;
+        let document = obkv::KvReaderU16::new(&buffer);
+        Ok(self.render(document, DelAdd::Addition, field_id_map)?)
+    }
 }
 
 #[cfg(test)]
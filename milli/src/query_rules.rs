@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+use crate::{Filter, FilterCondition, Result, UserError};
+
+/// A rule that changes the results of the searches matching its conditions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct QueryRule {
+    /// The identifier of the rule, reported in the results of the searches it applies to.
+    pub id: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub conditions: RuleConditions,
+    #[serde(default)]
+    #[deserr(default)]
+    pub actions: RuleActions,
+}
+
+/// The conditions that a search must all meet for a rule to apply.
+///
+/// A rule without any condition applies to every search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RuleConditions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub query: Option<QueryCondition>,
+    /// A filter expression that must be one of the conditions of the filter of the search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct QueryCondition {
+    pub pattern: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub anchoring: Anchoring,
+}
+
+/// Where the words of a pattern must appear in the query.
+///
+/// The query and the pattern are compared word by word, ignoring the case.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum Anchoring {
+    Is,
+    StartsWith,
+    EndsWith,
+    #[default]
+    Contains,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RuleActions {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[deserr(default)]
+    pub pin: Vec<PinnedDocument>,
+    /// The external ids of the documents to remove from the results.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[deserr(default)]
+    pub hide: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[deserr(default)]
+    pub boost: Vec<BoostClause>,
+}
+
+/// A document to show at a fixed position of the results, whether it matches the query or not.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct PinnedDocument {
+    pub id: String,
+    /// The 0-based position of the document among all the results.
+    pub position: usize,
+}
+
+/// Ranks the documents matching the filter before the others, or after them if the weight is negative.
+///
+/// The documents are ranked by the sum of the weights of the clauses they match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct BoostClause {
+    pub filter: String,
+    #[serde(default = "default_boost_weight")]
+    #[deserr(default = default_boost_weight())]
+    pub weight: i32,
+}
+
+fn default_boost_weight() -> i32 {
+    1
+}
+
+impl QueryRule {
+    /// Returns `true` if the search made of the query and filter meets all the conditions of the rule.
+    pub fn is_triggered(&self, query: Option<&str>, filter: Option<&FilterCondition<'_>>) -> bool {
+        let RuleConditions { query: query_condition, filter: filter_condition } = &self.conditions;
+
+        if let Some(condition) = query_condition {
+            if !condition.matches(query.unwrap_or_default()) {
+                return false;
+            }
+        }
+
+        if let Some(condition) = filter_condition {
+            // the filters were validated when the rules were registered
+            let Ok(Some(condition)) = Filter::from_str(condition) else { return false };
+            let Some(filter) = filter else { return false };
+            // every clause of the condition must be a clause of the filter, in any order
+            let filter = conjunction(filter);
+            let condition = FilterCondition::from(condition);
+            if !conjunction(&condition).iter().all(|clause| filter.contains(clause)) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Checks that the rule can be registered.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| UserError::InvalidQueryRule { id: self.id.clone(), reason };
+
+        if self.id.is_empty() {
+            return Err(invalid("The `id` of a rule cannot be empty.".to_string()).into());
+        }
+
+        let filters = self.conditions.filter.iter().map(|filter| ("conditions.filter", filter));
+        let filters = filters
+            .chain(self.actions.boost.iter().map(|boost| ("actions.boost.filter", &boost.filter)));
+        for (name, filter) in filters {
+            match Filter::from_str(filter) {
+                Ok(Some(_)) => (),
+                Ok(None) => {
+                    return Err(invalid(format!("The `{name}` of a rule cannot be empty.")).into())
+                }
+                Err(error) => {
+                    return Err(invalid(format!("Invalid `{name}`: {error}")).into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that the rules can be registered together.
+pub fn validate_query_rules(rules: &[QueryRule]) -> Result<()> {
+    let mut ids = HashSet::new();
+    for rule in rules {
+        rule.validate()?;
+        if !ids.insert(rule.id.as_str()) {
+            return Err(UserError::InvalidQueryRule {
+                id: rule.id.clone(),
+                reason: "The `id` of a rule must be unique.".to_string(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the boost filters of the rules only use filterable attributes.
+pub fn validate_boost_filters(
+    rules: &[QueryRule],
+    filterable_fields: &HashSet<String>,
+) -> Result<()> {
+    for rule in rules {
+        for BoostClause { filter, .. } in &rule.actions.boost {
+            let Some(filter) = Filter::from_str(filter)? else { continue };
+            let mut attributes = Vec::new();
+            filter_attributes(&FilterCondition::from(filter), &mut attributes);
+            if let Some(attribute) = attributes
+                .into_iter()
+                .find(|attribute| !crate::is_faceted(attribute, filterable_fields))
+            {
+                return Err(UserError::InvalidQueryRule {
+                    id: rule.id.clone(),
+                    reason: format!(
+                        "Invalid `actions.boost.filter`: Attribute `{attribute}` is not filterable."
+                    ),
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the clauses of the filter that must all match, flattening the nested `AND`s.
+fn conjunction<'a, 'b>(filter: &'b FilterCondition<'a>) -> Vec<&'b FilterCondition<'a>> {
+    match filter {
+        FilterCondition::And(conditions) => conditions.iter().flat_map(conjunction).collect(),
+        filter => vec![filter],
+    }
+}
+
+fn filter_attributes(filter: &FilterCondition<'_>, attributes: &mut Vec<String>) {
+    match filter {
+        FilterCondition::Not(filter) => filter_attributes(filter, attributes),
+        FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+            attributes.push(fid.value().to_string())
+        }
+        FilterCondition::Or(filters) | FilterCondition::And(filters) => {
+            filters.iter().for_each(|filter| filter_attributes(filter, attributes))
+        }
+        FilterCondition::GeoLowerThan { .. } | FilterCondition::GeoBoundingBox { .. } => {
+            attributes.push("_geo".to_string())
+        }
+    }
+}
+
+impl QueryCondition {
+    fn matches(&self, query: &str) -> bool {
+        let query: Vec<_> = query.split_whitespace().map(str::to_lowercase).collect();
+        let pattern: Vec<_> = self.pattern.split_whitespace().map(str::to_lowercase).collect();
+
+        match self.anchoring {
+            Anchoring::Is => query == pattern,
+            Anchoring::StartsWith => query.starts_with(&pattern),
+            Anchoring::EndsWith => query.ends_with(&pattern),
+            Anchoring::Contains => {
+                pattern.is_empty() || query.windows(pattern.len()).any(|words| words == pattern)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(conditions: RuleConditions) -> QueryRule {
+        QueryRule { id: "rule".to_string(), conditions, actions: RuleActions::default() }
+    }
+
+    fn query_rule(pattern: &str, anchoring: Anchoring) -> QueryRule {
+        rule(RuleConditions {
+            query: Some(QueryCondition { pattern: pattern.to_string(), anchoring }),
+            filter: None,
+        })
+    }
+
+    #[test]
+    fn query_condition() {
+        let contains = query_rule("iPhone 15", Anchoring::Contains);
+        assert!(contains.is_triggered(Some("cheap iphone 15 pro"), None));
+        assert!(!contains.is_triggered(Some("iphone 14 or 15"), None));
+        assert!(!contains.is_triggered(None, None));
+
+        let is = query_rule("iphone", Anchoring::Is);
+        assert!(is.is_triggered(Some(" IPHONE "), None));
+        assert!(!is.is_triggered(Some("iphone case"), None));
+
+        let starts_with = query_rule("iphone", Anchoring::StartsWith);
+        assert!(starts_with.is_triggered(Some("iphone case"), None));
+        assert!(!starts_with.is_triggered(Some("case iphone"), None));
+
+        let ends_with = query_rule("iphone", Anchoring::EndsWith);
+        assert!(ends_with.is_triggered(Some("case iphone"), None));
+        assert!(!ends_with.is_triggered(Some("iphone case"), None));
+
+        let empty = query_rule("", Anchoring::Is);
+        assert!(empty.is_triggered(None, None));
+        assert!(!empty.is_triggered(Some("iphone"), None));
+    }
+
+    #[test]
+    fn filter_condition() {
+        let rule = rule(RuleConditions { query: None, filter: Some("brand = apple".to_string()) });
+
+        let filter = |expression: &'static str| {
+            FilterCondition::from(Filter::from_str(expression).unwrap().unwrap())
+        };
+        assert!(rule.is_triggered(None, Some(&filter("brand = apple"))));
+        assert!(rule.is_triggered(None, Some(&filter("price < 100 AND brand = apple"))));
+        assert!(!rule.is_triggered(None, Some(&filter("brand = apple OR brand = samsung"))));
+        assert!(!rule.is_triggered(None, Some(&filter("brand = samsung"))));
+        assert!(!rule.is_triggered(None, None));
+
+        // the filters are compared once parsed
+        assert!(rule.is_triggered(None, Some(&filter("(brand=\"apple\") AND price < 100"))));
+
+        let rule = QueryRule {
+            conditions: RuleConditions { query: None, filter: Some("a = 1 AND b = 2".to_string()) },
+            ..rule
+        };
+        assert!(rule.is_triggered(None, Some(&filter("b = 2 AND c = 3 AND a = 1"))));
+        assert!(!rule.is_triggered(None, Some(&filter("a = 1 AND c = 3"))));
+    }
+
+    #[test]
+    fn boost_filters_must_be_filterable() {
+        let mut rule = rule(RuleConditions::default());
+        rule.actions.boost.push(BoostClause {
+            filter: "refurbished = true OR _geoRadius(45.4, 9.1, 1000)".to_string(),
+            weight: 1,
+        });
+        let rules = [rule];
+
+        let filterable = |fields: &[&str]| fields.iter().map(|field| field.to_string()).collect();
+        assert!(validate_boost_filters(&rules, &filterable(&["refurbished", "_geo"])).is_ok());
+        assert!(validate_boost_filters(&rules, &filterable(&["refurbished"])).is_err());
+        assert!(validate_boost_filters(&rules, &filterable(&["_geo"])).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let mut rule = rule(RuleConditions { query: None, filter: Some("brand =".to_string()) });
+        assert!(rule.validate().is_err());
+
+        rule.conditions.filter = Some("brand = apple".to_string());
+        assert!(rule.validate().is_ok());
+
+        rule.actions.boost.push(BoostClause { filter: "  ".to_string(), weight: 1 });
+        assert!(rule.validate().is_err());
+
+        rule.actions.boost[0].filter = "refurbished = true".to_string();
+        assert!(rule.validate().is_ok());
+
+        assert!(validate_query_rules(&[rule.clone()]).is_ok());
+        assert!(validate_query_rules(&[rule.clone(), rule.clone()]).is_err());
+
+        rule.id = String::new();
+        assert!(rule.validate().is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! Reranking of the top hits of a search by an external service, such as a cross-encoder model.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use deserr::Deserr;
+use heed::RoTxn;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::prompt::Prompt;
+use crate::{DocumentId, Index, Result, UserError};
+
+/// Maximum time to wait for the response of a reranker, as the search waits for it.
+const RERANK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The client shared by all the rerankers, so that the connections are reused across searches.
+static RERANK_CLIENT: Lazy<ureq::Agent> =
+    Lazy::new(|| ureq::AgentBuilder::new().timeout(RERANK_TIMEOUT).build());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum RerankerSource {
+    #[default]
+    Rest,
+}
+
+/// A service scoring the relevance of documents to the query of a search.
+///
+/// Each request contains the query and the rendered text of the documents to score,
+/// and each response contains one score per document, the highest being the most relevant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RerankerSettings {
+    #[serde(default)]
+    #[deserr(default)]
+    pub source: RerankerSource,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub api_key: Option<String>,
+    /// Additional headers sent with each request.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[deserr(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Body of the requests, in which the query and the documents are inserted.
+    #[serde(default = "default_request")]
+    #[deserr(default = default_request())]
+    pub request: Value,
+    /// Path of the query in the body of the requests.
+    #[serde(default = "default_query_field")]
+    #[deserr(default = default_query_field())]
+    pub query_field: Vec<String>,
+    /// Path of the array of documents in the body of the requests.
+    #[serde(default = "default_documents_field")]
+    #[deserr(default = default_documents_field())]
+    pub documents_field: Vec<String>,
+    /// Path of the array of results in the body of the responses.
+    #[serde(default = "default_path_to_results")]
+    #[deserr(default = default_path_to_results())]
+    pub path_to_results: Vec<String>,
+    /// Path, inside a result, of the position of the scored document in the request.
+    ///
+    /// When empty, the results are expected in the same order as the documents.
+    #[serde(default = "default_index_field")]
+    #[deserr(default = default_index_field())]
+    pub index_field: Vec<String>,
+    /// Path, inside a result, of the score of the document.
+    #[serde(default = "default_score_field")]
+    #[deserr(default = default_score_field())]
+    pub score_field: Vec<String>,
+    /// Template rendering the documents sent to the reranker, the default document template when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub document_template: Option<String>,
+}
+
+fn default_request() -> Value {
+    Value::Object(Default::default())
+}
+
+fn default_query_field() -> Vec<String> {
+    vec!["query".into()]
+}
+
+fn default_documents_field() -> Vec<String> {
+    vec!["documents".into()]
+}
+
+fn default_path_to_results() -> Vec<String> {
+    vec!["results".into()]
+}
+
+fn default_index_field() -> Vec<String> {
+    vec!["index".into()]
+}
+
+fn default_score_field() -> Vec<String> {
+    vec!["relevance_score".into()]
+}
+
+/// Checks that the rerankers can be used to send requests and read their responses.
+pub fn validate_rerankers(rerankers: &BTreeMap<String, RerankerSettings>) -> Result<()> {
+    for (name, settings) in rerankers {
+        let invalid =
+            |reason: String| UserError::InvalidReranker { reranker_name: name.clone(), reason };
+
+        if let Err(error) = url::Url::parse(&settings.url) {
+            return Err(invalid(format!("could not parse `{}`: {error}", settings.url)).into());
+        }
+        if !settings.request.is_object() {
+            return Err(invalid(format!(
+                "`request` must be an object, but found `{}`",
+                settings.request
+            ))
+            .into());
+        }
+        for (field, path) in [
+            ("queryField", &settings.query_field),
+            ("documentsField", &settings.documents_field),
+            ("scoreField", &settings.score_field),
+        ] {
+            if path.is_empty() {
+                return Err(invalid(format!("`{field}` cannot be empty")).into());
+            }
+        }
+        if settings.query_field == settings.documents_field {
+            return Err(
+                invalid("`queryField` and `documentsField` must be distinct".to_string()).into()
+            );
+        }
+        if let Some(template) = &settings.document_template {
+            if let Err(error) = Prompt::new(template.clone()) {
+                return Err(invalid(format!("invalid `documentTemplate`: {error}")).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+pub struct Reranker {
+    name: String,
+    client: ureq::Agent,
+    settings: RerankerSettings,
+    prompt: Prompt,
+}
+
+impl Reranker {
+    pub fn new(name: String, settings: RerankerSettings) -> Result<Self> {
+        let prompt = match &settings.document_template {
+            Some(template) => Prompt::new(template.clone())?,
+            None => Prompt::default(),
+        };
+        Ok(Self { name, client: RERANK_CLIENT.clone(), settings, prompt })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Scores the documents against the query, returning one score per document in the same order.
+    pub fn rerank(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn<'_>,
+        query: &str,
+        docids: &[DocumentId],
+    ) -> Result<Vec<f64>> {
+        if docids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let documents = index
+            .iter_documents(rtxn, docids.iter().copied())?
+            .map(|result| {
+                let (_docid, document) = result?;
+                self.prompt.render_stored(document, &fields_ids_map)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut body = self.settings.request.clone();
+        insert_at(&mut body, &self.settings.query_field, Value::String(query.to_owned()))
+            .map_err(|reason| self.error(reason))?;
+        insert_at(&mut body, &self.settings.documents_field, documents.into())
+            .map_err(|reason| self.error(reason))?;
+
+        let request = self.client.post(&self.settings.url).set("Content-Type", "application/json");
+        let request = match &self.settings.api_key {
+            Some(api_key) => request.set("Authorization", &format!("Bearer {api_key}")),
+            None => request,
+        };
+        let request = self
+            .settings
+            .headers
+            .iter()
+            .fold(request, |request, (name, value)| request.set(name, value));
+
+        let response: Value = match request.send_json(body) {
+            Ok(response) => response.into_json().map_err(|error| {
+                self.error(format!("could not deserialize the response as JSON: {error}"))
+            })?,
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                return Err(self.error(format!("received HTTP {code} from the reranker: {body}")));
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(self.error(format!("could not reach the reranker: {transport}")));
+            }
+        };
+
+        self.read_scores(&response, docids.len()).map_err(|reason| self.error(reason))
+    }
+
+    fn read_scores(&self, response: &Value, count: usize) -> std::result::Result<Vec<f64>, String> {
+        let results = get_at(response, &self.settings.path_to_results)
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                format!(
+                    "expected an array at `{}` in the response `{response}`",
+                    self.settings.path_to_results.join(".")
+                )
+            })?;
+
+        let mut scores = vec![None; count];
+        for (position, result) in results.iter().enumerate() {
+            let index = if self.settings.index_field.is_empty() {
+                position
+            } else {
+                get_at(result, &self.settings.index_field)
+                    .and_then(Value::as_u64)
+                    .map(|index| index as usize)
+                    .ok_or_else(|| {
+                        format!(
+                            "expected an integer at `{}` in the result `{result}`",
+                            self.settings.index_field.join(".")
+                        )
+                    })?
+            };
+            let score = get_at(result, &self.settings.score_field)
+                .and_then(Value::as_f64)
+                .ok_or_else(|| {
+                    format!(
+                        "expected a number at `{}` in the result `{result}`",
+                        self.settings.score_field.join(".")
+                    )
+                })?;
+            match scores.get_mut(index) {
+                Some(slot) => *slot = Some(score),
+                None => return Err(format!("the result `{result}` refers to no document")),
+            }
+        }
+
+        scores.into_iter().collect::<Option<Vec<_>>>().ok_or_else(|| {
+            format!("expected a score for each of the {count} documents, got {}", results.len())
+        })
+    }
+
+    fn error(&self, reason: String) -> crate::Error {
+        UserError::RerankingFailed { reranker_name: self.name.clone(), reason }.into()
+    }
+}
+
+fn get_at<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, component| value.get(component))
+}
+
+fn insert_at(body: &mut Value, path: &[String], value: Value) -> std::result::Result<(), String> {
+    let Some((last, path)) = path.split_last() else {
+        return Err("cannot insert a value at an empty path".to_string());
+    };
+    let mut current = body;
+    for component in path {
+        current = current
+            .as_object_mut()
+            .ok_or_else(|| format!("expected `{component}` to be in an object of the request"))?
+            .entry(component.clone())
+            .or_insert_with(default_request);
+    }
+    current
+        .as_object_mut()
+        .ok_or_else(|| format!("expected `{last}` to be in an object of the request"))?
+        .insert(last.clone(), value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn settings() -> RerankerSettings {
+        RerankerSettings {
+            source: RerankerSource::Rest,
+            url: "http://localhost:7700/rerank".into(),
+            api_key: None,
+            headers: Default::default(),
+            request: serde_json::json!({ "model": "cross-encoder" }),
+            query_field: default_query_field(),
+            documents_field: vec!["input".into(), "documents".into()],
+            path_to_results: default_path_to_results(),
+            index_field: default_index_field(),
+            score_field: default_score_field(),
+            document_template: None,
+        }
+    }
+
+    #[test]
+    fn request_body() {
+        let settings = settings();
+        let mut body = settings.request.clone();
+        insert_at(&mut body, &settings.query_field, "kefir".into()).unwrap();
+        insert_at(&mut body, &settings.documents_field, vec!["a", "b"].into()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({ "model": "cross-encoder", "query": "kefir", "input": { "documents": ["a", "b"] } })
+        );
+    }
+
+    #[test]
+    fn scores_in_request_order() {
+        let reranker = Reranker::new("cross".into(), settings()).unwrap();
+        let response = serde_json::json!({ "results": [
+            { "index": 1, "relevance_score": 0.9 },
+            { "index": 0, "relevance_score": 0.2 },
+        ]});
+        assert_eq!(reranker.read_scores(&response, 2).unwrap(), vec![0.2, 0.9]);
+        assert!(reranker.read_scores(&response, 3).is_err());
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let mut rerankers = BTreeMap::new();
+        let mut settings = settings();
+        settings.score_field = Vec::new();
+        rerankers.insert("cross".to_string(), settings);
+        assert!(validate_rerankers(&rerankers).is_err());
+    }
+}
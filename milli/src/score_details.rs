@@ -17,6 +17,14 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    /// Boost applied by the query rules of the index
+    QueryRules(Boost),
+    /// Boost applied by the `boost` parameter of the search
+    Boost(Boost),
+    /// Ranks of a hybrid search whose keyword and semantic results are merged by reciprocal rank fusion
+    RankFusion(RankFusion),
+    /// Score given by a reranker to one of the top hits of the search
+    Rerank(Rerank),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
@@ -53,6 +61,10 @@ impl ScoreDetails {
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
             ScoreDetails::Vector(_) => None,
+            ScoreDetails::QueryRules(details) => Some(details.rank),
+            ScoreDetails::Boost(details) => Some(details.rank),
+            ScoreDetails::RankFusion(_) => None,
+            ScoreDetails::Rerank(_) => None,
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
         }
     }
@@ -101,6 +113,10 @@ impl ScoreDetails {
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
+            ScoreDetails::QueryRules(boost) => RankOrValue::Rank(boost.rank),
+            ScoreDetails::Boost(boost) => RankOrValue::Rank(boost.rank),
+            ScoreDetails::RankFusion(fusion) => RankOrValue::Score(fusion.score),
+            ScoreDetails::Rerank(rerank) => RankOrValue::Score(rerank.score.clamp(0.0, 1.0)),
             ScoreDetails::Skipped => RankOrValue::Rank(Rank { rank: 0, max_rank: 1 }),
         }
     }
@@ -254,10 +270,49 @@ impl ScoreDetails {
                     let details = serde_json::json!({
                         "order": order,
                         "similarity": similarity,
+                        "embeddingIndex": s.embedding_index,
                     });
                     details_map.insert("vectorSort".into(), details);
                     order += 1;
                 }
+                ScoreDetails::QueryRules(boost) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "weight": boost.weight,
+                        "score": boost.rank.local_score(),
+                    });
+                    details_map.insert("queryRules".into(), details);
+                    order += 1;
+                }
+                ScoreDetails::Boost(boost) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "weight": boost.weight,
+                        "score": boost.rank.local_score(),
+                    });
+                    details_map.insert("boost".into(), details);
+                    order += 1;
+                }
+                ScoreDetails::RankFusion(fusion) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "keywordRank": fusion.keyword_rank,
+                        "semanticRank": fusion.semantic_rank,
+                        "rankConstant": fusion.rank_constant,
+                        "score": fusion.score,
+                    });
+                    details_map.insert("rankFusion".into(), details);
+                    order += 1;
+                }
+                ScoreDetails::Rerank(rerank) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "retrievalRank": rerank.retrieval_rank,
+                        "score": rerank.score,
+                    });
+                    details_map.insert("rerank".into(), details);
+                    order += 1;
+                }
                 ScoreDetails::Skipped => {
                     details_map
                         .insert("skipped".to_string(), serde_json::json!({ "order": order }));
@@ -483,9 +538,45 @@ impl PartialOrd for GeoSort {
     }
 }
 
+/// The ranks of a document in the keyword and semantic results of a hybrid search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankFusion {
+    /// The 1-based rank of the document in the keyword results, `None` if it isn't part of them.
+    pub keyword_rank: Option<u32>,
+    /// The 1-based rank of the document in the semantic results, `None` if it isn't part of them.
+    pub semantic_rank: Option<u32>,
+    pub rank_constant: usize,
+    /// The fused score, normalized so that a document ranked first by both searches has a score of 1.
+    pub score: f64,
+}
+
+/// The score of a document according to a reranker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rerank {
+    /// The 1-based rank of the document in the results before reranking.
+    pub retrieval_rank: u32,
+    /// The score returned by the reranker, clamped between 0 and 1 when computing the ranking score.
+    pub score: f64,
+}
+
+/// The boost of a document, from the filters it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Boost {
+    /// The sum of the weights of the boost filters matched by the document.
+    pub weight: i64,
+    /// The rank of `weight` among the sums of weights of all the candidates.
+    pub rank: Rank,
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Vector {
     pub similarity: Option<f32>,
+    /// The position of the embedding closest to the target, among the stored embeddings
+    /// of the document.
+    ///
+    /// The embeddings are stored sorted and deduplicated, so this is the position in the embeddings
+    /// returned by `retrieveVectors`, not in the embeddings sent with the document.
+    pub embedding_index: Option<usize>,
 }
 
 impl GeoSort {
@@ -1,11 +1,26 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
-use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
-use crate::search::SemanticSearch;
-use crate::{MatchingWords, Result, Search, SearchResult};
+use crate::score_details::{self, ScoreDetails, ScoreValue, ScoringStrategy};
+use crate::search::{Collapse, SemanticSearch, TriggeredRules};
+use crate::vector::error::EmbedError;
+use crate::vector::Embedding;
+use crate::{DocumentId, MatchingWords, Result, Search, SearchResult};
+
+/// How the results of the keyword and semantic searches of a hybrid search are merged,
+/// see [`Search::hybrid_fusion`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HybridFusion {
+    /// The scores of both searches are weighted by the semantic ratio and compared.
+    #[default]
+    Score,
+    /// The documents are ranked by the sum of `weight / (rank_constant + rank)` over both searches,
+    /// where `rank` starts at 1 and the weight of the semantic search is the semantic ratio.
+    Rrf { rank_constant: usize },
+}
 
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
@@ -142,6 +157,10 @@ impl ScoreWithRatioResult {
                 candidates: vector_results.candidates | keyword_results.candidates,
                 documents_ids,
                 document_scores,
+                collapsed_groups: Vec::new(),
+                applied_rules: Vec::new(),
+                pinned_documents: RoaringBitmap::new(),
+                cursor: None,
                 degraded: vector_results.degraded | keyword_results.degraded,
                 used_negative_operator: vector_results.used_negative_operator
                     | keyword_results.used_negative_operator,
@@ -151,18 +170,118 @@ impl ScoreWithRatioResult {
     }
 }
 
+/// Merges the results of the keyword and semantic searches by reciprocal rank fusion.
+///
+/// The score details of a document are its ranks, followed by its score details in the keyword
+/// results and in the semantic results.
+#[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
+fn fuse_ranks(
+    vector_results: SearchResult,
+    keyword_results: SearchResult,
+    semantic_ratio: f32,
+    rank_constant: usize,
+    from: usize,
+    length: usize,
+) -> (SearchResult, u32) {
+    #[derive(Default)]
+    struct Ranked {
+        keyword: Option<(u32, Vec<ScoreDetails>)>,
+        semantic: Option<(u32, Vec<ScoreDetails>)>,
+    }
+
+    let mut documents: BTreeMap<DocumentId, Ranked> = BTreeMap::new();
+    for (rank, (docid, scores)) in
+        keyword_results.documents_ids.into_iter().zip(keyword_results.document_scores).enumerate()
+    {
+        documents.entry(docid).or_default().keyword.get_or_insert((rank as u32 + 1, scores));
+    }
+    for (rank, (docid, scores)) in
+        vector_results.documents_ids.into_iter().zip(vector_results.document_scores).enumerate()
+    {
+        documents.entry(docid).or_default().semantic.get_or_insert((rank as u32 + 1, scores));
+    }
+
+    let contribution = |weight: f32, rank: Option<u32>| match rank {
+        Some(rank) => weight as f64 / (rank_constant as f64 + rank as f64),
+        None => 0.0,
+    };
+    let mut documents: Vec<_> = documents
+        .into_iter()
+        .map(|(docid, ranked)| {
+            let keyword_rank = ranked.keyword.as_ref().map(|(rank, _)| *rank);
+            let semantic_rank = ranked.semantic.as_ref().map(|(rank, _)| *rank);
+            let keyword = contribution(1.0 - semantic_ratio, keyword_rank);
+            let semantic = contribution(semantic_ratio, semantic_rank);
+            let fusion = score_details::RankFusion {
+                keyword_rank,
+                semantic_rank,
+                rank_constant,
+                // the weights sum to 1, so the best possible score is `1 / (rank_constant + 1)`
+                score: (keyword + semantic) * (rank_constant as f64 + 1.0),
+            };
+            (docid, fusion, semantic > keyword, ranked)
+        })
+        .collect();
+    // the documents are already sorted by docid, the sort is stable
+    documents.sort_by(|(_, left, ..), (_, right, ..)| right.score.total_cmp(&left.score));
+
+    let mut semantic_hit_count = 0;
+    let mut documents_ids = Vec::with_capacity(length);
+    let mut document_scores = Vec::with_capacity(length);
+    for (docid, fusion, is_semantic, ranked) in documents.into_iter().skip(from).take(length) {
+        if is_semantic {
+            semantic_hit_count += 1;
+        }
+        let mut scores = vec![ScoreDetails::RankFusion(fusion)];
+        scores.extend(ranked.keyword.into_iter().flat_map(|(_, scores)| scores));
+        scores.extend(ranked.semantic.into_iter().flat_map(|(_, scores)| scores));
+        documents_ids.push(docid);
+        document_scores.push(scores);
+    }
+
+    (
+        SearchResult {
+            matching_words: keyword_results.matching_words,
+            candidates: vector_results.candidates | keyword_results.candidates,
+            documents_ids,
+            document_scores,
+            collapsed_groups: Vec::new(),
+            applied_rules: Vec::new(),
+            pinned_documents: RoaringBitmap::new(),
+            cursor: None,
+            degraded: vector_results.degraded | keyword_results.degraded,
+            used_negative_operator: vector_results.used_negative_operator
+                | keyword_results.used_negative_operator,
+        },
+        semantic_hit_count,
+    )
+}
+
 impl<'a> Search<'a> {
     #[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
     pub fn execute_hybrid(&self, semantic_ratio: f32) -> Result<(SearchResult, Option<u32>)> {
+        // the pinned documents are inserted after the merge, the organic results start after the
+        // organic documents ranked before the offset
+        let rules = self.query_rules()?;
+        let offset =
+            if self.pin_documents { rules.organic_offset(self.offset) } else { self.offset };
+
         // TODO: find classier way to achieve that than to reset vector and query params
         // create separate keyword and semantic searches
         let mut search = Search {
             query: self.query.clone(),
             filter: self.filter.clone(),
             offset: 0,
-            limit: self.limit + self.offset,
+            limit: self.limit + offset,
             sort_criteria: self.sort_criteria.clone(),
-            distinct: self.distinct.clone(),
+            // collapsing is a distinct on the groups, the inner hits are computed after the merge
+            distinct: match &self.collapse {
+                Some(Collapse { field, .. }) => Some(field.clone()),
+                None => self.distinct.clone(),
+            },
+            collapse: None,
+            boost: self.boost.clone(),
+            search_after: None,
             searchable_attributes: self.searchable_attributes,
             geo_strategy: self.geo_strategy,
             terms_matching_strategy: self.terms_matching_strategy,
@@ -174,55 +293,109 @@ impl<'a> Search<'a> {
             semantic: self.semantic.clone(),
             time_budget: self.time_budget.clone(),
             ranking_score_threshold: self.ranking_score_threshold,
+            restricted_universe: self.restricted_universe.clone(),
+            pin_documents: false,
+            apply_query_rules: self.apply_query_rules,
+            hybrid_fusion: self.hybrid_fusion,
         };
 
         let semantic = search.semantic.take();
         let keyword_results = search.execute()?;
 
-        // completely skip semantic search if the results of the keyword search are good enough
-        if self.results_good_enough(&keyword_results, semantic_ratio) {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+        // completely skip semantic search if the results of the keyword search are good enough,
+        // the ranks of both searches are always needed to fuse them
+        if self.hybrid_fusion == HybridFusion::Score
+            && self.results_good_enough(&keyword_results, semantic_ratio)
+        {
+            if self.collapse.is_some() {
+                // the inner hits are ranked by a hybrid search, embedding the query only once
+                search.semantic = match (search.query.clone(), semantic) {
+                    (Some(query), Some(semantic)) => match query_vector(query, &semantic) {
+                        Ok(vector) => Some(SemanticSearch { vector, ..semantic }),
+                        Err(error) => {
+                            tracing::error!(error=%error, "Embedding failed");
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+            }
+            let results = return_keyword_results(self.limit, offset, keyword_results);
+            return self.finish_hybrid(&search, &rules, semantic_ratio, results);
         }
 
         // no vector search against placeholder search
-        let Some(query) = search.query.take() else {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+        let Some(query) = search.query.clone() else {
+            let results = return_keyword_results(self.limit, offset, keyword_results);
+            return self.finish_hybrid(&search, &rules, semantic_ratio, results);
         };
         // no embedder, no semantic search
-        let Some(SemanticSearch { vector, embedder_name, embedder }) = semantic else {
-            return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
+        let Some(semantic) = semantic else {
+            let results = return_keyword_results(self.limit, offset, keyword_results);
+            return self.finish_hybrid(&search, &rules, semantic_ratio, results);
         };
 
-        let vector_query = match vector {
-            Some(vector_query) => vector_query,
-            None => {
-                // attempt to embed the vector
-                let span = tracing::trace_span!(target: "search::hybrid", "embed_one");
-                let _entered = span.enter();
-
-                match embedder.embed_one(query) {
-                    Ok(embedding) => embedding,
-                    Err(error) => {
-                        tracing::error!(error=%error, "Embedding failed");
-                        return Ok((keyword_results, Some(0)));
-                    }
-                }
+        let vector_query = match query_vector(query, &semantic) {
+            Ok(vector_query) => vector_query,
+            Err(error) => {
+                // the top hits are keyword results, and so are the inner hits of their groups
+                tracing::error!(error=%error, "Embedding failed");
+                let results = return_keyword_results(self.limit, offset, keyword_results);
+                return self.finish_hybrid(&search, &rules, semantic_ratio, results);
             }
         };
 
-        search.semantic =
-            Some(SemanticSearch { vector: Some(vector_query), embedder_name, embedder });
+        search.semantic = Some(SemanticSearch { vector: vector_query, ..semantic });
 
         // TODO: would be better to have two distinct functions at this point
         let vector_results = search.execute()?;
 
-        let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
-        let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
-
-        let (merge_results, semantic_hit_count) =
-            ScoreWithRatioResult::merge(vector_results, keyword_results, self.offset, self.limit);
+        let (merge_results, semantic_hit_count) = match self.hybrid_fusion {
+            HybridFusion::Score => {
+                let keyword_results =
+                    ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
+                let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
+                ScoreWithRatioResult::merge(vector_results, keyword_results, offset, self.limit)
+            }
+            HybridFusion::Rrf { rank_constant } => fuse_ranks(
+                vector_results,
+                keyword_results,
+                semantic_ratio,
+                rank_constant,
+                offset,
+                self.limit,
+            ),
+        };
         assert!(merge_results.documents_ids.len() <= self.limit);
-        Ok((merge_results, Some(semantic_hit_count)))
+        self.finish_hybrid(
+            &search,
+            &rules,
+            semantic_ratio,
+            (merge_results, Some(semantic_hit_count)),
+        )
+    }
+
+    /// Inserts the pinned documents in the results, and computes the collapsed groups of the
+    /// results if this search is collapsed.
+    ///
+    /// The groups are computed using the inner search, so that the query is embedded only once.
+    fn finish_hybrid(
+        &self,
+        search: &Search<'_>,
+        rules: &TriggeredRules,
+        semantic_ratio: f32,
+        (mut results, semantic_hit_count): (SearchResult, Option<u32>),
+    ) -> Result<(SearchResult, Option<u32>)> {
+        if self.pin_documents {
+            rules.pin_documents(&mut results, self.offset, self.limit);
+        }
+        results.applied_rules = rules.ids.clone();
+
+        if let Some(collapse) = &self.collapse {
+            results.collapsed_groups =
+                search.collapse_groups(collapse, &results.documents_ids, Some(semantic_ratio))?;
+        }
+        Ok((results, semantic_hit_count))
     }
 
     fn results_good_enough(&self, keyword_results: &SearchResult, semantic_ratio: f32) -> bool {
@@ -246,6 +419,24 @@ impl<'a> Search<'a> {
     }
 }
 
+/// Returns the vector of the query of the semantic search, embedding the query when the search
+/// has neither a vector nor a sparse vector.
+fn query_vector(
+    query: String,
+    semantic: &SemanticSearch,
+) -> std::result::Result<Option<Embedding>, EmbedError> {
+    if semantic.vector.is_some() {
+        return Ok(semantic.vector.clone());
+    }
+    // sparse vectors are never embedded
+    if semantic.sparse_vector.is_some() {
+        return Ok(None);
+    }
+    let span = tracing::trace_span!(target: "search::hybrid", "embed_one");
+    let _entered = span.enter();
+    semantic.embedder.embed_search(query).map(Some)
+}
+
 fn return_keyword_results(
     limit: usize,
     offset: usize,
@@ -254,6 +445,10 @@ fn return_keyword_results(
         candidates,
         mut documents_ids,
         mut document_scores,
+        collapsed_groups: _,
+        applied_rules,
+        pinned_documents,
+        cursor: _,
         degraded,
         used_negative_operator,
     }: SearchResult,
@@ -280,6 +475,10 @@ fn return_keyword_results(
             candidates,
             documents_ids,
             document_scores,
+            collapsed_groups: Vec::new(),
+            applied_rules,
+            pinned_documents,
+            cursor: None,
             degraded,
             used_negative_operator,
         },
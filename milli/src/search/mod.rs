@@ -6,13 +6,21 @@ use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
 pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::hybrid::HybridFusion;
 pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
-use self::new::{execute_vector_search, PartialSearchResult};
+pub use self::new::SearchCursor;
+use self::new::{
+    distinct_single_docid, execute_vector_search, PartialSearchResult, PlaceholderQuery,
+    QueryGraph, QueryVector,
+};
+use crate::query_rules::BoostClause;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::vector::sparse::SparseVector;
 use crate::vector::Embedder;
 use crate::{
-    execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Error, Index,
-    Result, SearchContext, TimeBudget, UserError,
+    execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Error,
+    ExplainSearchLogger, FilterCondition, Index, Result, SearchContext, SearchExplanation,
+    SearchLogger, TimeBudget, UserError,
 };
 
 // Building these factories is not free.
@@ -26,13 +34,43 @@ pub mod hybrid;
 pub mod new;
 pub mod similar;
 
+/// Describes how to collapse the results of a search, see [`Search::collapse`].
+#[derive(Debug, Clone)]
+pub struct Collapse {
+    /// The filterable field whose values define the groups.
+    pub field: String,
+    /// The maximum number of documents to return for each group, in addition to its best document.
+    pub inner_hits: usize,
+}
+
+/// Describes how to boost the documents matching filters, see [`Search::boost`].
+#[derive(Debug, Clone)]
+pub struct SearchBoost {
+    pub clauses: Vec<BoostClause>,
+    /// The index of the ranking rule of the settings before which the documents are boosted.
+    pub position: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct SemanticSearch {
     vector: Option<Vec<f32>>,
+    /// The query vector of a sparse embedder, used instead of `vector`
+    sparse_vector: Option<SparseVector>,
     embedder_name: String,
     embedder: Arc<Embedder>,
 }
 
+impl SemanticSearch {
+    /// The vector compared to the vectors of the documents, if it is known.
+    fn query_vector(&self) -> Option<QueryVector<'_>> {
+        match (&self.sparse_vector, &self.vector) {
+            (Some(sparse_vector), _) => Some(QueryVector::Sparse(sparse_vector)),
+            (None, Some(vector)) => Some(QueryVector::Dense(vector)),
+            (None, None) => None,
+        }
+    }
+}
+
 pub struct Search<'a> {
     query: Option<String>,
     // this should be linked to the String in the query
@@ -41,6 +79,9 @@ pub struct Search<'a> {
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
     distinct: Option<String>,
+    collapse: Option<Collapse>,
+    boost: Option<SearchBoost>,
+    search_after: Option<SearchCursor>,
     searchable_attributes: Option<&'a [String]>,
     geo_strategy: new::GeoSortStrategy,
     terms_matching_strategy: TermsMatchingStrategy,
@@ -52,6 +93,15 @@ pub struct Search<'a> {
     semantic: Option<SemanticSearch>,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
+    /// Restricts the search to a subset of the documents, used to search inside a collapsed group.
+    restricted_universe: Option<RoaringBitmap>,
+    /// Whether the documents pinned by the query rules are inserted in the results, disabled for
+    /// the inner searches whose results are merged afterward.
+    pin_documents: bool,
+    /// Whether the query rules of the index are evaluated, disabled for the internal searches
+    /// whose query isn't written by a user.
+    apply_query_rules: bool,
+    hybrid_fusion: HybridFusion,
 }
 
 impl<'a> Search<'a> {
@@ -63,6 +113,9 @@ impl<'a> Search<'a> {
             limit: 20,
             sort_criteria: None,
             distinct: None,
+            collapse: None,
+            boost: None,
+            search_after: None,
             searchable_attributes: None,
             geo_strategy: new::GeoSortStrategy::default(),
             terms_matching_strategy: TermsMatchingStrategy::default(),
@@ -74,6 +127,10 @@ impl<'a> Search<'a> {
             semantic: None,
             time_budget: TimeBudget::max(),
             ranking_score_threshold: None,
+            restricted_universe: None,
+            pin_documents: true,
+            apply_query_rules: true,
+            hybrid_fusion: HybridFusion::default(),
         }
     }
 
@@ -88,7 +145,24 @@ impl<'a> Search<'a> {
         embedder: Arc<Embedder>,
         vector: Option<Vec<f32>>,
     ) -> &mut Search<'a> {
-        self.semantic = Some(SemanticSearch { embedder_name, embedder, vector });
+        self.semantic =
+            Some(SemanticSearch { embedder_name, embedder, vector, sparse_vector: None });
+        self
+    }
+
+    /// Searches the documents whose sparse vector has the highest dot product with the given one.
+    pub fn sparse_semantic(
+        &mut self,
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        sparse_vector: SparseVector,
+    ) -> &mut Search<'a> {
+        self.semantic = Some(SemanticSearch {
+            embedder_name,
+            embedder,
+            vector: None,
+            sparse_vector: Some(sparse_vector),
+        });
         self
     }
 
@@ -112,6 +186,39 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Groups the documents by the values of the given field.
+    ///
+    /// Only the best document of each group is returned in the `documents_ids`, the `offset` and
+    /// `limit` apply to the groups. The next best documents of each group, up to `inner_hits`,
+    /// are returned along with the size of the group in the `collapsed_groups`.
+    ///
+    /// The field takes precedence over any distinct attribute for the groups themselves, but the
+    /// distinct attribute of the index still applies to the documents inside of a group.
+    pub fn collapse(&mut self, field: String, inner_hits: usize) -> &mut Search<'a> {
+        self.collapse = Some(Collapse { field, inner_hits });
+        self
+    }
+
+    /// Ranks the documents by the sum of the weights of the clauses whose filter they match.
+    ///
+    /// The documents are boosted by a ranking rule inserted before the ranking rule of the
+    /// settings at `position`, or after all of them if `position` is out of bounds.
+    pub fn boost(&mut self, clauses: Vec<BoostClause>, position: usize) -> &mut Search<'a> {
+        self.boost = Some(SearchBoost { clauses, position });
+        self
+    }
+
+    /// Resumes the search right after the document at the position of the cursor.
+    ///
+    /// The cursor must have been returned by the same search, see [`SearchResult::cursor`], or be
+    /// the default cursor to start from the first document. The documents pinned by the query
+    /// rules are ranked like the others in a search using a cursor, and the cursor is ignored by
+    /// the vector searches.
+    pub fn search_after(&mut self, cursor: SearchCursor) -> &mut Search<'a> {
+        self.search_after = Some(cursor);
+        self
+    }
+
     pub fn searchable_attributes(&mut self, searchable: &'a [String]) -> &mut Search<'a> {
         self.searchable_attributes = Some(searchable);
         self
@@ -160,16 +267,38 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Sets how the keyword and semantic results are merged by [`Search::execute_hybrid`].
+    pub fn hybrid_fusion(&mut self, fusion: HybridFusion) -> &mut Search<'a> {
+        self.hybrid_fusion = fusion;
+        self
+    }
+
     pub fn execute_for_candidates(&self, has_vector_search: bool) -> Result<RoaringBitmap> {
         if has_vector_search {
-            let ctx = SearchContext::new(self.index, self.rtxn)?;
-            filtered_universe(ctx.index, ctx.txn, &self.filter)
+            let rules = self.query_rules()?;
+            let pinned: RoaringBitmap = rules.pins.iter().map(|&(_, docid)| docid).collect();
+            Ok(rules.universe | pinned)
         } else {
             Ok(self.execute()?.candidates)
         }
     }
 
     pub fn execute(&self) -> Result<SearchResult> {
+        Ok(self.execute_and_explain(false)?.0)
+    }
+
+    /// Executes the search and describes how the documents were ranked.
+    ///
+    /// Only the keyword part of the search is explained.
+    pub fn explain(&self) -> Result<(SearchResult, SearchExplanation)> {
+        let (result, explanation) = self.execute_and_explain(true)?;
+        Ok((result, explanation.unwrap_or_default()))
+    }
+
+    fn execute_and_explain(
+        &self,
+        explain: bool,
+    ) -> Result<(SearchResult, Option<SearchExplanation>)> {
         let mut ctx = SearchContext::new(self.index, self.rtxn)?;
 
         if let Some(searchable_attributes) = self.searchable_attributes {
@@ -189,30 +318,83 @@ impl<'a> Search<'a> {
             }
         }
 
-        let universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
+        if let Some(Collapse { field, .. }) = &self.collapse {
+            let filterable_fields = ctx.index.filterable_fields(ctx.txn)?;
+            if !crate::is_faceted(field, &filterable_fields) {
+                let (valid_fields, hidden_fields) =
+                    ctx.index.remove_hidden_fields(ctx.txn, filterable_fields)?;
+                return Err(Error::UserError(UserError::InvalidCollapseAttribute {
+                    field: field.clone(),
+                    valid_fields,
+                    hidden_fields,
+                }));
+            }
+        }
+
+        // collapsing is a distinct on the groups, the inner hits are computed afterward
+        let distinct = match &self.collapse {
+            Some(Collapse { field, .. }) => Some(field.clone()),
+            None => self.distinct.clone(),
+        };
+
+        let rules = self.query_rules()?;
+        // the pinned documents are inserted afterward, the organic results start after the organic
+        // documents ranked before the offset
+        let pin_documents = self.pin_documents && self.search_after.is_none();
+        let offset = if pin_documents { rules.organic_offset(self.offset) } else { self.offset };
+        // the pinned documents can't be inserted at a position of the whole results when
+        // paginating with a cursor, they are ranked like the others
+        let universe = match self.search_after {
+            Some(_) => {
+                rules.pins.iter().map(|&(_, docid)| docid).collect::<RoaringBitmap>()
+                    | &rules.universe
+            }
+            None => rules.universe.clone(),
+        };
+
+        let mut default_placeholder_logger = DefaultSearchLogger;
+        let mut default_query_graph_logger = DefaultSearchLogger;
+        let mut placeholder_explain_logger = ExplainSearchLogger::default();
+        let mut query_graph_explain_logger = ExplainSearchLogger::default();
+        let (placeholder_search_logger, query_graph_logger): (
+            &mut dyn SearchLogger<PlaceholderQuery>,
+            &mut dyn SearchLogger<QueryGraph>,
+        ) = if explain {
+            (&mut placeholder_explain_logger, &mut query_graph_explain_logger)
+        } else {
+            (&mut default_placeholder_logger, &mut default_query_graph_logger)
+        };
+
         let PartialSearchResult {
             located_query_terms,
             candidates,
             documents_ids,
             document_scores,
+            cursor,
             degraded,
             used_negative_operator,
-        } = match self.semantic.as_ref() {
-            Some(SemanticSearch { vector: Some(vector), embedder_name, embedder }) => {
+        } = match self
+            .semantic
+            .as_ref()
+            .and_then(|semantic| Some((semantic, semantic.query_vector()?)))
+        {
+            Some((SemanticSearch { embedder_name, embedder, .. }, vector)) => {
                 execute_vector_search(
                     &mut ctx,
                     vector,
                     self.scoring_strategy,
                     universe,
                     &self.sort_criteria,
-                    &self.distinct,
+                    &distinct,
                     self.geo_strategy,
-                    self.offset,
+                    offset,
                     self.limit,
                     embedder_name,
                     embedder,
                     self.time_budget.clone(),
                     self.ranking_score_threshold,
+                    &rules.boosts,
+                    self.boost.as_ref(),
                 )?
             }
             _ => execute_search(
@@ -223,32 +405,200 @@ impl<'a> Search<'a> {
                 self.exhaustive_number_hits,
                 universe,
                 &self.sort_criteria,
-                &self.distinct,
+                &distinct,
                 self.geo_strategy,
-                self.offset,
+                offset,
                 self.limit,
                 Some(self.words_limit),
-                &mut DefaultSearchLogger,
-                &mut DefaultSearchLogger,
+                placeholder_search_logger,
+                query_graph_logger,
                 self.time_budget.clone(),
                 self.ranking_score_threshold,
+                &rules.boosts,
+                self.boost.as_ref(),
+                self.search_after.as_ref(),
             )?,
         };
 
+        // only one of the loggers is used, depending on the kind of search
+        let explanation = if !explain {
+            None
+        } else if query_graph_explain_logger.is_empty() {
+            Some(placeholder_explain_logger.finish(&mut ctx)?)
+        } else {
+            Some(query_graph_explain_logger.finish(&mut ctx)?)
+        };
+
         // consume context and located_query_terms to build MatchingWords.
         let matching_words = match located_query_terms {
             Some(located_query_terms) => MatchingWords::new(ctx, located_query_terms),
             None => MatchingWords::default(),
         };
 
-        Ok(SearchResult {
+        let mut result = SearchResult {
             matching_words,
             candidates,
             document_scores,
             documents_ids,
+            collapsed_groups: Vec::new(),
+            applied_rules: Vec::new(),
+            pinned_documents: RoaringBitmap::new(),
+            cursor,
             degraded,
             used_negative_operator,
-        })
+        };
+        if pin_documents {
+            rules.pin_documents(&mut result, self.offset, self.limit);
+        }
+        result.applied_rules = rules.ids;
+
+        if let Some(collapse) = &self.collapse {
+            result.collapsed_groups =
+                self.collapse_groups(collapse, &result.documents_ids, None)?;
+        }
+
+        Ok((result, explanation))
+    }
+
+    /// Evaluates the query rules of the index that are triggered by this search.
+    fn query_rules(&self) -> Result<TriggeredRules> {
+        let mut universe = filtered_universe(self.index, self.rtxn, &self.filter)?;
+        if let Some(restricted_universe) = &self.restricted_universe {
+            universe &= restricted_universe;
+        }
+        if !self.apply_query_rules {
+            return Ok(TriggeredRules { universe, ..Default::default() });
+        }
+
+        let filter = self.filter.clone().map(FilterCondition::from);
+        let external_documents_ids = self.index.external_documents_ids();
+        let mut rules = TriggeredRules::default();
+        let mut hidden = RoaringBitmap::new();
+        let mut pins = Vec::new();
+        for rule in self.index.query_rules(self.rtxn)? {
+            if !rule.is_triggered(self.query.as_deref(), filter.as_ref()) {
+                continue;
+            }
+            for external_id in &rule.actions.hide {
+                if let Some(docid) = external_documents_ids.get(self.rtxn, external_id)? {
+                    hidden.insert(docid);
+                }
+            }
+            for pin in &rule.actions.pin {
+                if let Some(docid) = external_documents_ids.get(self.rtxn, &pin.id)? {
+                    pins.push((pin.position, docid));
+                }
+            }
+            rules.boosts.extend(rule.actions.boost);
+            rules.ids.push(rule.id);
+        }
+
+        universe -= hidden;
+        // a document is pinned at its first position only, if it matches the filter and isn't hidden
+        pins.sort_by_key(|&(position, _)| position);
+        let mut pinned = RoaringBitmap::new();
+        rules.pins = pins
+            .into_iter()
+            .filter(|&(_, docid)| universe.contains(docid) && pinned.insert(docid))
+            .collect();
+        // the pinned documents are inserted in the results afterward
+        rules.universe = universe - pinned;
+
+        Ok(rules)
+    }
+
+    /// Computes the inner hits and the size of the group of each of the given documents.
+    ///
+    /// The documents of a group are ranked by running the same search again, restricted to the
+    /// documents sharing a value with the best document of the group. When no inner hits are
+    /// requested, the sizes of all the groups are computed from the candidates of a single search.
+    fn collapse_groups(
+        &self,
+        collapse: &Collapse,
+        documents_ids: &[DocumentId],
+        semantic_ratio: Option<f32>,
+    ) -> Result<Vec<CollapsedGroup>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let field_id = fields_ids_map.id(&collapse.field);
+
+        let mut groups_docids = Vec::with_capacity(documents_ids.len());
+        for &docid in documents_ids {
+            let mut group_docids = RoaringBitmap::new();
+            if let Some(field_id) = field_id {
+                distinct_single_docid(self.index, self.rtxn, field_id, docid, &mut group_docids)?;
+            }
+            // a document without any value for the field is alone in its group
+            group_docids.insert(docid);
+            groups_docids.push(group_docids);
+        }
+
+        let inner_search = |universe: RoaringBitmap, limit: usize| Search {
+            query: self.query.clone(),
+            filter: self.filter.clone(),
+            offset: 0,
+            limit,
+            sort_criteria: self.sort_criteria.clone(),
+            distinct: None,
+            collapse: None,
+            boost: self.boost.clone(),
+            search_after: None,
+            searchable_attributes: self.searchable_attributes,
+            geo_strategy: self.geo_strategy,
+            terms_matching_strategy: self.terms_matching_strategy,
+            scoring_strategy: self.scoring_strategy,
+            words_limit: self.words_limit,
+            // the size of the group must be exact
+            exhaustive_number_hits: true,
+            rtxn: self.rtxn,
+            index: self.index,
+            semantic: self.semantic.clone(),
+            time_budget: self.time_budget.clone(),
+            ranking_score_threshold: self.ranking_score_threshold,
+            restricted_universe: Some(universe),
+            pin_documents: false,
+            apply_query_rules: self.apply_query_rules,
+            hybrid_fusion: self.hybrid_fusion,
+        };
+
+        if collapse.inner_hits == 0 {
+            let universe =
+                groups_docids.iter().fold(RoaringBitmap::new(), |acc, group| acc | group);
+            let candidates =
+                inner_search(universe, 0).execute_for_candidates(semantic_ratio.is_some())?;
+            return Ok(groups_docids
+                .iter()
+                .map(|group_docids| CollapsedGroup {
+                    total_hits: group_docids.intersection_len(&candidates).max(1),
+                    ..Default::default()
+                })
+                .collect());
+        }
+
+        let mut groups = Vec::with_capacity(documents_ids.len());
+        for (&docid, group_docids) in documents_ids.iter().zip(groups_docids) {
+            // we also fetch the best document of the group, that is skipped right after
+            let search = inner_search(group_docids, collapse.inner_hits.saturating_add(1));
+            let result = match semantic_ratio {
+                Some(semantic_ratio) => search.execute_hybrid(semantic_ratio)?.0,
+                None => search.execute()?,
+            };
+
+            let (inner_hits, inner_hits_scores): (Vec<_>, Vec<_>) = result
+                .documents_ids
+                .into_iter()
+                .zip(result.document_scores)
+                .filter(|(inner_docid, _)| *inner_docid != docid)
+                .take(collapse.inner_hits)
+                .unzip();
+
+            groups.push(CollapsedGroup {
+                inner_hits,
+                inner_hits_scores,
+                total_hits: result.candidates.len().max(1),
+            });
+        }
+
+        Ok(groups)
     }
 }
 
@@ -261,6 +611,9 @@ impl fmt::Debug for Search<'_> {
             limit,
             sort_criteria,
             distinct,
+            collapse,
+            boost,
+            search_after,
             searchable_attributes,
             geo_strategy: _,
             terms_matching_strategy,
@@ -272,6 +625,10 @@ impl fmt::Debug for Search<'_> {
             semantic,
             time_budget,
             ranking_score_threshold,
+            restricted_universe: _,
+            pin_documents: _,
+            apply_query_rules: _,
+            hybrid_fusion,
         } = self;
         f.debug_struct("Search")
             .field("query", query)
@@ -281,6 +638,9 @@ impl fmt::Debug for Search<'_> {
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
             .field("distinct", distinct)
+            .field("collapse", collapse)
+            .field("boost", boost)
+            .field("search_after", search_after)
             .field("searchable_attributes", searchable_attributes)
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("scoring_strategy", scoring_strategy)
@@ -292,6 +652,7 @@ impl fmt::Debug for Search<'_> {
             )
             .field("time_budget", time_budget)
             .field("ranking_score_threshold", ranking_score_threshold)
+            .field("hybrid_fusion", hybrid_fusion)
             .finish()
     }
 }
@@ -302,10 +663,95 @@ pub struct SearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// The group of each document of `documents_ids` when the search is collapsed, empty otherwise.
+    pub collapsed_groups: Vec<CollapsedGroup>,
+    /// The ids of the query rules of the index that were applied to the search.
+    pub applied_rules: Vec<String>,
+    /// The documents of `documents_ids` inserted at a fixed position by the query rules.
+    pub pinned_documents: RoaringBitmap,
+    /// The position of the last document of `documents_ids`, see [`Search::search_after`].
+    pub cursor: Option<SearchCursor>,
     pub degraded: bool,
     pub used_negative_operator: bool,
 }
 
+/// The documents sharing a value with a collapsed document.
+#[derive(Default, Debug, Clone)]
+pub struct CollapsedGroup {
+    /// The best documents of the group after the collapsed document, in ranking order.
+    pub inner_hits: Vec<DocumentId>,
+    pub inner_hits_scores: Vec<Vec<ScoreDetails>>,
+    /// The number of documents of the group matching the search, including the collapsed document.
+    pub total_hits: u64,
+}
+
+/// The effects of the query rules triggered by a search.
+#[derive(Debug, Default)]
+struct TriggeredRules {
+    ids: Vec<String>,
+    /// The documents matching the filter of the search, without the hidden and pinned documents.
+    universe: RoaringBitmap,
+    /// The documents to insert in the results along with their positions, ordered by position.
+    pins: Vec<(usize, DocumentId)>,
+    boosts: Vec<BoostClause>,
+}
+
+impl TriggeredRules {
+    /// The number of organic documents ranked before the given offset, when there are enough of them.
+    fn organic_offset(&self, offset: usize) -> usize {
+        let mut pins = self.pins.iter().peekable();
+        (0..offset).filter(|&position| pins.next_if(|(pin, _)| *pin <= position).is_none()).count()
+    }
+
+    /// Inserts the pinned documents among the organic results.
+    ///
+    /// The organic results are expected to start at the [`Self::organic_offset`] of the offset.
+    /// A document is pinned at its position, or right after the previous pinned document when they
+    /// share a position, or right after the last organic document if there aren't enough of them.
+    fn pin_documents(&self, result: &mut SearchResult, offset: usize, limit: usize) {
+        if self.pins.is_empty() {
+            return;
+        }
+
+        let mut organic_count = result.candidates.len();
+        let mut organic_index = 0;
+        let mut organic = std::mem::take(&mut result.documents_ids)
+            .into_iter()
+            .zip(std::mem::take(&mut result.document_scores));
+        let mut pins = self.pins.iter().peekable();
+
+        let mut position = 0;
+        while position < offset.saturating_add(limit) {
+            let pin = pins.next_if(|(pin, _)| *pin <= position || organic_index >= organic_count);
+            if let Some(&(_, docid)) = pin {
+                if position >= offset {
+                    result.documents_ids.push(docid);
+                    result.document_scores.push(Vec::new());
+                    result.pinned_documents.insert(docid);
+                }
+            } else if position < offset {
+                if organic_index >= organic_count {
+                    break;
+                }
+                organic_index += 1;
+            } else if let Some((docid, scores)) = organic.next() {
+                organic_index += 1;
+                result.documents_ids.push(docid);
+                result.document_scores.push(scores);
+            } else if pins.peek().is_some() {
+                // there are less organic documents than expected, the pins are placed right after
+                organic_count = organic_index;
+                continue;
+            } else {
+                break;
+            }
+            position += 1;
+        }
+
+        result.candidates.extend(self.pins.iter().map(|&(_, docid)| docid));
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TermsMatchingStrategy {
     // remove last word first
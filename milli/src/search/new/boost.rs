@@ -0,0 +1,108 @@
+use roaring::RoaringBitmap;
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::query_rules::BoostClause;
+use crate::score_details::{self, Rank, ScoreDetails};
+use crate::{Filter, Result};
+
+/// A ranking rule that sorts the documents by the sum of the weights of the filters they match.
+///
+/// The documents are grouped once, when the ranking rule is created, so that the rank of a
+/// given sum of weights is the same in all the buckets.
+pub struct Boost<Query> {
+    /// The documents of the universe grouped by sum of weights, from the highest sum to the lowest.
+    groups: Vec<(i64, RoaringBitmap)>,
+    score: fn(score_details::Boost) -> ScoreDetails,
+    next_group: usize,
+    original_query: Option<Query>,
+}
+
+impl<Query> Boost<Query> {
+    pub fn new(
+        ctx: &SearchContext<'_>,
+        clauses: &[BoostClause],
+        universe: &RoaringBitmap,
+        score: fn(score_details::Boost) -> ScoreDetails,
+    ) -> Result<Self> {
+        let mut groups = vec![(0, universe.clone())];
+        for BoostClause { filter, weight } in clauses {
+            let Some(filter) = Filter::from_str(filter)? else { continue };
+            let matching = filter.evaluate(ctx.txn, ctx.index)?;
+
+            let mut split_groups = Vec::with_capacity(groups.len() * 2);
+            for (sum, docids) in groups {
+                let (matching, others) = (&docids & &matching, docids - &matching);
+                split_groups.push((sum + *weight as i64, matching));
+                split_groups.push((sum, others));
+            }
+            split_groups.retain(|(_, docids)| !docids.is_empty());
+
+            // merge the groups that ended up with the same sum of weights
+            split_groups.sort_unstable_by_key(|(sum, _)| std::cmp::Reverse(*sum));
+            groups = Vec::with_capacity(split_groups.len());
+            for (sum, docids) in split_groups {
+                match groups.last_mut() {
+                    Some((last_sum, last_docids)) if *last_sum == sum => *last_docids |= docids,
+                    _ => groups.push((sum, docids)),
+                }
+            }
+        }
+
+        Ok(Self { groups, score, next_group: 0, original_query: None })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Boost<Query> {
+    fn id(&self) -> String {
+        "boost".to_owned()
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        _universe: &RoaringBitmap,
+        query: &Query,
+    ) -> Result<()> {
+        self.original_query = Some(query.clone());
+        self.next_group = 0;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        let max_rank = self.groups.len() as u32;
+
+        while let Some((weight, docids)) = self.groups.get(self.next_group) {
+            let rank = max_rank - self.next_group as u32;
+            self.next_group += 1;
+
+            let candidates = universe & docids;
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let boost = score_details::Boost { weight: *weight, rank: Rank { rank, max_rank } };
+            return Ok(Some(RankingRuleOutput { query, candidates, score: (self.score)(boost) }));
+        }
+
+        Ok(None)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+    }
+}
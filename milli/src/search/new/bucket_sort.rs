@@ -1,20 +1,100 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
 use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
 use super::ranking_rules::{BoxRankingRule, RankingRuleQueryTrait};
 use super::SearchContext;
+use crate::error::UserError;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::{apply_distinct_rule, distinct_single_docid, DistinctOutput};
-use crate::{Result, TimeBudget};
+use crate::{DocumentId, Result, TimeBudget};
 
 pub struct BucketSortOutput {
     pub docids: Vec<u32>,
     pub scores: Vec<Vec<ScoreDetails>>,
     pub all_candidates: RoaringBitmap,
+    /// The position of the last returned document.
+    pub cursor: Option<SearchCursor>,
 
     pub degraded: bool,
 }
 
+/// The position of a document in the results of a search, to resume the search right after it.
+///
+/// It is made of the index of the bucket containing the document for each ranking rule
+/// the document went through, followed by the id of the document. A cursor is only meaningful
+/// for the search it was returned by, on the same version of the index.
+///
+/// The default cursor is positioned before the first document.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SearchCursor {
+    buckets: Vec<u32>,
+    docid: Option<DocumentId>,
+}
+
+impl SearchCursor {
+    /// Returns the greatest document id to skip in the bucket at the given path,
+    /// or `None` if the whole bucket comes after the cursor.
+    fn skip_up_to(&self, bucket_path: &[u32]) -> Option<DocumentId> {
+        for (bucket, cursor_bucket) in bucket_path.iter().zip(&self.buckets) {
+            match bucket.cmp(cursor_bucket) {
+                Ordering::Less => return Some(DocumentId::MAX),
+                Ordering::Greater => return None,
+                Ordering::Equal => (),
+            }
+        }
+        self.docid
+    }
+
+    /// Returns `true` if the cursor comes after the whole bucket at the given path.
+    fn is_after(&self, bucket_path: &[u32]) -> bool {
+        self.skip_up_to(bucket_path) == Some(DocumentId::MAX)
+    }
+}
+
+/// The cursor is encoded as the hexadecimal representation of its bucket indexes and document id,
+/// the default cursor is encoded as an empty string.
+impl fmt::Display for SearchCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(docid) = self.docid else { return Ok(()) };
+        for bucket in &self.buckets {
+            write!(f, "{bucket:08x}")?;
+        }
+        write!(f, "{docid:08x}")
+    }
+}
+
+impl FromStr for SearchCursor {
+    type Err = UserError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || UserError::InvalidSearchCursor { cursor: s.to_string() };
+        if s.is_empty() {
+            return Ok(SearchCursor::default());
+        }
+        if s.len() % 8 != 0 || !s.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut numbers = s
+            .as_bytes()
+            .chunks(8)
+            .map(|chunk| {
+                std::str::from_utf8(chunk)
+                    .ok()
+                    .and_then(|chunk| u32::from_str_radix(chunk, 16).ok())
+                    .ok_or_else(invalid)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let docid = numbers.pop().ok_or_else(invalid)?;
+
+        Ok(SearchCursor { buckets: numbers, docid: Some(docid) })
+    }
+}
+
 // TODO: would probably be good to regroup some of these inside of a struct?
 #[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip_all, target = "search::bucket_sort")]
@@ -30,6 +110,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     logger: &mut dyn SearchLogger<Q>,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
+    search_after: Option<&SearchCursor>,
 ) -> Result<BucketSortOutput> {
     logger.initial_query(query);
     logger.ranking_rules(&ranking_rules);
@@ -51,13 +132,20 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
             docids: vec![],
             scores: vec![],
             all_candidates: universe.clone(),
+            cursor: None,
             degraded: false,
         });
     }
     if ranking_rules.is_empty() {
+        let skip_up_to = search_after.and_then(|cursor| cursor.skip_up_to(&[]));
+        let cursor_at = |docids: &[u32]| {
+            docids.last().map(|&docid| SearchCursor { buckets: Vec::new(), docid: Some(docid) })
+        };
+
         if let Some(distinct_fid) = distinct_fid {
             let mut excluded = RoaringBitmap::new();
             let mut results = vec![];
+            let mut before_cursor = RoaringBitmap::new();
             for docid in universe.iter() {
                 if results.len() >= from + length {
                     break;
@@ -67,10 +155,18 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 }
 
                 distinct_single_docid(ctx.index, ctx.txn, distinct_fid, docid, &mut excluded)?;
-                results.push(docid);
+                // the documents before the cursor were already returned, but they still exclude
+                // the documents sharing their distinct value
+                if skip_up_to.map_or(true, |skip_up_to| docid > skip_up_to) {
+                    results.push(docid);
+                } else {
+                    before_cursor.insert(docid);
+                }
             }
 
             let mut all_candidates = universe - excluded;
+            // the documents returned by the previous pages are still part of the total hits
+            all_candidates |= before_cursor;
             all_candidates.extend(results.iter().copied());
             // drain the results of the skipped elements
             // this **must** be done **after** writing the entire results in `all_candidates` to ensure
@@ -83,14 +179,20 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
 
             return Ok(BucketSortOutput {
                 scores: vec![Default::default(); results.len()],
+                cursor: cursor_at(&results),
                 docids: results,
                 all_candidates,
                 degraded: false,
             });
         } else {
-            let docids: Vec<u32> = universe.iter().skip(from).take(length).collect();
+            let mut after_cursor = universe.clone();
+            if let Some(skip_up_to) = skip_up_to {
+                after_cursor.remove_range(..=skip_up_to);
+            }
+            let docids: Vec<u32> = after_cursor.iter().skip(from).take(length).collect();
             return Ok(BucketSortOutput {
                 scores: vec![Default::default(); docids.len()],
+                cursor: cursor_at(&docids),
                 docids,
                 all_candidates: universe.clone(),
                 degraded: false,
@@ -105,6 +207,9 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     ranking_rules[0].start_iteration(ctx, logger, universe, query)?;
 
     let mut ranking_rule_scores: Vec<ScoreDetails> = vec![];
+    // the index of the current bucket of each ranking rule, among the buckets of its parent
+    let mut bucket_path: Vec<u32> = vec![];
+    let mut next_bucket_index: Vec<u32> = vec![0; ranking_rules_len];
 
     let mut ranking_rule_universes: Vec<RoaringBitmap> =
         vec![RoaringBitmap::default(); ranking_rules_len];
@@ -137,6 +242,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
             if ranking_rule_scores.len() > cur_ranking_rule_index {
                 ranking_rule_scores.pop();
             }
+            bucket_path.truncate(cur_ranking_rule_index);
         };
     }
 
@@ -144,6 +250,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     let mut valid_docids = vec![];
     let mut valid_scores = vec![];
     let mut cur_offset = 0usize;
+    let mut cursor = None;
 
     macro_rules! maybe_add_to_results {
         ($candidates:expr) => {
@@ -161,6 +268,9 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 &mut cur_offset,
                 distinct_fid,
                 &ranking_rule_scores,
+                &bucket_path,
+                search_after,
+                &mut cursor,
                 $candidates,
             )?;
         };
@@ -197,6 +307,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 scores: valid_scores,
                 docids: valid_docids,
                 all_candidates,
+                cursor,
                 degraded: true,
             });
         }
@@ -224,6 +335,8 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         };
 
         ranking_rule_scores.push(next_bucket.score);
+        bucket_path.push(next_bucket_index[cur_ranking_rule_index]);
+        next_bucket_index[cur_ranking_rule_index] += 1;
 
         logger.next_bucket_ranking_rule(
             cur_ranking_rule_index,
@@ -252,13 +365,16 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         if cur_ranking_rule_index == ranking_rules_len - 1
             || (scoring_strategy == ScoringStrategy::Skip && next_bucket.candidates.len() <= 1)
             || cur_offset + (next_bucket.candidates.len() as usize) < from
+            || search_after.map_or(false, |cursor| cursor.is_after(&bucket_path))
         {
             maybe_add_to_results!(next_bucket.candidates);
             ranking_rule_scores.pop();
+            bucket_path.pop();
             continue;
         }
 
         cur_ranking_rule_index += 1;
+        next_bucket_index[cur_ranking_rule_index] = 0;
         ranking_rule_universes[cur_ranking_rule_index].clone_from(&next_bucket.candidates);
         logger.start_iteration_ranking_rule(
             cur_ranking_rule_index,
@@ -278,12 +394,13 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         docids: valid_docids,
         scores: valid_scores,
         all_candidates,
+        cursor,
         degraded: false,
     })
 }
 
-/// Add the candidates to the results. Take `distinct`, `from`, `length`, and `cur_offset`
-/// into account and inform the logger.
+/// Add the candidates to the results. Take `distinct`, `from`, `length`, `cur_offset`, and
+/// `search_after` into account and inform the logger.
 #[allow(clippy::too_many_arguments)]
 fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
     ctx: &mut SearchContext<'ctx>,
@@ -304,6 +421,9 @@ fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
 
     distinct_fid: Option<u16>,
     ranking_rule_scores: &[ScoreDetails],
+    bucket_path: &[u32],
+    search_after: Option<&SearchCursor>,
+    cursor: &mut Option<SearchCursor>,
     candidates: RoaringBitmap,
 ) -> Result<()> {
     // First apply the distinct rule on the candidates, reducing the universes if necessary
@@ -320,10 +440,22 @@ fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
     };
     *all_candidates |= &candidates;
 
+    // the documents up to the cursor were returned by the previous pages
+    let mut candidates = candidates;
+    match search_after.and_then(|cursor| cursor.skip_up_to(bucket_path)) {
+        // the whole bucket comes before the cursor
+        Some(DocumentId::MAX) => return Ok(()),
+        Some(skip_up_to) => {
+            candidates.remove_range(..=skip_up_to);
+        }
+        None => (),
+    }
+
     // if the candidates are empty, there is nothing to do;
     if candidates.is_empty() {
         return Ok(());
     }
+    let previous_results_len = valid_docids.len();
 
     // if we still haven't reached the first document to return
     if *cur_offset < from {
@@ -361,6 +493,10 @@ fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
             .extend(std::iter::repeat(ranking_rule_scores.to_owned()).take(candidates.len()));
     }
 
+    if let Some(&docid) = valid_docids.get(previous_results_len..).and_then(<[u32]>::last) {
+        *cursor = Some(SearchCursor { buckets: bucket_path.to_vec(), docid: Some(docid) });
+    }
+
     *cur_offset += candidates.len() as usize;
     Ok(())
 }
@@ -0,0 +1,247 @@
+use std::any::Any;
+
+use roaring::RoaringBitmap;
+use serde::Serialize;
+
+use crate::search::new::interner::Interned;
+use crate::search::new::query_graph::QueryNodeData;
+use crate::search::new::query_term::{LocatedQueryTermSubset, Phrase};
+use crate::search::new::ranking_rules::BoxRankingRule;
+use crate::search::new::{
+    QueryGraph, RankingRule, RankingRuleQueryTrait, SearchContext, SearchLogger,
+};
+use crate::{DocumentId, Result};
+
+/// The maximum number of hits listed for each bucket of the explanation.
+const MAX_HITS_PER_BUCKET: usize = 10;
+
+/// A description of how a search was executed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchExplanation {
+    /// The terms of the query, along with the words derived from them
+    pub query_terms: Vec<ExplainedQueryTerm>,
+    /// The nodes of the graph of all the interpretations of the query
+    pub query_graph: Vec<ExplainedQueryNode>,
+    /// The buckets computed by each ranking rule, in the order they were computed
+    pub ranking_rules: Vec<ExplainedRankingRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedQueryTerm {
+    /// The id of the node of the query graph holding this term
+    pub node: u16,
+    pub original: String,
+    /// The first and last positions of the term in the query
+    pub positions: (u16, u16),
+    pub is_prefix: bool,
+    pub is_ngram: bool,
+    pub max_typos: u8,
+    pub exact: Option<String>,
+    pub prefix_of: Vec<String>,
+    pub synonyms: Vec<String>,
+    pub prefix_db: Option<String>,
+    pub split_words: Option<String>,
+    pub one_typo: Vec<String>,
+    pub two_typos: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedQueryNode {
+    pub id: u16,
+    #[serde(rename = "type")]
+    pub kind: ExplainedQueryNodeKind,
+    /// The original word of the term, if the node is a term
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub term: Option<String>,
+    pub successors: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExplainedQueryNodeKind {
+    Start,
+    Term,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedRankingRule {
+    pub name: String,
+    pub buckets: Vec<ExplainedBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedBucket {
+    /// The number of documents the ranking rule was sorting when it computed this bucket
+    pub universe: u64,
+    /// The number of documents in the bucket
+    pub size: u64,
+    /// The external ids of the first hits returned from this bucket
+    pub first_hits: Vec<String>,
+}
+
+struct Bucket {
+    universe: u64,
+    size: u64,
+    first_hits: Vec<DocumentId>,
+}
+
+/// A [`SearchLogger`] recording what is needed to build a [`SearchExplanation`].
+#[derive(Default)]
+pub struct ExplainSearchLogger {
+    initial_query: Option<QueryGraph>,
+    ranking_rules_ids: Option<Vec<String>>,
+    buckets: Vec<Vec<Bucket>>,
+    /// The current bucket of each ranking rule being iterated, and its documents
+    current_buckets: Vec<Option<(usize, RoaringBitmap)>>,
+}
+
+impl<Q: RankingRuleQueryTrait> SearchLogger<Q> for ExplainSearchLogger {
+    fn initial_query(&mut self, query: &Q) {
+        let query: &dyn Any = query;
+        self.initial_query = query.downcast_ref::<QueryGraph>().cloned();
+    }
+
+    fn initial_universe(&mut self, _universe: &RoaringBitmap) {}
+
+    fn query_for_initial_universe(&mut self, _query: &Q) {}
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<'_, Q>]) {
+        self.ranking_rules_ids = Some(rr.iter().map(|rr| rr.id()).collect());
+        self.buckets = rr.iter().map(|_| Vec::new()).collect();
+    }
+
+    fn start_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<'_, Q>,
+        _query: &Q,
+        _universe: &RoaringBitmap,
+    ) {
+        self.current_buckets.truncate(ranking_rule_idx);
+        self.current_buckets.push(None);
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<'_, Q>,
+        universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        let Some(buckets) = self.buckets.get_mut(ranking_rule_idx) else { return };
+        buckets.push(Bucket {
+            universe: universe.len(),
+            size: candidates.len(),
+            first_hits: Vec::new(),
+        });
+        self.current_buckets.truncate(ranking_rule_idx);
+        self.current_buckets.push(Some((buckets.len() - 1, candidates.clone())));
+    }
+
+    fn end_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<'_, Q>,
+        _universe: &RoaringBitmap,
+    ) {
+        self.current_buckets.truncate(ranking_rule_idx);
+    }
+
+    fn add_to_results(&mut self, docids: &[u32]) {
+        for (ranking_rule_idx, current) in self.current_buckets.iter().enumerate() {
+            let Some((bucket_idx, candidates)) = current else { continue };
+            let bucket = &mut self.buckets[ranking_rule_idx][*bucket_idx];
+            let remaining = MAX_HITS_PER_BUCKET.saturating_sub(bucket.first_hits.len());
+            bucket.first_hits.extend(
+                docids.iter().copied().filter(|docid| candidates.contains(*docid)).take(remaining),
+            );
+        }
+    }
+
+    fn log_internal_state(&mut self, _rr: &dyn Any) {}
+}
+
+impl ExplainSearchLogger {
+    /// Returns `true` if the logger wasn't used by the search.
+    pub fn is_empty(&self) -> bool {
+        self.initial_query.is_none() && self.ranking_rules_ids.is_none()
+    }
+
+    pub fn finish(self, ctx: &mut SearchContext<'_>) -> Result<SearchExplanation> {
+        let mut explanation = SearchExplanation::default();
+
+        if let Some(graph) = &self.initial_query {
+            for (node_id, node) in graph.nodes.iter() {
+                let (kind, term) = match &node.data {
+                    QueryNodeData::Deleted => continue,
+                    QueryNodeData::Start => (ExplainedQueryNodeKind::Start, None),
+                    QueryNodeData::End => (ExplainedQueryNodeKind::End, None),
+                    QueryNodeData::Term(term) => {
+                        explanation.query_terms.push(explain_term(ctx, node_id.into_raw(), term)?);
+                        (ExplainedQueryNodeKind::Term, Some(term.term_subset.description(ctx)))
+                    }
+                };
+                explanation.query_graph.push(ExplainedQueryNode {
+                    id: node_id.into_raw(),
+                    kind,
+                    term,
+                    successors: node.successors.iter().map(|id| id.into_raw()).collect(),
+                });
+            }
+        }
+
+        let ranking_rules_ids = self.ranking_rules_ids.unwrap_or_default();
+        for (name, buckets) in ranking_rules_ids.into_iter().zip(self.buckets) {
+            let buckets = buckets
+                .into_iter()
+                .map(|Bucket { universe, size, first_hits }| -> Result<_> {
+                    let first_hits = ctx
+                        .index
+                        .external_id_of(ctx.txn, first_hits)?
+                        .into_iter()
+                        .collect::<Result<_>>()?;
+                    Ok(ExplainedBucket { universe, size, first_hits })
+                })
+                .collect::<Result<_>>()?;
+            explanation.ranking_rules.push(ExplainedRankingRule { name, buckets });
+        }
+
+        Ok(explanation)
+    }
+}
+
+fn explain_term(
+    ctx: &mut SearchContext<'_>,
+    node: u16,
+    term: &LocatedQueryTermSubset,
+) -> Result<ExplainedQueryTerm> {
+    let LocatedQueryTermSubset { term_subset, positions, term_ids: _ } = term;
+    let derivations = term_subset.derivations(ctx)?;
+
+    let word = |w: Interned<String>| ctx.word_interner.get(w).to_owned();
+    let words =
+        |words: Vec<Interned<String>>| -> Vec<String> { words.into_iter().map(word).collect() };
+    let phrase = |p: Interned<Phrase>| p.description(ctx);
+
+    Ok(ExplainedQueryTerm {
+        node,
+        original: term_subset.description(ctx),
+        positions: (*positions.start(), *positions.end()),
+        is_prefix: term_subset.is_prefix(ctx),
+        is_ngram: term_subset.is_ngram(ctx),
+        max_typos: term_subset.max_typo_cost(ctx),
+        exact: derivations.exact.map(word),
+        prefix_of: words(derivations.prefix_of),
+        synonyms: derivations.synonyms.into_iter().map(phrase).collect(),
+        prefix_db: derivations.use_prefix_db.map(word),
+        split_words: derivations.split_words.map(phrase),
+        one_typo: words(derivations.one_typo),
+        two_typos: words(derivations.two_typos),
+    })
+}
@@ -9,11 +9,13 @@ use super::super::query_term::LocatedQueryTerm;
 use super::super::{DedupInterner, Phrase};
 use crate::SearchContext;
 
+#[derive(Clone)]
 pub struct LocatedMatchingPhrase {
     pub value: Interned<Phrase>,
     pub positions: RangeInclusive<WordId>,
 }
 
+#[derive(Clone)]
 pub struct LocatedMatchingWords {
     pub value: Vec<Interned<String>>,
     pub positions: RangeInclusive<WordId>,
@@ -23,7 +25,7 @@ pub struct LocatedMatchingWords {
 
 /// Structure created from a query tree
 /// referencing words that match the given query tree.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct MatchingWords {
     word_interner: DedupInterner<String>,
     phrase_interner: DedupInterner<Phrase>,
@@ -103,6 +103,10 @@ pub struct Match {
 pub struct MatchBounds {
     pub start: usize,
     pub length: usize,
+    /// The position of the matched value in its (possibly nested) arrays, from the outermost
+    /// array to the innermost one. `None` when the value isn't part of an array.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indices: Option<Vec<usize>>,
 }
 
 /// Structure used to analyze a string, compute words that match,
@@ -230,14 +234,18 @@ impl<'t, 'tokenizer> Matcher<'t, 'tokenizer, '_> {
     }
 
     /// Returns boundaries of the words that match the query.
-    pub fn matches(&mut self) -> Vec<MatchBounds> {
+    ///
+    /// `array_indices` is the position of the analyzed text in the arrays containing it,
+    /// it is reported as is in every returned [`MatchBounds`].
+    pub fn matches(&mut self, array_indices: &[usize]) -> Vec<MatchBounds> {
         match &self.matches {
-            None => self.compute_matches().matches(),
+            None => self.compute_matches().matches(array_indices),
             Some((tokens, matches)) => matches
                 .iter()
                 .map(|m| MatchBounds {
                     start: tokens[m.token_position].byte_start,
                     length: m.match_len,
+                    indices: (!array_indices.is_empty()).then(|| array_indices.to_vec()),
                 })
                 .collect(),
         }
@@ -530,6 +538,9 @@ mod tests {
                 &mut crate::DefaultSearchLogger,
                 TimeBudget::max(),
                 None,
+                &[],
+                None,
+                None,
             )
             .unwrap();
 
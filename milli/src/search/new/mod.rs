@@ -1,3 +1,4 @@
+mod boost;
 mod bucket_sort;
 mod db_cache;
 mod distinct;
@@ -23,6 +24,8 @@ mod tests;
 
 use std::collections::HashSet;
 
+use boost::Boost;
+pub use bucket_sort::SearchCursor;
 use bucket_sort::{bucket_sort, BucketSortOutput};
 use charabia::TokenizerBuilder;
 use db_cache::DatabaseCache;
@@ -30,31 +33,38 @@ use exact_attribute::ExactAttribute;
 use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, Typo};
 use heed::RoTxn;
 use interner::{DedupInterner, Interner};
+pub use logger::explain::{
+    ExplainSearchLogger, ExplainedBucket, ExplainedQueryNode, ExplainedQueryNodeKind,
+    ExplainedQueryTerm, ExplainedRankingRule, SearchExplanation,
+};
 pub use logger::visual::VisualSearchLogger;
 pub use logger::{DefaultSearchLogger, SearchLogger};
-use query_graph::{QueryGraph, QueryNode};
+pub(crate) use query_graph::QueryGraph;
+use query_graph::QueryNode;
 use query_term::{
     located_query_terms_from_tokens, ExtractedTokens, LocatedQueryTerm, Phrase, QueryTerm,
 };
-use ranking_rules::{
-    BoxRankingRule, PlaceholderQuery, RankingRule, RankingRuleOutput, RankingRuleQueryTrait,
-};
+pub(crate) use ranking_rules::PlaceholderQuery;
+use ranking_rules::{BoxRankingRule, RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use resolve_query_graph::{compute_query_graph_docids, PhraseDocIdsCache};
 use roaring::RoaringBitmap;
 use sort::Sort;
 
+pub(crate) use self::distinct::distinct_single_docid;
 use self::distinct::facet_string_values;
 use self::geo_sort::GeoSort;
 pub use self::geo_sort::Strategy as GeoSortStrategy;
 use self::graph_based_ranking_rule::Words;
 use self::interner::Interned;
+pub use self::vector_sort::QueryVector;
 use self::vector_sort::VectorSort;
+use crate::query_rules::BoostClause;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::apply_distinct_rule;
 use crate::vector::Embedder;
 use crate::{
-    AscDesc, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy, TimeBudget,
-    UserError, Weight,
+    AscDesc, DocumentId, Error, FieldId, Filter, Index, Member, Result, SearchBoost,
+    TermsMatchingStrategy, TimeBudget, UserError, Weight,
 };
 
 /// A structure used throughout the execution of a search query.
@@ -264,13 +274,15 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
     ctx: &SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
+    mut boost: Option<PositionedRankingRule<'ctx, PlaceholderQuery>>,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     let mut sort = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>> = vec![];
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
-    for rr in settings_ranking_rules {
+    for (position, rr) in settings_ranking_rules.into_iter().enumerate() {
+        push_ranking_rule_at(&mut ranking_rules, &mut boost, position);
         match rr {
             // These rules need a query to have an effect; ignore them in placeholder search
             crate::Criterion::Words
@@ -308,6 +320,7 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
             }
         }
     }
+    ranking_rules.extend(boost.map(|(_, boost)| boost));
     Ok(ranking_rules)
 }
 
@@ -316,9 +329,10 @@ fn get_ranking_rules_for_vector<'ctx>(
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
     limit_plus_offset: usize,
-    target: &[f32],
+    target: QueryVector<'_>,
     embedder_name: &str,
     embedder: &Embedder,
+    mut boost: Option<PositionedRankingRule<'ctx, PlaceholderQuery>>,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     // query graph search
 
@@ -330,7 +344,8 @@ fn get_ranking_rules_for_vector<'ctx>(
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>> = vec![];
 
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
-    for rr in settings_ranking_rules {
+    for (position, rr) in settings_ranking_rules.into_iter().enumerate() {
+        push_ranking_rule_at(&mut ranking_rules, &mut boost, position);
         match rr {
             crate::Criterion::Words
             | crate::Criterion::Typo
@@ -341,7 +356,7 @@ fn get_ranking_rules_for_vector<'ctx>(
                     let vector_candidates = ctx.index.documents_ids(ctx.txn)?;
                     let vector_sort = VectorSort::new(
                         ctx,
-                        target.to_vec(),
+                        target,
                         vector_candidates,
                         limit_plus_offset,
                         embedder_name,
@@ -381,6 +396,7 @@ fn get_ranking_rules_for_vector<'ctx>(
             }
         }
     }
+    ranking_rules.extend(boost.map(|(_, boost)| boost));
 
     Ok(ranking_rules)
 }
@@ -391,6 +407,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
     terms_matching_strategy: TermsMatchingStrategy,
+    mut boost: Option<PositionedRankingRule<'ctx, QueryGraph>>,
 ) -> Result<Vec<BoxRankingRule<'ctx, QueryGraph>>> {
     // query graph search
     let mut words = false;
@@ -409,7 +426,8 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
 
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, QueryGraph>> = vec![];
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
-    for rr in settings_ranking_rules {
+    for (position, rr) in settings_ranking_rules.into_iter().enumerate() {
+        push_ranking_rule_at(&mut ranking_rules, &mut boost, position);
         // Add Words before any of: typo, proximity, attribute
         match rr {
             crate::Criterion::Typo
@@ -491,6 +509,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
             }
         }
     }
+    ranking_rules.extend(boost.map(|(_, boost)| boost));
     Ok(ranking_rules)
 }
 
@@ -549,6 +568,58 @@ fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
     Ok(())
 }
 
+/// A ranking rule to insert before the ranking rule of the settings at the given position.
+type PositionedRankingRule<'ctx, Query> = (usize, BoxRankingRule<'ctx, Query>);
+
+/// Push the positioned ranking rule if it must be inserted before the ranking rule of the settings
+/// at `position`.
+fn push_ranking_rule_at<'ctx, Query>(
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+    ranking_rule: &mut Option<PositionedRankingRule<'ctx, Query>>,
+    position: usize,
+) {
+    if ranking_rule.as_ref().map_or(false, |(at, _)| *at <= position) {
+        if let Some((_, ranking_rule)) = ranking_rule.take() {
+            ranking_rules.push(ranking_rule);
+        }
+    }
+}
+
+/// Build the ranking rule applying the `boost` parameter of the search.
+fn boost_ranking_rule<'ctx, Query: RankingRuleQueryTrait>(
+    ctx: &SearchContext<'ctx>,
+    boost: Option<&SearchBoost>,
+    universe: &RoaringBitmap,
+) -> Result<Option<PositionedRankingRule<'ctx, Query>>> {
+    let Some(SearchBoost { clauses, position }) = boost else { return Ok(None) };
+    if clauses.is_empty() {
+        return Ok(None);
+    }
+
+    let boost =
+        Boost::new(ctx, clauses, universe, ScoreDetails::Boost).map_err(|error| match error {
+            Error::UserError(UserError::InvalidFilter(error)) => {
+                Error::UserError(UserError::InvalidBoostFilter(error))
+            }
+            error => error,
+        })?;
+    Ok(Some((*position, Box::new(boost))))
+}
+
+/// Insert the ranking rule applying the boosts of the query rules before all the other ranking rules.
+fn insert_boost_ranking_rule<'ctx, Query: RankingRuleQueryTrait>(
+    ctx: &SearchContext<'ctx>,
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+    boosts: &[BoostClause],
+    universe: &RoaringBitmap,
+) -> Result<()> {
+    if !boosts.is_empty() {
+        let boost = Boost::new(ctx, boosts, universe, ScoreDetails::QueryRules)?;
+        ranking_rules.insert(0, Box::new(boost));
+    }
+    Ok(())
+}
+
 #[tracing::instrument(level = "trace", skip_all, target = "search::universe")]
 pub fn filtered_universe(
     index: &Index,
@@ -565,7 +636,7 @@ pub fn filtered_universe(
 #[allow(clippy::too_many_arguments)]
 pub fn execute_vector_search(
     ctx: &mut SearchContext<'_>,
-    vector: &[f32],
+    vector: QueryVector<'_>,
     scoring_strategy: ScoringStrategy,
     universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
@@ -577,12 +648,14 @@ pub fn execute_vector_search(
     embedder: &Embedder,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
+    boosts: &[BoostClause],
+    boost: Option<&SearchBoost>,
 ) -> Result<PartialSearchResult> {
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
     // FIXME: input universe = universe & documents_with_vectors
     // for now if we're computing embeddings for ALL documents, we can assume that this is just universe
-    let ranking_rules = get_ranking_rules_for_vector(
+    let mut ranking_rules = get_ranking_rules_for_vector(
         ctx,
         sort_criteria,
         geo_strategy,
@@ -590,13 +663,15 @@ pub fn execute_vector_search(
         vector,
         embedder_name,
         embedder,
+        boost_ranking_rule(ctx, boost, &universe)?,
     )?;
+    insert_boost_ranking_rule(ctx, &mut ranking_rules, boosts, &universe)?;
 
     let mut placeholder_search_logger = logger::DefaultSearchLogger;
     let placeholder_search_logger: &mut dyn SearchLogger<PlaceholderQuery> =
         &mut placeholder_search_logger;
 
-    let BucketSortOutput { docids, scores, all_candidates, degraded } = bucket_sort(
+    let BucketSortOutput { docids, scores, all_candidates, cursor: _, degraded } = bucket_sort(
         ctx,
         ranking_rules,
         &PlaceholderQuery,
@@ -608,12 +683,14 @@ pub fn execute_vector_search(
         placeholder_search_logger,
         time_budget,
         ranking_score_threshold,
+        None,
     )?;
 
     Ok(PartialSearchResult {
         candidates: all_candidates,
         document_scores: scores,
         documents_ids: docids,
+        cursor: None,
         located_query_terms: None,
         degraded,
         used_negative_operator: false,
@@ -639,6 +716,9 @@ pub fn execute_search(
     query_graph_logger: &mut dyn SearchLogger<QueryGraph>,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
+    boosts: &[BoostClause],
+    boost: Option<&SearchBoost>,
+    search_after: Option<&SearchCursor>,
 ) -> Result<PartialSearchResult> {
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
@@ -707,15 +787,17 @@ pub fn execute_search(
         let (graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
         located_query_terms = Some(new_located_query_terms);
 
-        let ranking_rules = get_ranking_rules_for_query_graph_search(
+        let mut ranking_rules = get_ranking_rules_for_query_graph_search(
             ctx,
             sort_criteria,
             geo_strategy,
             terms_matching_strategy,
+            boost_ranking_rule(ctx, boost, &universe)?,
         )?;
 
         universe &=
             resolve_universe(ctx, &universe, &graph, terms_matching_strategy, query_graph_logger)?;
+        insert_boost_ranking_rule(ctx, &mut ranking_rules, boosts, &universe)?;
 
         bucket_sort(
             ctx,
@@ -729,10 +811,16 @@ pub fn execute_search(
             query_graph_logger,
             time_budget,
             ranking_score_threshold,
+            search_after,
         )?
     } else {
-        let ranking_rules =
-            get_ranking_rules_for_placeholder_search(ctx, sort_criteria, geo_strategy)?;
+        let mut ranking_rules = get_ranking_rules_for_placeholder_search(
+            ctx,
+            sort_criteria,
+            geo_strategy,
+            boost_ranking_rule(ctx, boost, &universe)?,
+        )?;
+        insert_boost_ranking_rule(ctx, &mut ranking_rules, boosts, &universe)?;
         bucket_sort(
             ctx,
             ranking_rules,
@@ -745,10 +833,12 @@ pub fn execute_search(
             placeholder_search_logger,
             time_budget,
             ranking_score_threshold,
+            search_after,
         )?
     };
 
-    let BucketSortOutput { docids, scores, mut all_candidates, degraded } = bucket_sort_output;
+    let BucketSortOutput { docids, scores, mut all_candidates, cursor, degraded } =
+        bucket_sort_output;
     let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
 
     // The candidates is the universe unless the exhaustive number of hits
@@ -770,6 +860,7 @@ pub fn execute_search(
         candidates: all_candidates,
         document_scores: scores,
         documents_ids: docids,
+        cursor,
         located_query_terms,
         degraded,
         used_negative_operator,
@@ -836,6 +927,8 @@ pub struct PartialSearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// The position of the last returned document, to resume the search after it.
+    pub cursor: Option<SearchCursor>,
 
     pub degraded: bool,
     pub used_negative_operator: bool,
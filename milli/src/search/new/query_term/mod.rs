@@ -403,6 +403,75 @@ impl QueryTermSubset {
         let t = ctx.term_interner.get(self.original);
         ctx.word_interner.get(t.original).to_owned()
     }
+    pub fn is_prefix(&self, ctx: &SearchContext<'_>) -> bool {
+        ctx.term_interner.get(self.original).is_prefix
+    }
+    pub fn is_ngram(&self, ctx: &SearchContext<'_>) -> bool {
+        ctx.term_interner.get(self.original).ngram_words.is_some()
+    }
+    /// Return the derivations of the original term that are part of this subset,
+    /// grouped by the way they were derived.
+    pub fn derivations(&self, ctx: &mut SearchContext<'_>) -> Result<QueryTermDerivations> {
+        if !self.one_typo_subset.is_empty() || !self.two_typo_subset.is_empty() {
+            self.original.compute_fully_if_needed(ctx)?;
+        }
+        let use_prefix_db = self.use_prefix_db(ctx).map(|w| w.interned());
+
+        let original = ctx.term_interner.get(self.original);
+        let ZeroTypoTerm { phrase: _, exact, prefix_of, synonyms, use_prefix_db: _ } =
+            &original.zero_typo;
+        let mut derivations = QueryTermDerivations {
+            exact: exact.filter(|&w| self.zero_typo_subset.contains_word(w)),
+            prefix_of: prefix_of
+                .iter()
+                .copied()
+                .filter(|&w| self.zero_typo_subset.contains_word(w))
+                .collect(),
+            synonyms: synonyms
+                .iter()
+                .copied()
+                .filter(|&p| self.zero_typo_subset.contains_phrase(p))
+                .collect(),
+            use_prefix_db,
+            ..Default::default()
+        };
+        if let Lazy::Init(OneTypoTerm { split_words, one_typo }) = &original.one_typo {
+            derivations.split_words =
+                split_words.filter(|&p| self.one_typo_subset.contains_phrase(p));
+            derivations.one_typo = one_typo
+                .iter()
+                .copied()
+                .filter(|&w| self.one_typo_subset.contains_word(w))
+                .collect();
+        }
+        if let Lazy::Init(TwoTypoTerm { two_typos }) = &original.two_typo {
+            derivations.two_typos = two_typos
+                .iter()
+                .copied()
+                .filter(|&w| self.two_typo_subset.contains_word(w))
+                .collect();
+        }
+        Ok(derivations)
+    }
+}
+
+/// The words and phrases derived from a query term, grouped by the way they were derived.
+#[derive(Default)]
+pub struct QueryTermDerivations {
+    /// A single word equivalent to the original term, with zero typos
+    pub exact: Option<Interned<String>>,
+    /// All the words that contain the original word as prefix
+    pub prefix_of: Vec<Interned<String>>,
+    /// All the synonyms of the original word or phrase
+    pub synonyms: Vec<Interned<Phrase>>,
+    /// A prefix in the prefix databases matching the original word
+    pub use_prefix_db: Option<Interned<String>>,
+    /// The original word split into multiple consecutive words
+    pub split_words: Option<Interned<Phrase>>,
+    /// Words that are 1 typo away from the original word
+    pub one_typo: Vec<Interned<String>>,
+    /// Words that are 2 typos away from the original word
+    pub two_typos: Vec<Interned<String>>,
 }
 
 impl ZeroTypoTerm {
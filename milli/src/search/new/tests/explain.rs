@@ -0,0 +1,108 @@
+/*!
+This module tests the search explanation:
+
+1. the query terms are listed with their derivations
+2. the query graph links the terms from the start node to the end node
+3. each ranking rule lists its buckets, along with the first hits returned from them
+4. placeholder searches only explain the ranking rules
+*/
+
+use big_s::S;
+use maplit::{btreemap, hashset};
+
+use crate::index::tests::TempIndex;
+use crate::search::new::ExplainedQueryNodeKind;
+use crate::{Criterion, Search, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_sortable_fields(hashset! { S("id") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Sort, Criterion::Typo]);
+            s.set_synonyms(btreemap! { S("fox") => vec![S("vixen")] });
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick brown fox" },
+            { "id": 1, "text": "the quick brown foxes" },
+            { "id": 2, "text": "the quack brown vixen" },
+            { "id": 3, "text": "the quick dog" },
+            { "id": 4, "text": "nothing to see here" },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_explain_query() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("quikc fox");
+    let (result, explanation) = s.explain().unwrap();
+    assert_eq!(result.documents_ids, s.execute().unwrap().documents_ids);
+
+    let quikc = explanation.query_terms.iter().find(|term| term.original == "quikc").unwrap();
+    assert_eq!(quikc.positions, (0, 0));
+    assert!(!quikc.is_prefix);
+    assert_eq!(quikc.max_typos, 1);
+    assert_eq!(quikc.exact, None);
+    assert!(quikc.one_typo.contains(&S("quick")), "{quikc:?}");
+
+    let fox = explanation.query_terms.iter().find(|term| term.original == "fox").unwrap();
+    assert_eq!(fox.positions, (1, 1));
+    assert!(fox.is_prefix);
+    assert_eq!(fox.exact, Some(S("fox")));
+    // the prefix derivations are either listed or resolved with the prefix database
+    assert!(fox.prefix_of.contains(&S("foxes")) || fox.prefix_db == Some(S("fox")), "{fox:?}");
+    assert_eq!(fox.synonyms, vec![S("vixen")]);
+
+    // the ngram made of both words is part of the query graph
+    assert!(explanation
+        .query_terms
+        .iter()
+        .any(|term| term.original == "quikcfox" && term.is_ngram));
+    let start = explanation
+        .query_graph
+        .iter()
+        .find(|node| node.kind == ExplainedQueryNodeKind::Start)
+        .unwrap();
+    assert!(start.successors.contains(&quikc.node));
+    assert!(explanation.query_graph.iter().any(|node| node.kind == ExplainedQueryNodeKind::End));
+
+    let names: Vec<_> = explanation.ranking_rules.iter().map(|rr| rr.name.as_str()).collect();
+    assert_eq!(names, ["words", "typo"]);
+    let words = &explanation.ranking_rules[0];
+    // the documents matching both words come first
+    assert_eq!(words.buckets[0].universe, 3);
+    assert_eq!(words.buckets[0].size, 2);
+    assert_eq!(words.buckets[0].first_hits, vec![S("0"), S("1")]);
+    let typo = &explanation.ranking_rules[1];
+    assert_eq!(typo.buckets.iter().map(|bucket| bucket.size).sum::<u64>(), 2);
+}
+
+#[test]
+fn test_explain_placeholder() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.sort_criteria(vec![crate::AscDesc::Desc(crate::Member::Field(S("id")))]);
+    s.limit(2);
+    let (result, explanation) = s.explain().unwrap();
+    assert_eq!(result.documents_ids, vec![4, 3]);
+
+    assert!(explanation.query_terms.is_empty());
+    assert!(explanation.query_graph.is_empty());
+    let sort = explanation.ranking_rules.iter().find(|rr| rr.name == "id:desc").unwrap();
+    assert_eq!(sort.buckets[0].universe, 5);
+    assert_eq!(sort.buckets[0].first_hits, vec![S("4")]);
+}
@@ -3,6 +3,7 @@ pub mod attribute_position;
 pub mod cutoff;
 pub mod distinct;
 pub mod exactness;
+pub mod explain;
 pub mod geo_sort;
 pub mod integration;
 #[cfg(feature = "all-tokenizations")]
@@ -10,6 +11,7 @@ pub mod language;
 pub mod ngram_split_words;
 pub mod proximity;
 pub mod proximity_typo;
+pub mod search_after;
 pub mod sort;
 pub mod stop_words;
 pub mod typo;
@@ -0,0 +1,197 @@
+/*!
+This module tests the `search_after` cursor:
+
+1. paging through the results with the cursor returns the same documents, in the same order,
+   as a single search returning all of them
+2. the cursor works with the `distinct` attribute, and with placeholder searches without any
+   ranking rule, without changing the total number of hits from one page to the next
+3. the cursor is encoded as an opaque string that can be parsed back, the empty string being the
+   cursor positioned before the first document
+*/
+
+use big_s::S;
+use maplit::hashset;
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::{AscDesc, Criterion, Member, Search, SearchCursor, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_filterable_fields(hashset! { S("letter") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Sort, Criterion::Typo]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick brown fox", "letter": "A", "rank": 3 },
+            { "id": 1, "text": "the quick brown dog", "letter": "A", "rank": 1 },
+            { "id": 2, "text": "the quikc fox", "letter": "B", "rank": 3 },
+            { "id": 3, "text": "the brown fox jumps", "letter": "B", "rank": 2 },
+            { "id": 4, "text": "a quick fox", "letter": "C", "rank": 0 },
+            { "id": 5, "text": "the lazy dog", "letter": "C", "rank": 3 },
+            { "id": 6, "text": "the fox", "letter": "D", "rank": 1 },
+            { "id": 7, "text": "quick quick quick", "letter": "D", "rank": 2 },
+            { "id": 8, "text": "the brown quikc fox", "letter": "E", "rank": 1 },
+            { "id": 9, "text": "nothing to see here", "letter": "E", "rank": 0 },
+            { "id": 10, "text": "the quick fox", "letter": "F", "rank": 3 },
+            { "id": 11, "text": "fox", "letter": "F", "rank": 2 },
+        ]))
+        .unwrap();
+    index
+}
+
+/// Returns all the documents of the search, fetching them `page_size` by `page_size`,
+/// along with the last cursor and the number of searches.
+fn search_with_cursor<'t>(
+    new_search: impl Fn() -> Search<'t>,
+    page_size: usize,
+) -> (Vec<u32>, Option<SearchCursor>, usize) {
+    let mut documents_ids = Vec::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let mut search = new_search();
+        search.limit(page_size);
+        if let Some(cursor) = cursor.clone() {
+            search.search_after(cursor);
+        }
+        let result = search.execute().unwrap();
+        pages += 1;
+        if result.documents_ids.is_empty() {
+            return (documents_ids, cursor, pages);
+        }
+        assert!(result.cursor.is_some());
+        documents_ids.extend(result.documents_ids);
+        cursor = result.cursor;
+    }
+}
+
+fn all_documents(mut search: Search<'_>) -> Vec<u32> {
+    search.limit(100);
+    search.execute().unwrap().documents_ids
+}
+
+#[test]
+fn test_search_after_query() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let new_search = || {
+        let mut s = Search::new(&txn, &index);
+        s.terms_matching_strategy(TermsMatchingStrategy::Last);
+        s.query("the quick fox");
+        s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+        s
+    };
+    let all = all_documents(new_search());
+    assert!(all.len() > 5);
+
+    for page_size in 1..5 {
+        let (documents_ids, _, _) = search_with_cursor(new_search, page_size);
+        assert_eq!(documents_ids, all, "page size: {page_size}");
+    }
+}
+
+#[test]
+fn test_search_after_distinct() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let new_search = || {
+        let mut s = Search::new(&txn, &index);
+        s.terms_matching_strategy(TermsMatchingStrategy::Last);
+        s.query("the quick fox");
+        s.distinct(S("letter"));
+        s
+    };
+    let all = all_documents(new_search());
+    assert!(all.len() > 3);
+
+    for page_size in 1..4 {
+        let (documents_ids, _, _) = search_with_cursor(new_search, page_size);
+        assert_eq!(documents_ids, all, "page size: {page_size}");
+    }
+}
+
+#[test]
+fn test_search_after_placeholder() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![]);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let new_search = || Search::new(&txn, &index);
+    let all = all_documents(new_search());
+    assert_eq!(all, (0..12).collect::<Vec<_>>());
+
+    let (documents_ids, cursor, pages) = search_with_cursor(new_search, 5);
+    assert_eq!(documents_ids, all);
+    assert_eq!(pages, 4);
+    insta::assert_snapshot!(cursor.unwrap(), @"0000000b");
+}
+
+#[test]
+fn test_search_after_placeholder_distinct_total() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![]);
+            s.set_distinct_field(S("letter"));
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    // the documents returned by the previous pages still count in the total hits,
+    // which are the same as when paginating with an offset
+    let mut cursor = None;
+    for page in 0.. {
+        let mut search = Search::new(&txn, &index);
+        search.limit(2);
+        if let Some(cursor) = cursor.clone() {
+            search.search_after(cursor);
+        }
+        let result = search.execute().unwrap();
+        if result.documents_ids.is_empty() {
+            break;
+        }
+
+        let mut search = Search::new(&txn, &index);
+        search.offset(page * 2).limit(2);
+        let with_offset = search.execute().unwrap();
+        assert_eq!(result.documents_ids, with_offset.documents_ids, "page: {page}");
+        assert_eq!(result.candidates, with_offset.candidates, "page: {page}");
+        cursor = result.cursor;
+    }
+}
+
+#[test]
+fn test_search_cursor_encoding() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick fox");
+    s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+    s.limit(2);
+    let cursor = s.execute().unwrap().cursor.unwrap();
+
+    let encoded = cursor.to_string();
+    assert_eq!(encoded.len() % 8, 0);
+    assert_eq!(encoded.parse::<SearchCursor>().unwrap(), cursor);
+
+    assert_eq!("".parse::<SearchCursor>().unwrap(), SearchCursor::default());
+    assert_eq!(SearchCursor::default().to_string(), "");
+    assert!("0000000".parse::<SearchCursor>().is_err());
+    assert!("0000000g".parse::<SearchCursor>().is_err());
+}
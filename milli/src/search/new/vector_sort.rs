@@ -1,37 +1,55 @@
 use std::iter::FromIterator;
 
-use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use crate::score_details::{self, ScoreDetails};
-use crate::vector::{DistributionShift, Embedder};
+use crate::vector::sparse::{self, SparseVector, SparseVectorStore};
+use crate::vector::{ArroyWrapper, DistributionShift, Embedder};
 use crate::{DocumentId, Result, SearchContext, SearchLogger};
 
+/// The vector of a semantic search, compared to the vectors of the documents.
+#[derive(Debug, Clone, Copy)]
+pub enum QueryVector<'a> {
+    Dense(&'a [f32]),
+    Sparse(&'a SparseVector),
+}
+
+/// Where the nearest documents of the target are looked for.
+enum Target {
+    Dense { arroy: ArroyWrapper, vector: Vec<f32> },
+    Sparse { store: SparseVectorStore, vector: SparseVector },
+}
+
 pub struct VectorSort<Q: RankingRuleQueryTrait> {
     query: Option<Q>,
-    target: Vec<f32>,
+    target: Target,
     vector_candidates: RoaringBitmap,
-    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32)>,
+    /// The nearest documents, along with their similarity and the position of the matching embedding.
+    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32, Option<usize>)>,
     limit: usize,
     distribution_shift: Option<DistributionShift>,
-    embedder_index: u8,
 }
 
 impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
     pub fn new(
         ctx: &SearchContext<'_>,
-        target: Vec<f32>,
+        target: QueryVector<'_>,
         vector_candidates: RoaringBitmap,
         limit: usize,
         embedder_name: &str,
         embedder: &Embedder,
     ) -> Result<Self> {
-        let embedder_index = ctx
-            .index
-            .embedder_category_id
-            .get(ctx.txn, embedder_name)?
-            .ok_or_else(|| crate::UserError::InvalidEmbedder(embedder_name.to_owned()))?;
+        let target = match target {
+            QueryVector::Dense(vector) => Target::Dense {
+                arroy: ctx.index.arroy_wrapper(ctx.txn, embedder_name)?,
+                vector: vector.to_vec(),
+            },
+            QueryVector::Sparse(vector) => Target::Sparse {
+                store: ctx.index.sparse_vector_store(ctx.txn, embedder_name)?,
+                vector: vector.clone(),
+            },
+        };
 
         Ok(Self {
             query: None,
@@ -40,7 +58,6 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             cached_sorted_docids: Default::default(),
             limit,
             distribution_shift: embedder.distribution(),
-            embedder_index,
         })
     }
 
@@ -49,19 +66,23 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         ctx: &mut SearchContext<'_>,
         vector_candidates: &RoaringBitmap,
     ) -> Result<()> {
-        let readers: std::result::Result<Vec<_>, _> =
-            ctx.index.arroy_readers(ctx.txn, self.embedder_index).collect();
-        let readers = readers?;
-
-        let target = &self.target;
-        let mut results = Vec::new();
-
-        for reader in readers.iter() {
-            let nns_by_vector =
-                reader.nns_by_vector(ctx.txn, target, self.limit, None, Some(vector_candidates))?;
-            results.extend(nns_by_vector.into_iter());
-        }
-        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+        let results: Vec<_> = match &self.target {
+            Target::Dense { arroy, vector } => {
+                let distance = arroy.options().distance;
+                arroy
+                    .nns_by_vector(ctx.txn, vector, self.limit, Some(vector_candidates))?
+                    .into_iter()
+                    .map(|(docid, distance_to_target, embedding_index)| {
+                        (docid, distance.similarity(distance_to_target), Some(embedding_index))
+                    })
+                    .collect()
+            }
+            Target::Sparse { store, vector } => store
+                .nearest(ctx.txn, vector, self.limit, vector_candidates)?
+                .into_iter()
+                .map(|(docid, dot_product)| (docid, sparse::similarity(dot_product), None))
+                .collect(),
+        };
         self.cached_sorted_docids = results.into_iter();
 
         Ok(())
@@ -104,13 +125,16 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    embedding_index: None,
+                }),
             }));
         }
 
-        for (docid, distance) in self.cached_sorted_docids.by_ref() {
+        // the first occurrence of a document is its closest embedding
+        for (docid, score, embedding_index) in self.cached_sorted_docids.by_ref() {
             if vector_candidates.contains(docid) {
-                let score = 1.0 - distance;
                 let score = self
                     .distribution_shift
                     .map(|distribution| distribution.shift(score))
@@ -118,7 +142,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
                 return Ok(Some(RankingRuleOutput {
                     query,
                     candidates: RoaringBitmap::from_iter([docid]),
-                    score: ScoreDetails::Vector(score_details::Vector { similarity: Some(score) }),
+                    score: ScoreDetails::Vector(score_details::Vector {
+                        similarity: Some(score),
+                        embedding_index,
+                    }),
                 }));
             }
         }
@@ -134,7 +161,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    embedding_index: None,
+                }),
             }));
         }
 
@@ -1,14 +1,30 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use ordered_float::OrderedFloat;
+use charabia::{Tokenizer, TokenizerBuilder};
 use roaring::RoaringBitmap;
+use serde_json::Value;
 
-use crate::score_details::{self, ScoreDetails};
-use crate::vector::Embedder;
-use crate::{filtered_universe, DocumentId, Filter, Index, Result, SearchResult};
+use super::Search;
+use crate::score_details::{self, ScoreDetails, ScoringStrategy};
+use crate::vector::{ArroyWrapper, Embedder, Embedding};
+use crate::{
+    filtered_universe, is_faceted, CboRoaringBitmapLenCodec, DocumentId, FieldsIdsMap, Filter,
+    Index, InternalError, Result, SearchResult, TermsMatchingStrategy,
+};
+
+/// The weight of the documents the results should be similar to, in the Rocchio formula.
+const POSITIVE_WEIGHT: f32 = 0.75;
+/// The weight of the documents the results should be dissimilar to, in the Rocchio formula.
+const NEGATIVE_WEIGHT: f32 = 0.15;
+
+/// The maximum number of words of the source documents searched by [`SimilarKeywords`].
+const MAX_SIGNIFICANT_TERMS: usize = 10;
 
 pub struct Similar<'a> {
-    id: DocumentId,
+    positive: Vec<DocumentId>,
+    negative: Vec<DocumentId>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
     offset: usize,
@@ -21,8 +37,9 @@ pub struct Similar<'a> {
 }
 
 impl<'a> Similar<'a> {
+    /// Searches the documents similar to the `positive` documents.
     pub fn new(
-        id: DocumentId,
+        positive: Vec<DocumentId>,
         offset: usize,
         limit: usize,
         index: &'a Index,
@@ -31,7 +48,8 @@ impl<'a> Similar<'a> {
         embedder: Arc<Embedder>,
     ) -> Self {
         Self {
-            id,
+            positive,
+            negative: Vec::new(),
             filter: None,
             offset,
             limit,
@@ -48,6 +66,12 @@ impl<'a> Similar<'a> {
         self
     }
 
+    /// Moves the results away from the given documents.
+    pub fn negative(&mut self, negative: Vec<DocumentId>) -> &mut Self {
+        self.negative = negative;
+        self
+    }
+
     pub fn ranking_score_threshold(&mut self, ranking_score_threshold: f64) -> &mut Self {
         self.ranking_score_threshold = Some(ranking_score_threshold);
         self
@@ -56,67 +80,51 @@ impl<'a> Similar<'a> {
     pub fn execute(&self) -> Result<SearchResult> {
         let mut universe = filtered_universe(self.index, self.rtxn, &self.filter)?;
 
-        // we never want to receive the docid
-        universe.remove(self.id);
+        // we never want to receive the source documents
+        let sources: RoaringBitmap =
+            self.positive.iter().chain(self.negative.iter()).copied().collect();
+        universe -= &sources;
 
         let universe = universe;
 
-        let embedder_index =
-            self.index
-                .embedder_category_id
-                .get(self.rtxn, &self.embedder_name)?
-                .ok_or_else(|| crate::UserError::InvalidEmbedder(self.embedder_name.to_owned()))?;
-
-        let readers: std::result::Result<Vec<_>, _> =
-            self.index.arroy_readers(self.rtxn, embedder_index).collect();
-
-        let readers = readers?;
-
-        let mut results = Vec::new();
-
-        for reader in readers.iter() {
-            let nns_by_item = reader.nns_by_item(
-                self.rtxn,
-                self.id,
-                self.limit + self.offset + 1,
-                None,
-                Some(&universe),
-            )?;
-            if let Some(mut nns_by_item) = nns_by_item {
-                results.append(&mut nns_by_item);
-            } else {
-                break;
-            }
-        }
-
-        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+        let arroy = self.index.arroy_wrapper(self.rtxn, &self.embedder_name)?;
+        let limit = self.limit + self.offset + sources.len() as usize;
+        let results = match (self.positive.as_slice(), self.negative.as_slice()) {
+            // a single document is compared with the embeddings of the other documents as is
+            ([id], []) => arroy.nns_by_item(self.rtxn, *id, limit, Some(&universe))?,
+            _ => match self.rocchio_vector(&arroy)? {
+                Some(vector) => arroy.nns_by_vector(self.rtxn, &vector, limit, Some(&universe))?,
+                None => Vec::new(),
+            },
+        };
 
         let mut documents_ids = Vec::with_capacity(self.limit);
         let mut document_scores = Vec::with_capacity(self.limit);
         // list of documents we've already seen, so that we don't return the same document multiple times.
-        // initialized to the target document, that we never want to return.
-        let mut documents_seen = RoaringBitmap::new();
-        documents_seen.insert(self.id);
+        // initialized to the source documents, that we never want to return.
+        let mut documents_seen = sources;
 
         let mut candidates = universe;
 
-        for (docid, distance) in results
+        for (docid, distance, embedding_index) in results
             .into_iter()
             // skip documents we've already seen & mark that we saw the current document
-            .filter(|(docid, _)| documents_seen.insert(*docid))
+            .filter(|(docid, ..)| documents_seen.insert(*docid))
             .skip(self.offset)
             // take **after** filter and skip so that we get exactly limit elements if available
             .take(self.limit)
         {
-            let score = 1.0 - distance;
+            let score = arroy.options().distance.similarity(distance);
             let score = self
                 .embedder
                 .distribution()
                 .map(|distribution| distribution.shift(score))
                 .unwrap_or(score);
 
-            let score_details =
-                vec![ScoreDetails::Vector(score_details::Vector { similarity: Some(score) })];
+            let score_details = vec![ScoreDetails::Vector(score_details::Vector {
+                similarity: Some(score),
+                embedding_index: Some(embedding_index),
+            })];
 
             let score = ScoreDetails::global_score(score_details.iter());
 
@@ -139,8 +147,272 @@ impl<'a> Similar<'a> {
             candidates,
             documents_ids,
             document_scores,
+            collapsed_groups: Vec::new(),
+            applied_rules: Vec::new(),
+            pinned_documents: RoaringBitmap::new(),
+            cursor: None,
             degraded: false,
             used_negative_operator: false,
         })
     }
+
+    /// Combines the embeddings of the positive and negative documents with the Rocchio formula.
+    ///
+    /// There is no query vector, so the mean of the positive documents is kept as is, and the
+    /// mean of the negative documents is weighted relatively to it: the vector keeps the magnitude
+    /// of the positive embeddings, which matters for the non-angular distances.
+    ///
+    /// Returns `None` when none of the positive documents have embeddings.
+    fn rocchio_vector(&self, arroy: &ArroyWrapper) -> Result<Option<Embedding>> {
+        let Some(positive) = self.mean_vector(arroy, &self.positive)? else {
+            return Ok(None);
+        };
+        let negative = self.mean_vector(arroy, &self.negative)?;
+
+        let vector = match negative {
+            Some(negative) => {
+                let negative_weight = NEGATIVE_WEIGHT / POSITIVE_WEIGHT;
+                positive
+                    .iter()
+                    .zip(negative)
+                    .map(|(positive, negative)| positive - negative_weight * negative)
+                    .collect()
+            }
+            None => positive,
+        };
+        Ok(Some(vector))
+    }
+
+    /// Averages the embeddings of the given documents, giving the same weight to each document.
+    ///
+    /// The documents without embeddings are ignored. The embeddings of a binary quantized embedder
+    /// only keep the sign of their dimensions, so the mean is computed on these approximations.
+    fn mean_vector(
+        &self,
+        arroy: &ArroyWrapper,
+        docids: &[DocumentId],
+    ) -> Result<Option<Embedding>> {
+        let mut sum: Option<Embedding> = None;
+        let mut count = 0;
+        for &docid in docids {
+            let embeddings = arroy.item_vectors(self.rtxn, docid)?;
+            let Some(dimensions) = embeddings.first().map(Vec::len) else { continue };
+            let sum = sum.get_or_insert_with(|| vec![0.0; dimensions]);
+            let weight = 1.0 / embeddings.len() as f32;
+            for embedding in &embeddings {
+                for (sum, value) in sum.iter_mut().zip(embedding) {
+                    *sum += weight * value;
+                }
+            }
+            count += 1;
+        }
+
+        Ok(sum.map(|mut sum| {
+            sum.iter_mut().for_each(|value| *value /= count as f32);
+            sum
+        }))
+    }
+}
+
+/// Searches the documents sharing the most distinctive words with the given documents.
+///
+/// This is how the indexes without embedders find similar documents. The words of the positive
+/// documents are weighted by their inverse document frequency, and the most significant ones are
+/// searched with the [`TermsMatchingStrategy::Frequency`] strategy. The words of the negative
+/// documents are never searched.
+pub struct SimilarKeywords<'a> {
+    positive: Vec<DocumentId>,
+    negative: Vec<DocumentId>,
+    filter: Option<Filter<'a>>,
+    offset: usize,
+    limit: usize,
+    rtxn: &'a heed::RoTxn<'a>,
+    index: &'a Index,
+    ranking_score_threshold: Option<f64>,
+}
+
+impl<'a> SimilarKeywords<'a> {
+    /// Searches the documents similar to the `positive` documents.
+    pub fn new(
+        positive: Vec<DocumentId>,
+        offset: usize,
+        limit: usize,
+        index: &'a Index,
+        rtxn: &'a heed::RoTxn<'a>,
+    ) -> Self {
+        Self {
+            positive,
+            negative: Vec::new(),
+            filter: None,
+            offset,
+            limit,
+            rtxn,
+            index,
+            ranking_score_threshold: None,
+        }
+    }
+
+    /// Never searches the words of the given documents.
+    pub fn negative(&mut self, negative: Vec<DocumentId>) -> &mut Self {
+        self.negative = negative;
+        self
+    }
+
+    pub fn filter(&mut self, filter: Filter<'a>) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn ranking_score_threshold(&mut self, ranking_score_threshold: f64) -> &mut Self {
+        self.ranking_score_threshold = Some(ranking_score_threshold);
+        self
+    }
+
+    pub fn execute(&self) -> Result<SearchResult> {
+        let terms = self.significant_terms()?;
+        // an empty query would be a placeholder search
+        if terms.is_empty() {
+            return Ok(SearchResult::default());
+        }
+
+        // we never want to receive the source documents
+        let mut universe = self.index.documents_ids(self.rtxn)?;
+        for docid in self.positive.iter().chain(self.negative.iter()) {
+            universe.remove(*docid);
+        }
+
+        let mut search = Search::new(self.rtxn, self.index);
+        search
+            .query(terms.join(" "))
+            .offset(self.offset)
+            .limit(self.limit)
+            .terms_matching_strategy(TermsMatchingStrategy::Frequency)
+            .scoring_strategy(ScoringStrategy::Detailed);
+        if let Some(filter) = &self.filter {
+            search.filter(filter.clone());
+        }
+        if let Some(ranking_score_threshold) = self.ranking_score_threshold {
+            search.ranking_score_threshold(ranking_score_threshold);
+        }
+        search.restricted_universe = Some(universe);
+        // the query is made of the extracted terms, the query rules don't apply to it
+        search.pin_documents = false;
+        search.apply_query_rules = false;
+
+        search.execute()
+    }
+
+    /// Returns the words of the positive documents with the highest TF-IDF, most significant first.
+    fn significant_terms(&self) -> Result<Vec<String>> {
+        // the documents must be tokenized like they were indexed
+        let mut builder = TokenizerBuilder::new();
+        let stop_words = self.index.stop_words(self.rtxn)?;
+        if let Some(ref stop_words) = stop_words {
+            builder.stop_words(stop_words);
+        }
+        let separators = self.index.allowed_separators(self.rtxn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
+        let dictionary = self.index.dictionary(self.rtxn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+        let script_lang_map = self.index.script_language(self.rtxn)?;
+        if !script_lang_map.is_empty() {
+            builder.allow_list(&script_lang_map);
+        }
+        let tokenizer = builder.build();
+
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let searchable_fields = self.index.searchable_fields(self.rtxn)?;
+
+        let mut words = Vec::new();
+        for result in self.index.iter_documents(self.rtxn, self.negative.iter().copied())? {
+            let (_docid, document) = result?;
+            document_words(&tokenizer, &document, &fields_ids_map, &searchable_fields, &mut words)?;
+        }
+        let negative_words: HashSet<String> = words.drain(..).collect();
+
+        for result in self.index.iter_documents(self.rtxn, self.positive.iter().copied())? {
+            let (_docid, document) = result?;
+            document_words(&tokenizer, &document, &fields_ids_map, &searchable_fields, &mut words)?;
+        }
+        let mut term_frequencies: HashMap<String, usize> = HashMap::new();
+        for word in words.into_iter().filter(|word| !negative_words.contains(word)) {
+            *term_frequencies.entry(word).or_default() += 1;
+        }
+
+        let number_of_documents = self.index.number_of_documents(self.rtxn)? as f64;
+        let word_docids_len = self.index.word_docids.remap_data_type::<CboRoaringBitmapLenCodec>();
+        let mut terms = Vec::new();
+        for (word, term_frequency) in term_frequencies {
+            let document_frequency = word_docids_len.get(self.rtxn, &word)?.unwrap_or_default();
+            if document_frequency == 0 {
+                continue;
+            }
+            // the words contained in all the documents are not distinctive at all
+            let inverse_document_frequency = (number_of_documents / document_frequency as f64).ln();
+            if inverse_document_frequency > 0.0 {
+                terms.push((term_frequency as f64 * inverse_document_frequency, word));
+            }
+        }
+
+        terms.sort_unstable_by(|(left_score, left), (right_score, right)| {
+            right_score.total_cmp(left_score).then_with(|| left.cmp(right))
+        });
+        Ok(terms.into_iter().take(MAX_SIGNIFICANT_TERMS).map(|(_, word)| word).collect())
+    }
+}
+
+/// Pushes the normalized words of the searchable fields of a document.
+fn document_words(
+    tokenizer: &Tokenizer<'_>,
+    document: &obkv::KvReaderU16<'_>,
+    fields_ids_map: &FieldsIdsMap,
+    searchable_fields: &[Cow<'_, str>],
+    words: &mut Vec<String>,
+) -> Result<()> {
+    let mut texts = Vec::new();
+    for (field_id, value) in document.iter() {
+        let Some(name) = fields_ids_map.name(field_id) else { continue };
+        let value: Value = serde_json::from_slice(value).map_err(InternalError::SerdeJson)?;
+        searchable_texts(name, &value, searchable_fields, &mut texts);
+    }
+
+    for text in texts {
+        let tokens = tokenizer.tokenize(&text).filter(|token| token.is_word());
+        words.extend(tokens.map(|token| token.lemma().to_string()));
+    }
+    Ok(())
+}
+
+/// Pushes the strings and numbers of a field that are at a searchable path.
+fn searchable_texts(
+    path: &str,
+    value: &Value,
+    searchable_fields: &[Cow<'_, str>],
+    texts: &mut Vec<String>,
+) {
+    match value {
+        Value::String(string) if is_faceted(path, searchable_fields) => texts.push(string.clone()),
+        Value::Number(number) if is_faceted(path, searchable_fields) => {
+            texts.push(number.to_string())
+        }
+        Value::Array(values) => {
+            for value in values {
+                searchable_texts(path, value, searchable_fields, texts);
+            }
+        }
+        Value::Object(object) => {
+            for (key, value) in object {
+                searchable_texts(&format!("{path}.{key}"), value, searchable_fields, texts);
+            }
+        }
+        _ => (),
+    }
 }
@@ -48,6 +48,9 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             field_id_docid_facet_strings,
             vector_arroy,
             embedder_category_id: _,
+            // the embeddings of the prompts are still valid once the documents are cleared
+            embedding_cache: _,
+            sparse_vector_postings,
             documents,
         } = self.index;
 
@@ -95,6 +98,7 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         field_id_docid_facet_strings.clear(self.wtxn)?;
         // vector
         vector_arroy.clear(self.wtxn)?;
+        sparse_vector_postings.clear(self.wtxn)?;
 
         documents.clear(self.wtxn)?;
 
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
 use std::fs::File;
 use std::io::{self, BufReader, BufWriter};
@@ -12,17 +13,30 @@ use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 use serde_json::Value;
 
-use super::helpers::{create_writer, writer_into_reader, GrenadParameters};
+use super::helpers::{
+    create_sorter, create_writer, keep_first, sorter_into_reader, writer_into_reader,
+    GrenadParameters, MergeFn,
+};
 use crate::error::FaultSource;
 use crate::index::IndexEmbeddingConfig;
 use crate::prompt::Prompt;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::settings::InnerIndexSettingsDiff;
-use crate::vector::error::{EmbedErrorKind, PossibleEmbeddingMistakes, UnusedVectorsDistribution};
-use crate::vector::parsed_vectors::{ParsedVectorsDiff, VectorState, RESERVED_VECTORS_FIELD_NAME};
-use crate::vector::settings::{EmbedderAction, ReindexAction};
-use crate::vector::{Embedder, Embeddings};
-use crate::{try_split_array_at, DocumentId, FieldId, FieldsIdsMap, Result, ThreadPoolNoAbort};
+use crate::vector::cache::{cache_key, cache_prefix, CacheKey, CachePrefix};
+use crate::vector::chunking::ChunkingOptions;
+use crate::vector::error::{
+    EmbedError, EmbedErrorKind, EmbeddingFailure, PossibleEmbeddingMistakes,
+    UnusedVectorsDistribution,
+};
+use crate::vector::parsed_vectors::{
+    ParsedVectorsDiff, VectorState, Vectors, RESERVED_VECTORS_FIELD_NAME,
+};
+use crate::vector::settings::ReindexAction;
+use crate::vector::sparse::SparseVector;
+use crate::vector::{Embedder, Embeddings, OnEmbeddingError};
+use crate::{
+    try_split_array_at, DocumentId, FieldId, FieldsIdsMap, Index, Result, ThreadPoolNoAbort,
+};
 
 /// The length of the elements that are always in the buffer when inserting new values.
 const TRUNCATE_SIZE: usize = size_of::<DocumentId>();
@@ -32,16 +46,29 @@ pub struct ExtractedVectorPoints {
     pub manual_vectors: grenad::Reader<BufReader<File>>,
     // docid -> ()
     pub remove_vectors: grenad::Reader<BufReader<File>>,
-    // docid -> prompt
+    // docid, external docid -> prompt
     pub prompts: grenad::Reader<BufReader<File>>,
 
     // embedder
     pub embedder_name: String,
     pub embedder: Arc<Embedder>,
+    // prefix of the keys of the embedding cache for this embedder
+    pub cache_prefix: Option<CachePrefix>,
+    // how the prompts are split before being embedded
+    pub chunking: Option<ChunkingOptions>,
+    pub on_error: OnEmbeddingError,
+    // documents whose prompt could not be rendered
+    pub embedding_failures: Vec<EmbeddingFailure>,
     pub add_to_user_provided: RoaringBitmap,
     pub remove_from_user_provided: RoaringBitmap,
 }
 
+pub struct ExtractedSparseVectors {
+    // token length, token, docid -> weight, or nothing when the token is removed from the document
+    pub postings: grenad::Reader<BufReader<File>>,
+    pub embedder_name: String,
+}
+
 enum VectorStateDelta {
     NoChange,
     // Remove all vectors, generated or manual, from this document
@@ -71,8 +98,12 @@ struct EmbedderVectorExtractor {
     embedder_name: String,
     embedder: Arc<Embedder>,
     prompt: Arc<Prompt>,
+    cache_prefix: Option<CachePrefix>,
+    chunking: Option<ChunkingOptions>,
+    on_error: OnEmbeddingError,
+    embedding_failures: Vec<EmbeddingFailure>,
 
-    // (docid) -> (prompt)
+    // (docid, external docid) -> (prompt)
     prompts_writer: Writer<BufWriter<File>>,
     // (docid) -> ()
     remove_vectors_writer: Writer<BufWriter<File>>,
@@ -84,6 +115,14 @@ struct EmbedderVectorExtractor {
     action: ExtractionAction,
 }
 
+struct SparseVectorExtractor {
+    embedder_name: String,
+    // token length, token, docid -> weight, or nothing when the token is removed from the document
+    postings_sorter: grenad::Sorter<MergeFn>,
+    // a new embedder has no sparse vectors to remove
+    full_reindex: bool,
+}
+
 struct DocumentOperation {
     // The docids of the documents that contains an auto-generated embedding
     remove_from_user_provided: RoaringBitmap,
@@ -97,14 +136,15 @@ enum ExtractionAction {
 
 /// Extracts the embedding vector contained in each document under the `_vectors` field.
 ///
-/// Returns the generated grenad reader containing the docid as key associated to the Vec<f32>
+/// Returns the generated grenad reader containing the docid as key associated to the Vec<f32>,
+/// and the changes to the postings of the sparse embedders.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_vector_points<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
     embedders_configs: &[IndexEmbeddingConfig],
     settings_diff: &InnerIndexSettingsDiff,
-) -> Result<(Vec<ExtractedVectorPoints>, UnusedVectorsDistribution)> {
+) -> Result<(Vec<ExtractedVectorPoints>, Vec<ExtractedSparseVectors>, UnusedVectorsDistribution)> {
     let mut unused_vectors_distribution = UnusedVectorsDistribution::new();
     let reindex_vectors = settings_diff.reindex_vectors();
 
@@ -116,71 +156,110 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     let new_vectors_fid = new_fields_ids_map.id(RESERVED_VECTORS_FIELD_NAME);
 
     let mut extractors = Vec::new();
+    let mut sparse_extractors = Vec::new();
+    let new_postings_sorter = || {
+        create_sorter(
+            grenad::SortAlgorithm::Stable,
+            keep_first,
+            indexer.chunk_compression_type,
+            indexer.chunk_compression_level,
+            indexer.max_nb_chunks,
+            indexer.max_memory_by_thread(),
+        )
+    };
+
+    let config_of = |embedder_name: &str| {
+        embedders_configs
+            .iter()
+            .find(|config| config.name == embedder_name)
+            .map(|config| &config.config)
+    };
 
     let mut configs = settings_diff.new.embedding_configs.clone().into_inner();
     let old_configs = &settings_diff.old.embedding_configs;
 
     if reindex_vectors {
         for (name, action) in settings_diff.embedding_config_updates.iter() {
-            match action {
-                EmbedderAction::WriteBackToDocuments(_) => continue, // already deleted
-                EmbedderAction::Reindex(action) => {
-                    let Some((embedder_name, (embedder, prompt))) = configs.remove_entry(name)
-                    else {
-                        tracing::error!(embedder = name, "Requested embedder config not found");
-                        continue;
-                    };
-
-                    // (docid, _index) -> KvWriterDelAdd -> Vector
-                    let manual_vectors_writer = create_writer(
-                        indexer.chunk_compression_type,
-                        indexer.chunk_compression_level,
-                        tempfile::tempfile()?,
-                    );
-
-                    // (docid) -> (prompt)
-                    let prompts_writer = create_writer(
-                        indexer.chunk_compression_type,
-                        indexer.chunk_compression_level,
-                        tempfile::tempfile()?,
-                    );
-
-                    // (docid) -> ()
-                    let remove_vectors_writer = create_writer(
-                        indexer.chunk_compression_type,
-                        indexer.chunk_compression_level,
-                        tempfile::tempfile()?,
-                    );
-
-                    let action = match action {
-                        ReindexAction::FullReindex => ExtractionAction::SettingsFullReindex,
-                        ReindexAction::RegeneratePrompts => {
-                            let Some((_, old_prompt)) = old_configs.get(name) else {
-                                tracing::error!(embedder = name, "Old embedder config not found");
-                                continue;
-                            };
-
-                            ExtractionAction::SettingsRegeneratePrompts { old_prompt }
-                        }
-                    };
-
-                    extractors.push(EmbedderVectorExtractor {
+            if let Some(action) = action.reindex() {
+                let Some((embedder_name, (embedder, prompt, _options))) =
+                    configs.remove_entry(name)
+                else {
+                    tracing::error!(embedder = name, "Requested embedder config not found");
+                    continue;
+                };
+
+                if embedder.is_sparse() {
+                    sparse_extractors.push(SparseVectorExtractor {
                         embedder_name,
-                        embedder,
-                        prompt,
-                        prompts_writer,
-                        remove_vectors_writer,
-                        manual_vectors_writer,
-                        add_to_user_provided: RoaringBitmap::new(),
-                        action,
+                        postings_sorter: new_postings_sorter(),
+                        full_reindex: *action == ReindexAction::FullReindex,
                     });
+                    continue;
                 }
+
+                // (docid, _index) -> KvWriterDelAdd -> Vector
+                let manual_vectors_writer = create_writer(
+                    indexer.chunk_compression_type,
+                    indexer.chunk_compression_level,
+                    tempfile::tempfile()?,
+                );
+
+                // (docid) -> (prompt)
+                let prompts_writer = create_writer(
+                    indexer.chunk_compression_type,
+                    indexer.chunk_compression_level,
+                    tempfile::tempfile()?,
+                );
+
+                // (docid) -> ()
+                let remove_vectors_writer = create_writer(
+                    indexer.chunk_compression_type,
+                    indexer.chunk_compression_level,
+                    tempfile::tempfile()?,
+                );
+
+                let action = match action {
+                    ReindexAction::FullReindex => ExtractionAction::SettingsFullReindex,
+                    ReindexAction::RegeneratePrompts => {
+                        let Some((_, old_prompt, _options)) = old_configs.get(name) else {
+                            tracing::error!(embedder = name, "Old embedder config not found");
+                            continue;
+                        };
+
+                        ExtractionAction::SettingsRegeneratePrompts { old_prompt }
+                    }
+                };
+
+                let config = config_of(&embedder_name);
+                extractors.push(EmbedderVectorExtractor {
+                    cache_prefix: config.map(cache_prefix),
+                    chunking: config.and_then(|config| config.chunking.clone()),
+                    on_error: config.map(|config| config.on_error()).unwrap_or_default(),
+                    embedding_failures: Vec::new(),
+                    embedder_name,
+                    embedder,
+                    prompt,
+                    prompts_writer,
+                    remove_vectors_writer,
+                    manual_vectors_writer,
+                    add_to_user_provided: RoaringBitmap::new(),
+                    action,
+                });
             }
         }
     } else {
         // document operation
 
-        for (embedder_name, (embedder, prompt)) in configs.into_iter() {
+        for (embedder_name, (embedder, prompt, _options)) in configs.into_iter() {
+            if embedder.is_sparse() {
+                sparse_extractors.push(SparseVectorExtractor {
+                    embedder_name,
+                    postings_sorter: new_postings_sorter(),
+                    full_reindex: false,
+                });
+                continue;
+            }
+
             // (docid, _index) -> KvWriterDelAdd -> Vector
             let manual_vectors_writer = create_writer(
                 indexer.chunk_compression_type,
@@ -202,7 +281,12 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                 tempfile::tempfile()?,
             );
 
+            let config = config_of(&embedder_name);
             extractors.push(EmbedderVectorExtractor {
+                cache_prefix: config.map(cache_prefix),
+                chunking: config.and_then(|config| config.chunking.clone()),
+                on_error: config.map(|config| config.on_error()).unwrap_or_default(),
+                embedding_failures: Vec::new(),
                 embedder_name,
                 embedder,
                 prompt,
@@ -218,6 +302,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     }
 
     let mut key_buffer = Vec::new();
+    let mut sparse_key_buffer = Vec::new();
     let mut cursor = obkv_documents.into_cursor()?;
     while let Some((key, value)) = cursor.move_on_next()? {
         // this must always be serialized as (docid, external_docid);
@@ -248,6 +333,10 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             embedder_name,
             embedder: _,
             prompt,
+            cache_prefix: _,
+            chunking,
+            on_error,
+            embedding_failures,
             prompts_writer,
             remove_vectors_writer,
             manual_vectors_writer,
@@ -255,7 +344,16 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             action,
         } in extractors.iter_mut()
         {
+            let source_field =
+                chunking.as_ref().and_then(|chunking| chunking.source_field.as_deref());
             let (old, new) = parsed_vectors.remove(embedder_name);
+            if let VectorState::Inline(Vectors::Sparse(_)) = new {
+                return Err(crate::Error::UserError(crate::UserError::InvalidSparseVectors {
+                    document_id: document_id().to_string(),
+                    embedder_name: embedder_name.clone(),
+                    reason: "The embedder is not sparse: expected an array of embeddings, or an object with `embeddings` and `regenerate`.".to_owned(),
+                }));
+            }
             let delta = match action {
                 ExtractionAction::SettingsFullReindex => match old {
                     // A full reindex can be triggered either by:
@@ -277,15 +375,17 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                                         ),
                                     ));
                                 }
-                                VectorStateDelta::NowManual(add_vectors)
+                                Ok(VectorStateDelta::NowManual(add_vectors))
                             }
-                            None => VectorStateDelta::NoChange,
+                            None => Ok(VectorStateDelta::NoChange),
                         }
                     }
                     // this happens only when an existing embedder changed. We cannot regenerate userProvided vectors
-                    VectorState::Manual => VectorStateDelta::NoChange,
+                    VectorState::Manual => Ok(VectorStateDelta::NoChange),
                     // generated vectors must be regenerated
-                    VectorState::Generated => regenerate_prompt(obkv, prompt, new_fields_ids_map)?,
+                    VectorState::Generated => {
+                        regenerate_prompt(obkv, (prompt, source_field), new_fields_ids_map)
+                    }
                 },
                 // prompt regeneration is only triggered for existing embedders
                 ExtractionAction::SettingsRegeneratePrompts { old_prompt } => {
@@ -293,12 +393,13 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                         regenerate_if_prompt_changed(
                             obkv,
                             (old_prompt, prompt),
+                            source_field,
                             (old_fields_ids_map, new_fields_ids_map),
-                        )?
+                        )
                     } else {
                         // we can simply ignore user provided vectors as they are not regenerated and are
                         // already in the DB since this is an existing embedder
-                        VectorStateDelta::NoChange
+                        Ok(VectorStateDelta::NoChange)
                     }
                 }
                 ExtractionAction::DocumentOperation(DocumentOperation {
@@ -306,12 +407,27 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                 }) => extract_vector_document_diff(
                     docid,
                     obkv,
-                    prompt,
+                    (prompt, source_field),
                     (add_to_user_provided, remove_from_user_provided),
                     (old, new),
                     (old_fields_ids_map, new_fields_ids_map),
                     document_id,
-                )?,
+                ),
+            };
+            let delta = match delta {
+                Ok(delta) => delta,
+                // the document is kept without vectors for this embedder
+                Err(crate::Error::UserError(crate::UserError::MissingDocumentField(error)))
+                    if *on_error == OnEmbeddingError::SkipVectors =>
+                {
+                    embedding_failures.push(EmbeddingFailure {
+                        embedder_name: embedder_name.clone(),
+                        document_id: from_utf8(external_id_bytes).unwrap().to_owned(),
+                        reason: error.to_string(),
+                    });
+                    VectorStateDelta::NowRemoved
+                }
+                Err(error) => return Err(error),
             };
             // and we finally push the unique vectors into the writer
             push_vectors_diff(
@@ -319,10 +435,28 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                 prompts_writer,
                 manual_vectors_writer,
                 &mut key_buffer,
+                external_id_bytes,
                 delta,
             )?;
         }
 
+        for SparseVectorExtractor { embedder_name, postings_sorter, full_reindex } in
+            sparse_extractors.iter_mut()
+        {
+            let (old, new) = parsed_vectors.remove(embedder_name);
+            // the previous versions of the documents were validated when they were indexed
+            let old =
+                if *full_reindex { None } else { into_sparse_vector(old).unwrap_or_default() };
+            let new = into_sparse_vector(new).map_err(|reason| {
+                crate::Error::UserError(crate::UserError::InvalidSparseVectors {
+                    document_id: document_id().to_string(),
+                    embedder_name: embedder_name.clone(),
+                    reason: reason.to_owned(),
+                })
+            })?;
+            push_sparse_vectors_diff(postings_sorter, &mut sparse_key_buffer, docid, old, new)?;
+        }
+
         unused_vectors_distribution.append(parsed_vectors);
     }
 
@@ -332,6 +466,10 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         embedder_name,
         embedder,
         prompt: _,
+        cache_prefix,
+        chunking,
+        on_error,
+        embedding_failures,
         prompts_writer,
         remove_vectors_writer,
         action,
@@ -355,18 +493,100 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             prompts: writer_into_reader(prompts_writer)?,
             embedder,
             embedder_name,
+            cache_prefix,
+            chunking,
+            on_error,
+            embedding_failures,
             add_to_user_provided,
             remove_from_user_provided,
         })
     }
 
-    Ok((results, unused_vectors_distribution))
+    let mut sparse_results = Vec::new();
+    for SparseVectorExtractor { embedder_name, postings_sorter, full_reindex: _ } in
+        sparse_extractors
+    {
+        sparse_results.push(ExtractedSparseVectors {
+            postings: sorter_into_reader(postings_sorter, indexer)?,
+            embedder_name,
+        });
+    }
+
+    Ok((results, sparse_results, unused_vectors_distribution))
+}
+
+/// Returns the sparse vector of a document, if it has one.
+fn into_sparse_vector(
+    state: VectorState,
+) -> std::result::Result<Option<SparseVector>, &'static str> {
+    match state {
+        VectorState::Inline(Vectors::Sparse(vector)) => Ok(Some(vector)),
+        VectorState::Inline(Vectors::ImplicitlyUserProvided(vectors)) => {
+            match vectors.into_array_of_vectors() {
+                // `null` removes the sparse vector of the document
+                None => Ok(None),
+                Some(_) => Err("Expected an object mapping tokens to their weight."),
+            }
+        }
+        VectorState::Inline(Vectors::Explicit(_)) => {
+            Err("Expected an object mapping tokens to their weight.")
+        }
+        // sparse vectors are never generated
+        VectorState::Manual | VectorState::Generated => Ok(None),
+    }
+}
+
+/// Pushes the tokens whose weight changed in a document, and the tokens removed from it.
+fn push_sparse_vectors_diff(
+    postings_sorter: &mut grenad::Sorter<MergeFn>,
+    key_buffer: &mut Vec<u8>,
+    docid: DocumentId,
+    old: Option<SparseVector>,
+    new: Option<SparseVector>,
+) -> Result<()> {
+    // the tokens with a weight of zero don't contribute to the dot product
+    let old: BTreeMap<String, f32> = old
+        .map(SparseVector::into_inner)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, weight)| *weight != 0.0)
+        .collect();
+    let new: BTreeMap<String, f32> = new
+        .map(SparseVector::into_inner)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, weight)| *weight != 0.0)
+        .collect();
+
+    let mut push = |token: &str, weight: Option<f32>| {
+        key_buffer.clear();
+        key_buffer.push(token.len() as u8);
+        key_buffer.extend_from_slice(token.as_bytes());
+        key_buffer.extend_from_slice(&docid.to_be_bytes());
+        match weight {
+            Some(weight) => postings_sorter.insert(key_buffer.as_slice(), weight.to_be_bytes()),
+            None => postings_sorter.insert(key_buffer.as_slice(), []),
+        }
+    };
+
+    for token in old.keys() {
+        if !new.contains_key(token) {
+            push(token, None)?;
+        }
+    }
+    for (token, weight) in &new {
+        if old.get(token) != Some(weight) {
+            push(token, Some(*weight))?;
+        }
+    }
+
+    Ok(())
 }
 
 fn extract_vector_document_diff(
     docid: DocumentId,
     obkv: obkv::KvReader<'_, FieldId>,
-    prompt: &Prompt,
+    (prompt, source_field): (&Prompt, Option<&str>),
     (add_to_user_provided, remove_from_user_provided): (&mut RoaringBitmap, &mut RoaringBitmap),
     (old, new): (VectorState, VectorState),
     (old_fields_ids_map, new_fields_ids_map): (&FieldsIdsMap, &FieldsIdsMap),
@@ -410,9 +630,15 @@ fn extract_vector_document_diff(
             if document_is_kept {
                 // Don't give up if the old prompt was failing
                 let old_prompt = Some(&prompt).map(|p| {
-                    p.render(obkv, DelAdd::Deletion, old_fields_ids_map).unwrap_or_default()
+                    render_text(obkv, (p, source_field), DelAdd::Deletion, old_fields_ids_map)
+                        .unwrap_or_default()
                 });
-                let new_prompt = prompt.render(obkv, DelAdd::Addition, new_fields_ids_map)?;
+                let new_prompt = render_text(
+                    obkv,
+                    (prompt, source_field),
+                    DelAdd::Addition,
+                    new_fields_ids_map,
+                )?;
                 if old_prompt.as_ref() != Some(&new_prompt) {
                     let old_prompt = old_prompt.unwrap_or_default();
                     tracing::trace!(
@@ -440,8 +666,9 @@ fn extract_vector_document_diff(
                 .any(|deladd| deladd.get(DelAdd::Addition).is_some());
             if document_is_kept {
                 // becomes autogenerated
-                VectorStateDelta::NowGenerated(prompt.render(
+                VectorStateDelta::NowGenerated(render_text(
                     obkv,
+                    (prompt, source_field),
                     DelAdd::Addition,
                     new_fields_ids_map,
                 )?)
@@ -478,11 +705,14 @@ fn extract_vector_document_diff(
 fn regenerate_if_prompt_changed(
     obkv: obkv::KvReader<'_, FieldId>,
     (old_prompt, new_prompt): (&Prompt, &Prompt),
+    source_field: Option<&str>,
     (old_fields_ids_map, new_fields_ids_map): (&FieldsIdsMap, &FieldsIdsMap),
 ) -> Result<VectorStateDelta> {
     let old_prompt =
-        old_prompt.render(obkv, DelAdd::Deletion, old_fields_ids_map).unwrap_or(Default::default());
-    let new_prompt = new_prompt.render(obkv, DelAdd::Addition, new_fields_ids_map)?;
+        render_text(obkv, (old_prompt, source_field), DelAdd::Deletion, old_fields_ids_map)
+            .unwrap_or(Default::default());
+    let new_prompt =
+        render_text(obkv, (new_prompt, source_field), DelAdd::Addition, new_fields_ids_map)?;
 
     if new_prompt == old_prompt {
         return Ok(VectorStateDelta::NoChange);
@@ -492,14 +722,40 @@ fn regenerate_if_prompt_changed(
 
 fn regenerate_prompt(
     obkv: obkv::KvReader<'_, FieldId>,
-    prompt: &Prompt,
+    (prompt, source_field): (&Prompt, Option<&str>),
     new_fields_ids_map: &FieldsIdsMap,
 ) -> Result<VectorStateDelta> {
-    let prompt = prompt.render(obkv, DelAdd::Addition, new_fields_ids_map)?;
+    let prompt = render_text(obkv, (prompt, source_field), DelAdd::Addition, new_fields_ids_map)?;
 
     Ok(VectorStateDelta::NowGenerated(prompt))
 }
 
+/// Renders the text to embed for a document: the value of the chunked source field if any,
+/// or the document template.
+fn render_text(
+    obkv: obkv::KvReader<'_, FieldId>,
+    (prompt, source_field): (&Prompt, Option<&str>),
+    side: DelAdd,
+    fields_ids_map: &FieldsIdsMap,
+) -> Result<String> {
+    let Some(source_field) = source_field else {
+        return prompt.render(obkv, side, fields_ids_map);
+    };
+
+    let value = fields_ids_map
+        .id(source_field)
+        .and_then(|fid| obkv.get(fid))
+        .and_then(|deladd| KvReaderDelAdd::new(deladd).get(side));
+    let Some(value) = value else { return Ok(String::new()) };
+
+    let text = match serde_json::from_slice(value).map_err(crate::InternalError::SerdeJson)? {
+        Value::Null => String::new(),
+        Value::String(text) => text,
+        value => value.to_string(),
+    };
+    Ok(text)
+}
+
 /// We cannot compute the diff between both Del and Add vectors.
 /// We'll push every vector and compute the difference later in TypedChunk.
 fn push_vectors_diff(
@@ -507,6 +763,7 @@ fn push_vectors_diff(
     prompts_writer: &mut Writer<BufWriter<File>>,
     manual_vectors_writer: &mut Writer<BufWriter<File>>,
     key_buffer: &mut Vec<u8>,
+    external_id: &[u8],
     delta: VectorStateDelta,
 ) -> Result<()> {
     let (must_remove, prompt, mut add_vectors) = delta.into_values();
@@ -516,6 +773,8 @@ fn push_vectors_diff(
     }
     if !prompt.is_empty() {
         key_buffer.truncate(TRUNCATE_SIZE);
+        // the external id is kept to report the documents whose embeddings can't be generated
+        key_buffer.extend_from_slice(external_id);
         prompts_writer.insert(&key_buffer, prompt.as_bytes())?;
     }
 
@@ -546,160 +805,298 @@ fn compare_vectors(a: &[f32], b: &[f32]) -> Ordering {
     a.iter().copied().map(OrderedFloat).cmp(b.iter().copied().map(OrderedFloat))
 }
 
+pub struct ExtractedEmbeddings {
+    // docid -> embeddings
+    pub embeddings: grenad::Reader<BufReader<File>>,
+    // cache key -> embeddings, for the prompts that were not found in the embedding cache
+    pub cache_entries: grenad::Reader<BufReader<File>>,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    // documents whose embeddings could not be generated
+    pub embedding_failures: Vec<EmbeddingFailure>,
+}
+
+/// A document whose texts are waiting to be embedded.
+struct PendingDocument {
+    docid: DocumentId,
+    external_id: String,
+    cache_key: Option<CacheKey>,
+    text_count: usize,
+}
+
+/// Generates the embeddings of the prompts, reusing the embeddings found in the embedding cache of the index.
+///
+/// When chunking is enabled, each prompt is split in several texts whose embeddings are all stored for the document.
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_embeddings<R: io::Read + io::Seek>(
-    // docid, prompt
+    // docid, external docid, prompt
     prompt_reader: grenad::Reader<R>,
     indexer: GrenadParameters,
+    index: &Index,
     embedder: Arc<Embedder>,
     embedder_name: &str,
+    cache_prefix: Option<CachePrefix>,
+    chunking: Option<&ChunkingOptions>,
+    on_error: OnEmbeddingError,
     possible_embedding_mistakes: &PossibleEmbeddingMistakes,
     unused_vectors_distribution: &UnusedVectorsDistribution,
     request_threads: &ThreadPoolNoAbort,
-) -> Result<grenad::Reader<BufReader<File>>> {
+) -> Result<ExtractedEmbeddings> {
     let n_chunks = embedder.chunk_count_hint(); // chunk level parallelism
     let n_vectors_per_chunk = embedder.prompt_count_in_chunk_hint(); // number of vectors in a single chunk
 
     // docid, state with embedding
-    let mut state_writer = create_writer(
+    // the embeddings found in the cache are inserted out of order with the generated ones
+    let mut state_sorter = create_sorter(
+        grenad::SortAlgorithm::Stable,
+        keep_first,
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        indexer.max_nb_chunks,
+        indexer.max_memory_by_thread(),
+    );
+    // cache key, embedding
+    let mut cache_sorter = create_sorter(
+        grenad::SortAlgorithm::Stable,
+        keep_first,
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
-        tempfile::tempfile()?,
+        indexer.max_nb_chunks,
+        indexer.max_memory_by_thread(),
     );
+    let mut cache_hits = 0;
+    let mut cache_misses = 0;
+    let mut embedding_failures = Vec::new();
 
-    let mut chunks = Vec::with_capacity(n_chunks);
-    let mut current_chunk = Vec::with_capacity(n_vectors_per_chunk);
-    let mut current_chunk_ids = Vec::with_capacity(n_vectors_per_chunk);
-    let mut chunks_ids = Vec::with_capacity(n_chunks);
+    // the cache contains the embeddings generated by the previous indexing operations
+    let rtxn = index.read_txn()?;
+
+    // the texts waiting to be embedded, and the documents they belong to
+    let mut texts = Vec::with_capacity(n_chunks * n_vectors_per_chunk);
+    let mut documents: Vec<PendingDocument> = Vec::new();
     let mut cursor = prompt_reader.into_cursor()?;
 
     while let Some((key, value)) = cursor.move_on_next()? {
-        let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
+        let (docid_bytes, external_id) = try_split_array_at::<u8, TRUNCATE_SIZE>(key).unwrap();
+        let docid = DocumentId::from_be_bytes(docid_bytes);
         // SAFETY: precondition, the grenad value was saved from a string
         let prompt = unsafe { std::str::from_utf8_unchecked(value) };
-        if current_chunk.len() == current_chunk.capacity() {
-            chunks.push(std::mem::replace(
-                &mut current_chunk,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-            chunks_ids.push(std::mem::replace(
-                &mut current_chunk_ids,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-        };
-        current_chunk.push(prompt.to_owned());
-        current_chunk_ids.push(docid);
 
-        if chunks.len() == chunks.capacity() {
-            let chunked_embeds = embed_chunks(
+        let cache_key = cache_prefix.as_ref().map(|prefix| cache_key(prefix, prompt));
+        if let Some(cache_key) = &cache_key {
+            if let Some(embeddings) = index.embedding_cache.get(&rtxn, cache_key)? {
+                state_sorter.insert(docid.to_be_bytes(), embeddings)?;
+                cache_hits += 1;
+                continue;
+            }
+            cache_misses += 1;
+        }
+
+        let document_texts = match chunking {
+            Some(chunking) => chunking.split(prompt),
+            None => vec![prompt.to_owned()],
+        };
+        documents.push(PendingDocument {
+            docid,
+            external_id: from_utf8(external_id).unwrap().to_owned(),
+            cache_key,
+            text_count: document_texts.len(),
+        });
+        texts.extend(document_texts);
+
+        // the texts of a document are always embedded together
+        if texts.len() >= texts.capacity() {
+            embed_documents(
                 &embedder,
-                std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks)),
-                embedder_name,
-                possible_embedding_mistakes,
-                unused_vectors_distribution,
+                std::mem::take(&mut texts),
+                std::mem::take(&mut documents),
+                (embedder_name, n_vectors_per_chunk, on_error),
+                (possible_embedding_mistakes, unused_vectors_distribution),
                 request_threads,
+                (&mut state_sorter, &mut cache_sorter),
+                &mut embedding_failures,
             )?;
-
-            for (docid, embeddings) in chunks_ids
-                .iter()
-                .flat_map(|docids| docids.iter())
-                .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-            {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
-            }
-            chunks_ids.clear();
+            texts.reserve(n_chunks * n_vectors_per_chunk);
         }
     }
 
-    // send last chunk
-    if !chunks.is_empty() {
-        let chunked_embeds = embed_chunks(
+    // send last texts
+    if !texts.is_empty() {
+        embed_documents(
             &embedder,
-            std::mem::take(&mut chunks),
-            embedder_name,
-            possible_embedding_mistakes,
-            unused_vectors_distribution,
+            texts,
+            documents,
+            (embedder_name, n_vectors_per_chunk, on_error),
+            (possible_embedding_mistakes, unused_vectors_distribution),
             request_threads,
+            (&mut state_sorter, &mut cache_sorter),
+            &mut embedding_failures,
         )?;
-        for (docid, embeddings) in chunks_ids
-            .iter()
-            .flat_map(|docids| docids.iter())
-            .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-        {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
-        }
     }
 
-    if !current_chunk.is_empty() {
-        let embeds = embed_chunks(
-            &embedder,
-            vec![std::mem::take(&mut current_chunk)],
+    Ok(ExtractedEmbeddings {
+        embeddings: sorter_into_reader(state_sorter, indexer)?,
+        cache_entries: sorter_into_reader(cache_sorter, indexer)?,
+        cache_hits,
+        cache_misses,
+        embedding_failures,
+    })
+}
+
+/// Embeds the texts of the documents and inserts their embeddings.
+///
+/// When the failures are tolerated and the texts cannot be embedded together, each document is embedded on its own
+/// so that only the documents whose embeddings cannot be generated are skipped.
+#[allow(clippy::too_many_arguments)]
+fn embed_documents(
+    embedder: &Embedder,
+    texts: Vec<String>,
+    documents: Vec<PendingDocument>,
+    (embedder_name, n_vectors_per_chunk, on_error): (&str, usize, OnEmbeddingError),
+    (possible_embedding_mistakes, unused_vectors_distribution): (
+        &PossibleEmbeddingMistakes,
+        &UnusedVectorsDistribution,
+    ),
+    request_threads: &ThreadPoolNoAbort,
+    (state_sorter, cache_sorter): (&mut grenad::Sorter<MergeFn>, &mut grenad::Sorter<MergeFn>),
+    embedding_failures: &mut Vec<EmbeddingFailure>,
+) -> Result<()> {
+    let skip_failures = on_error == OnEmbeddingError::SkipVectors;
+    let retry_texts = skip_failures.then(|| texts.clone());
+
+    let error =
+        match embedder.embed_chunks(into_chunks(texts, n_vectors_per_chunk), request_threads) {
+            Ok(embeddings) => {
+                return insert_embeddings(
+                    state_sorter,
+                    cache_sorter,
+                    documents,
+                    embeddings.into_iter().flatten(),
+                )
+            }
+            Err(error) => error,
+        };
+
+    let Some(texts) = retry_texts.filter(|_| !matches!(error.fault, FaultSource::Bug)) else {
+        return Err(embedding_error(
+            error,
             embedder_name,
             possible_embedding_mistakes,
             unused_vectors_distribution,
-            request_threads,
-        )?;
+        ));
+    };
 
-        if let Some(embeds) = embeds.first() {
-            for (docid, embeddings) in current_chunk_ids.iter().zip(embeds.iter()) {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+    let mut texts = texts.into_iter();
+    for document in documents {
+        let document_texts: Vec<String> = texts.by_ref().take(document.text_count).collect();
+        match embedder
+            .embed_chunks(into_chunks(document_texts, n_vectors_per_chunk), request_threads)
+        {
+            Ok(embeddings) => insert_embeddings(
+                state_sorter,
+                cache_sorter,
+                std::iter::once(document),
+                embeddings.into_iter().flatten(),
+            )?,
+            Err(error) if !matches!(error.fault, FaultSource::Bug) => {
+                tracing::warn!(
+                    embedder = embedder_name,
+                    document = %document.external_id,
+                    %error,
+                    "Skipping the vectors of a document"
+                );
+                embedding_failures.push(EmbeddingFailure {
+                    embedder_name: embedder_name.to_owned(),
+                    document_id: document.external_id,
+                    reason: error.to_string(),
+                });
+            }
+            Err(error) => {
+                return Err(embedding_error(
+                    error,
+                    embedder_name,
+                    possible_embedding_mistakes,
+                    unused_vectors_distribution,
+                ))
             }
         }
     }
+    Ok(())
+}
 
-    writer_into_reader(state_writer)
+/// Splits the texts to embed in chunks of at most `n_vectors_per_chunk` texts.
+fn into_chunks(texts: Vec<String>, n_vectors_per_chunk: usize) -> Vec<Vec<String>> {
+    let mut chunks = Vec::with_capacity(texts.len().div_ceil(n_vectors_per_chunk));
+    let mut texts = texts.into_iter().peekable();
+    while texts.peek().is_some() {
+        chunks.push(texts.by_ref().take(n_vectors_per_chunk).collect());
+    }
+    chunks
 }
 
-fn embed_chunks(
-    embedder: &Embedder,
-    text_chunks: Vec<Vec<String>>,
+/// Inserts the generated embeddings of the documents, and of their prompts to be cached.
+///
+/// The embeddings of the texts of a document are merged, in order, as the embeddings of the document.
+fn insert_embeddings(
+    state_sorter: &mut grenad::Sorter<MergeFn>,
+    cache_sorter: &mut grenad::Sorter<MergeFn>,
+    documents: impl IntoIterator<Item = PendingDocument>,
+    mut embeddings: impl Iterator<Item = Embeddings<f32>>,
+) -> Result<()> {
+    let mut document_embeddings = Vec::new();
+    for PendingDocument { docid, external_id: _, cache_key, text_count } in documents {
+        document_embeddings.clear();
+        for text_embeddings in embeddings.by_ref().take(text_count) {
+            document_embeddings.extend_from_slice(text_embeddings.as_inner());
+        }
+        let document_embeddings: &[u8] = cast_slice(&document_embeddings);
+        state_sorter.insert(docid.to_be_bytes(), document_embeddings)?;
+        if let Some(cache_key) = cache_key {
+            cache_sorter.insert(cache_key, document_embeddings)?;
+        }
+    }
+    Ok(())
+}
+
+fn embedding_error(
+    error: EmbedError,
     embedder_name: &str,
     possible_embedding_mistakes: &PossibleEmbeddingMistakes,
     unused_vectors_distribution: &UnusedVectorsDistribution,
-    request_threads: &ThreadPoolNoAbort,
-) -> Result<Vec<Vec<Embeddings<f32>>>> {
-    match embedder.embed_chunks(text_chunks, request_threads) {
-        Ok(chunks) => Ok(chunks),
-        Err(error) => {
-            if let FaultSource::Bug = error.fault {
-                Err(crate::Error::InternalError(crate::InternalError::VectorEmbeddingError(
-                    error.into(),
-                )))
-            } else {
-                let mut msg =
-                    format!(r"While embedding documents for embedder `{embedder_name}`: {error}");
-
-                if let EmbedErrorKind::ManualEmbed(_) = &error.kind {
-                    msg += &format!("\n- Note: `{embedder_name}` has `source: userProvided`, so documents must provide embeddings as an array in `_vectors.{embedder_name}`.");
-                }
+) -> crate::Error {
+    if let FaultSource::Bug = error.fault {
+        crate::Error::InternalError(crate::InternalError::VectorEmbeddingError(error.into()))
+    } else {
+        let mut msg = format!(r"While embedding documents for embedder `{embedder_name}`: {error}");
 
-                let mut hint_count = 0;
+        if let EmbedErrorKind::ManualEmbed(_) = &error.kind {
+            msg += &format!("\n- Note: `{embedder_name}` has `source: userProvided`, so documents must provide embeddings as an array in `_vectors.{embedder_name}`.");
+        }
 
-                for (vector_misspelling, count) in
-                    possible_embedding_mistakes.vector_mistakes().take(2)
-                {
-                    msg += &format!("\n- Hint: try replacing `{vector_misspelling}` by `_vectors` in {count} document(s).");
-                    hint_count += 1;
-                }
+        let mut hint_count = 0;
 
-                for (embedder_misspelling, count) in possible_embedding_mistakes
-                    .embedder_mistakes(embedder_name, unused_vectors_distribution)
-                    .take(2)
-                {
-                    msg += &format!("\n- Hint: try replacing `_vectors.{embedder_misspelling}` by `_vectors.{embedder_name}` in {count} document(s).");
-                    hint_count += 1;
-                }
+        for (vector_misspelling, count) in possible_embedding_mistakes.vector_mistakes().take(2) {
+            msg += &format!("\n- Hint: try replacing `{vector_misspelling}` by `_vectors` in {count} document(s).");
+            hint_count += 1;
+        }
 
-                if hint_count == 0 {
-                    if let EmbedErrorKind::ManualEmbed(_) = &error.kind {
-                        msg += &format!(
-                            "\n- Hint: opt-out for a document with `_vectors.{embedder_name}: null`"
-                        );
-                    }
-                }
+        for (embedder_misspelling, count) in possible_embedding_mistakes
+            .embedder_mistakes(embedder_name, unused_vectors_distribution)
+            .take(2)
+        {
+            msg += &format!("\n- Hint: try replacing `_vectors.{embedder_misspelling}` by `_vectors.{embedder_name}` in {count} document(s).");
+            hint_count += 1;
+        }
 
-                Err(crate::Error::UserError(crate::UserError::DocumentEmbeddingError(msg)))
+        if hint_count == 0 {
+            if let EmbedErrorKind::ManualEmbed(_) = &error.kind {
+                msg += &format!(
+                    "\n- Hint: opt-out for a document with `_vectors.{embedder_name}: null`"
+                );
             }
         }
+
+        crate::Error::UserError(crate::UserError::DocumentEmbeddingError(msg))
     }
 }
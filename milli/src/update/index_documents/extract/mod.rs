@@ -23,7 +23,8 @@ use self::extract_fid_docid_facet_values::{extract_fid_docid_facet_values, Extra
 use self::extract_fid_word_count_docids::extract_fid_word_count_docids;
 use self::extract_geo_points::extract_geo_points;
 use self::extract_vector_points::{
-    extract_embeddings, extract_vector_points, ExtractedVectorPoints,
+    extract_embeddings, extract_vector_points, ExtractedEmbeddings, ExtractedSparseVectors,
+    ExtractedVectorPoints,
 };
 use self::extract_word_docids::extract_word_docids;
 use self::extract_word_pair_proximity_docids::extract_word_pair_proximity_docids;
@@ -33,7 +34,7 @@ use super::{helpers, TypedChunk};
 use crate::index::IndexEmbeddingConfig;
 use crate::update::settings::InnerIndexSettingsDiff;
 use crate::vector::error::PossibleEmbeddingMistakes;
-use crate::{FieldId, Result, ThreadPoolNoAbort, ThreadPoolNoAbortBuilder};
+use crate::{FieldId, Index, Result, ThreadPoolNoAbort, ThreadPoolNoAbortBuilder};
 
 /// Extract data for each databases from obkv documents in parallel.
 /// Send data in grenad file over provided Sender.
@@ -43,6 +44,7 @@ pub(crate) fn data_from_obkv_documents(
     original_obkv_chunks: impl Iterator<Item = Result<grenad::Reader<BufReader<File>>>> + Send,
     flattened_obkv_chunks: impl Iterator<Item = Result<grenad::Reader<BufReader<File>>>> + Send,
     indexer: GrenadParameters,
+    index: Index,
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     primary_key_id: FieldId,
     embedders_configs: Arc<Vec<IndexEmbeddingConfig>>,
@@ -58,6 +60,7 @@ pub(crate) fn data_from_obkv_documents(
                     send_original_documents_data(
                         original_documents_chunk,
                         indexer,
+                        index.clone(),
                         lmdb_writer_sx.clone(),
                         embedders_configs.clone(),
                         settings_diff.clone(),
@@ -227,6 +230,7 @@ fn request_threads() -> &'static ThreadPoolNoAbort {
 fn send_original_documents_data(
     original_documents_chunk: Result<grenad::Reader<BufReader<File>>>,
     indexer: GrenadParameters,
+    index: Index,
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     embedders_configs: Arc<Vec<IndexEmbeddingConfig>>,
     settings_diff: Arc<InnerIndexSettingsDiff>,
@@ -252,22 +256,39 @@ fn send_original_documents_data(
                 &embedders_configs,
                 &settings_diff,
             ) {
-                Ok((extracted_vectors, unused_vectors_distribution)) => {
+                Ok((extracted_vectors, extracted_sparse_vectors, unused_vectors_distribution)) => {
+                    for ExtractedSparseVectors { postings, embedder_name } in
+                        extracted_sparse_vectors
+                    {
+                        if !postings.is_empty() {
+                            let _ = lmdb_writer_sx
+                                .send(Ok(TypedChunk::SparseVectors { postings, embedder_name }));
+                        }
+                    }
+
                     for ExtractedVectorPoints {
                         manual_vectors,
                         remove_vectors,
                         prompts,
                         embedder_name,
                         embedder,
+                        cache_prefix,
+                        chunking,
+                        on_error,
+                        mut embedding_failures,
                         add_to_user_provided,
                         remove_from_user_provided,
                     } in extracted_vectors
                     {
-                        let embeddings = match extract_embeddings(
+                        let extracted = match extract_embeddings(
                             prompts,
                             indexer,
+                            &index,
                             embedder.clone(),
                             &embedder_name,
+                            cache_prefix,
+                            chunking.as_ref(),
+                            on_error,
                             &possible_embedding_mistakes,
                             &unused_vectors_distribution,
                             request_threads(),
@@ -278,13 +299,34 @@ fn send_original_documents_data(
                                 None
                             }
                         };
+                        let (embeddings, cache_entries, cache_hits, cache_misses) = match extracted
+                        {
+                            Some(ExtractedEmbeddings {
+                                embeddings,
+                                cache_entries,
+                                cache_hits,
+                                cache_misses,
+                                embedding_failures: failures,
+                            }) => {
+                                embedding_failures.extend(failures);
+                                (Some(embeddings), Some(cache_entries), cache_hits, cache_misses)
+                            }
+                            None => (None, None, 0, 0),
+                        };
                         if !(remove_vectors.is_empty()
                             && manual_vectors.is_empty()
-                            && embeddings.as_ref().map_or(true, |e| e.is_empty()))
+                            && embeddings.as_ref().map_or(true, |e| e.is_empty())
+                            && cache_hits == 0
+                            && cache_misses == 0
+                            && embedding_failures.is_empty())
                         {
                             let _ = lmdb_writer_sx.send(Ok(TypedChunk::VectorPoints {
                                 remove_vectors,
                                 embeddings,
+                                cache_entries,
+                                cache_hits,
+                                cache_misses,
+                                embedding_failures,
                                 expected_dimension: embedder.dimensions(),
                                 manual_vectors,
                                 embedder_name,
@@ -43,7 +43,8 @@ use crate::update::index_documents::parallel::ImmutableObkvs;
 use crate::update::{
     IndexerConfig, UpdateIndexingStep, WordPrefixDocids, WordPrefixIntegerDocids, WordsPrefixesFst,
 };
-use crate::vector::EmbeddingConfigs;
+use crate::vector::error::{EmbeddingFailure, MAX_REPORTED_EMBEDDING_FAILURES};
+use crate::vector::{ArroyWrapper, EmbeddingConfigs};
 use crate::{CboRoaringBitmapCodec, Index, Object, Result};
 
 static MERGED_DATABASE_COUNT: usize = 7;
@@ -56,6 +57,9 @@ pub struct DocumentAdditionResult {
     pub indexed_documents: u64,
     /// The total number of documents in the index after the update
     pub number_of_documents: u64,
+    /// The documents indexed without vectors because their embeddings could not be generated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedding_failures: Vec<EmbeddingFailure>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -384,7 +388,11 @@ where
     pub fn execute(mut self) -> Result<DocumentAdditionResult> {
         if self.added_documents == 0 && self.deleted_documents == 0 {
             let number_of_documents = self.index.number_of_documents(self.wtxn)?;
-            return Ok(DocumentAdditionResult { indexed_documents: 0, number_of_documents });
+            return Ok(DocumentAdditionResult {
+                indexed_documents: 0,
+                number_of_documents,
+                embedding_failures: Vec::new(),
+            });
         }
         let output = self
             .transform
@@ -393,19 +401,21 @@ where
             .output_from_sorter(self.wtxn, &self.progress)?;
 
         let indexed_documents = output.documents_count as u64;
-        let number_of_documents = self.execute_raw(output)?;
+        let (number_of_documents, embedding_failures) = self.execute_raw(output)?;
 
-        Ok(DocumentAdditionResult { indexed_documents, number_of_documents })
+        Ok(DocumentAdditionResult { indexed_documents, number_of_documents, embedding_failures })
     }
 
-    /// Returns the total number of documents in the index after the update.
+    /// Returns the total number of documents in the index after the update,
+    /// and at most [`MAX_REPORTED_EMBEDDING_FAILURES`] documents whose vectors were skipped
+    /// because their embeddings could not be generated.
     #[tracing::instrument(
         level = "trace",
         skip_all,
         target = "indexing::details",
         name = "index_documents_raw"
     )]
-    pub fn execute_raw(self, output: TransformOutput) -> Result<u64>
+    pub fn execute_raw(self, output: TransformOutput) -> Result<(u64, Vec<EmbeddingFailure>)>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -507,12 +517,14 @@ where
         let mut exact_word_docids = None;
         let mut chunk_accumulator = ChunkAccumulator::default();
         let mut dimension = HashMap::new();
+        let mut embedding_failures = Vec::new();
 
         let current_span = tracing::Span::current();
 
         // Run extraction pipeline in parallel.
         pool.install(|| {
             let settings_diff_cloned = settings_diff.clone();
+            let index = self.index.clone();
             rayon::spawn(move || {
                 let child_span = tracing::trace_span!(target: "indexing::details", parent: &current_span, "extract_and_send_grenad_chunks");
                 let _enter = child_span.enter();
@@ -540,6 +552,7 @@ where
                         original_chunk,
                         flattened_chunk,
                         pool_params,
+                        index,
                         lmdb_writer_sx.clone(),
                         primary_key_id,
                         embedders_configs.clone(),
@@ -644,15 +657,35 @@ where
                                 expected_dimension,
                                 remove_vectors,
                                 embeddings,
+                                cache_entries,
+                                cache_hits,
+                                cache_misses,
+                                embedding_failures: failures,
                                 manual_vectors,
                                 embedder_name,
                                 add_to_user_provided,
                                 remove_from_user_provided,
                             } => {
                                 dimension.insert(embedder_name.clone(), expected_dimension);
+                                for failure in failures {
+                                    if embedding_failures.len() < MAX_REPORTED_EMBEDDING_FAILURES {
+                                        embedding_failures.push(failure);
+                                    } else {
+                                        tracing::warn!(
+                                            embedder = %failure.embedder_name,
+                                            document = %failure.document_id,
+                                            reason = %failure.reason,
+                                            "skipped the vectors of a document"
+                                        );
+                                    }
+                                }
                                 TypedChunk::VectorPoints {
                                     remove_vectors,
                                     embeddings,
+                                    cache_entries,
+                                    cache_hits,
+                                    cache_misses,
+                                    embedding_failures: Vec::new(),
                                     expected_dimension,
                                     manual_vectors,
                                     embedder_name,
@@ -679,6 +712,17 @@ where
         let number_of_documents = self.index.number_of_documents(self.wtxn)?;
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
 
+        // The embedders being converted must be built even if none of their embeddings was updated
+        for (embedder_name, action) in settings_diff.embedding_config_updates.iter() {
+            if action.is_being_converted() && !dimension.contains_key(embedder_name) {
+                let Some((embedder, _, _)) = settings_diff.new.embedding_configs.get(embedder_name)
+                else {
+                    continue;
+                };
+                dimension.insert(embedder_name.clone(), embedder.dimensions());
+            }
+        }
+
         for (embedder_name, dimension) in dimension {
             let wtxn = &mut *self.wtxn;
             let vector_arroy = self.index.vector_arroy;
@@ -686,16 +730,22 @@ where
             let embedder_index = self.index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
+            let old_options = settings_diff
+                .old
+                .embedding_configs
+                .get(&embedder_name)
+                .map(|(_, _, options)| options)
+                .unwrap_or_default();
+            let new_options = settings_diff
+                .new
+                .embedding_configs
+                .get(&embedder_name)
+                .map(|(_, _, options)| options)
+                .unwrap_or(old_options);
 
             pool.install(|| {
-                for k in crate::vector::arroy_db_range_for_embedder(embedder_index) {
-                    let writer = arroy::Writer::new(vector_arroy, k, dimension);
-                    if writer.need_build(wtxn)? {
-                        writer.build(wtxn, &mut rng, None)?;
-                    } else if writer.is_empty(wtxn)? {
-                        break;
-                    }
-                }
+                let mut writer = ArroyWrapper::new(vector_arroy, embedder_index, old_options);
+                writer.build_and_convert(wtxn, &mut rng, dimension, new_options)?;
                 Result::Ok(())
             })
             .map_err(InternalError::from)??;
@@ -708,7 +758,7 @@ where
             word_fid_docids.map(MergerBuilder::build),
         )?;
 
-        Ok(number_of_documents)
+        Ok((number_of_documents, embedding_failures))
     }
 
     #[tracing::instrument(
@@ -2382,6 +2432,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 2,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2431,6 +2482,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 5,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2469,6 +2521,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 3,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2507,6 +2560,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2547,6 +2601,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 2,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2594,6 +2649,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2630,6 +2686,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2665,6 +2722,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2737,6 +2795,7 @@ mod tests {
                         source: Setting::Set(crate::vector::settings::EmbedderSource::UserProvided),
                         model: Setting::NotSet,
                         revision: Setting::NotSet,
+                        model_path: Setting::NotSet,
                         api_key: Setting::NotSet,
                         dimensions: Setting::Set(3),
                         document_template: Setting::NotSet,
@@ -2746,7 +2805,16 @@ mod tests {
                         path_to_embeddings: Setting::NotSet,
                         embedding_object: Setting::NotSet,
                         input_type: Setting::NotSet,
+                        headers: Setting::NotSet,
+                        max_batch_size: Setting::NotSet,
+                        max_concurrent_requests: Setting::NotSet,
                         distribution: Setting::NotSet,
+                        binary_quantized: Setting::NotSet,
+                        distance: Setting::NotSet,
+                        chunking: Setting::NotSet,
+                        search_embedder: Setting::NotSet,
+                        on_error: Setting::NotSet,
+                        sparse: Setting::NotSet,
                     }),
                 );
                 settings.set_embedder_settings(embedders);
@@ -2843,6 +2911,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2887,6 +2956,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2927,6 +2997,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 2,
+            embedding_failures: [],
         }
         "###);
         wtxn.commit().unwrap();
@@ -2,6 +2,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::convert::TryInto;
 use std::fs::File;
 use std::io::{self, BufReader};
+use std::mem::size_of;
 
 use bytemuck::allocation::pod_collect_to_vec;
 use charabia::{Language, Script};
@@ -28,6 +29,9 @@ use crate::update::index_documents::helpers::{
     as_cloneable_grenad, keep_latest_obkv, try_split_array_at,
 };
 use crate::update::settings::InnerIndexSettingsDiff;
+use crate::vector::error::EmbeddingFailure;
+use crate::vector::sparse::SparseVectorStore;
+use crate::vector::ArroyWrapper;
 use crate::{
     lat_lng_to_xyz, CboRoaringBitmapCodec, DocumentId, FieldId, GeoPoint, Index, InternalError,
     Result, SerializationError, U8StrStrCodec,
@@ -88,12 +92,22 @@ pub(crate) enum TypedChunk {
     VectorPoints {
         remove_vectors: grenad::Reader<BufReader<File>>,
         embeddings: Option<grenad::Reader<BufReader<File>>>,
+        cache_entries: Option<grenad::Reader<BufReader<File>>>,
+        cache_hits: u64,
+        cache_misses: u64,
+        // documents whose vectors were skipped, reported in the result of the indexing
+        embedding_failures: Vec<EmbeddingFailure>,
         expected_dimension: usize,
         manual_vectors: grenad::Reader<BufReader<File>>,
         embedder_name: String,
         add_to_user_provided: RoaringBitmap,
         remove_from_user_provided: RoaringBitmap,
     },
+    SparseVectors {
+        // token length, token, docid -> weight, or nothing when the token is removed from the document
+        postings: grenad::Reader<BufReader<File>>,
+        embedder_name: String,
+    },
     ScriptLanguageDocids(HashMap<(Script, Language), (RoaringBitmap, RoaringBitmap)>),
 }
 
@@ -119,6 +133,10 @@ impl TypedChunk {
                 VectorPoints { embedder_name: left, expected_dimension: left_dim, .. },
                 VectorPoints { embedder_name: right, expected_dimension: right_dim, .. },
             ) => left == right && left_dim == right_dim,
+            (
+                SparseVectors { embedder_name: left, .. },
+                SparseVectors { embedder_name: right, .. },
+            ) => left == right,
             _ => false,
         }
     }
@@ -157,9 +175,11 @@ pub(crate) fn write_typed_chunk_into_index(
             let mut docids = index.documents_ids(wtxn)?;
             let mut iter = merger.into_stream_merger_iter()?;
 
+            // the sparse vectors are kept in the documents
             let embedders: BTreeSet<_> = index
                 .embedding_configs(wtxn)?
                 .into_iter()
+                .filter(|IndexEmbeddingConfig { config, .. }| !config.embedder_options.is_sparse())
                 .map(|IndexEmbeddingConfig { name, .. }| name)
                 .collect();
             let mut vectors_buffer = Vec::new();
@@ -625,6 +645,9 @@ pub(crate) fn write_typed_chunk_into_index(
             let mut remove_vectors_builder = MergerBuilder::new(keep_first as MergeFn);
             let mut manual_vectors_builder = MergerBuilder::new(keep_first as MergeFn);
             let mut embeddings_builder = MergerBuilder::new(keep_first as MergeFn);
+            let mut cache_entries_builder = MergerBuilder::new(keep_first as MergeFn);
+            let mut cache_hits = 0;
+            let mut cache_misses = 0;
             let mut add_to_user_provided = RoaringBitmap::new();
             let mut remove_from_user_provided = RoaringBitmap::new();
             let mut params = None;
@@ -633,6 +656,10 @@ pub(crate) fn write_typed_chunk_into_index(
                     remove_vectors,
                     manual_vectors,
                     embeddings,
+                    cache_entries,
+                    cache_hits: hits,
+                    cache_misses: misses,
+                    embedding_failures: _,
                     expected_dimension,
                     embedder_name,
                     add_to_user_provided: aud,
@@ -649,6 +676,11 @@ pub(crate) fn write_typed_chunk_into_index(
                 if let Some(embeddings) = embeddings {
                     embeddings_builder.push(embeddings.into_cursor()?);
                 }
+                if let Some(cache_entries) = cache_entries {
+                    cache_entries_builder.push(cache_entries.into_cursor()?);
+                }
+                cache_hits += hits;
+                cache_misses += misses;
                 add_to_user_provided |= aud;
                 remove_from_user_provided |= rud;
             }
@@ -669,23 +701,22 @@ pub(crate) fn write_typed_chunk_into_index(
             let embedder_index = index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
-            // FIXME: allow customizing distance
-            let writers: Vec<_> = crate::vector::arroy_db_range_for_embedder(embedder_index)
-                .map(|k| arroy::Writer::new(index.vector_arroy, k, expected_dimension))
-                .collect();
+            // the embeddings are written with the previous distance and quantization,
+            // and converted once all the chunks are processed
+            let options = settings_diff
+                .old
+                .embedding_configs
+                .get(&embedder_name)
+                .map(|(_, _, options)| options)
+                .unwrap_or_default();
+            let writer = ArroyWrapper::new(index.vector_arroy, embedder_index, options);
 
             // remove vectors for docids we want them removed
             let merger = remove_vectors_builder.build();
             let mut iter = merger.into_stream_merger_iter()?;
             while let Some((key, _)) = iter.next()? {
                 let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
-
-                for writer in &writers {
-                    // Uses invariant: vectors are packed in the first writers.
-                    if !writer.del_item(wtxn, docid)? {
-                        break;
-                    }
-                }
+                writer.del_items(wtxn, expected_dimension, docid)?;
             }
 
             // add generated embeddings
@@ -713,9 +744,7 @@ pub(crate) fn write_typed_chunk_into_index(
                         embeddings.embedding_count(),
                     )));
                 }
-                for (embedding, writer) in embeddings.iter().zip(&writers) {
-                    writer.add_item(wtxn, docid, embedding)?;
-                }
+                writer.add_items(wtxn, docid, &embeddings)?;
             }
 
             // perform the manual diff
@@ -729,52 +758,70 @@ pub(crate) fn write_typed_chunk_into_index(
                 let vector_deladd_obkv = KvReaderDelAdd::new(value);
                 if let Some(value) = vector_deladd_obkv.get(DelAdd::Deletion) {
                     let vector: Vec<f32> = pod_collect_to_vec(value);
-
-                    let mut deleted_index = None;
-                    for (index, writer) in writers.iter().enumerate() {
-                        let Some(candidate) = writer.item_vector(wtxn, docid)? else {
-                            // uses invariant: vectors are packed in the first writers.
-                            break;
-                        };
-                        if candidate == vector {
-                            writer.del_item(wtxn, docid)?;
-                            deleted_index = Some(index);
-                        }
-                    }
-
-                    // 🥲 enforce invariant: vectors are packed in the first writers.
-                    if let Some(deleted_index) = deleted_index {
-                        let mut last_index_with_a_vector = None;
-                        for (index, writer) in writers.iter().enumerate().skip(deleted_index) {
-                            let Some(candidate) = writer.item_vector(wtxn, docid)? else {
-                                break;
-                            };
-                            last_index_with_a_vector = Some((index, candidate));
-                        }
-                        if let Some((last_index, vector)) = last_index_with_a_vector {
-                            // unwrap: computed the index from the list of writers
-                            let writer = writers.get(last_index).unwrap();
-                            writer.del_item(wtxn, docid)?;
-                            writers.get(deleted_index).unwrap().add_item(wtxn, docid, &vector)?;
-                        }
-                    }
+                    writer.del_item(wtxn, docid, &vector)?;
                 }
 
                 if let Some(value) = vector_deladd_obkv.get(DelAdd::Addition) {
-                    let vector = pod_collect_to_vec(value);
-
+                    let vector: Vec<f32> = pod_collect_to_vec(value);
                     // overflow was detected during vector extraction.
-                    for writer in &writers {
-                        if !writer.contains_item(wtxn, docid)? {
-                            writer.add_item(wtxn, docid, &vector)?;
-                            break;
-                        }
-                    }
+                    writer.add_item(wtxn, docid, &vector)?;
                 }
             }
 
+            // store the embeddings of the new prompts in the embedding cache
+            let merger = cache_entries_builder.build();
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, value)) = iter.next()? {
+                index.embedding_cache.put(wtxn, key, value)?;
+            }
+
+            if cache_hits != 0 || cache_misses != 0 {
+                let mut stats = index.embedding_cache_stats(wtxn)?;
+                stats.hits += cache_hits;
+                stats.misses += cache_misses;
+                index.put_embedding_cache_stats(wtxn, &stats)?;
+            }
+
             tracing::debug!("Finished vector chunk for {}", embedder_name);
         }
+        TypedChunk::SparseVectors { .. } => {
+            let span = tracing::trace_span!(target: "indexing::write_db", "sparse_vectors");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(keep_first as MergeFn);
+            let mut params = None;
+            for typed_chunk in typed_chunks {
+                let TypedChunk::SparseVectors { postings, embedder_name } = typed_chunk else {
+                    unreachable!();
+                };
+                params = Some(embedder_name);
+                builder.push(postings.into_cursor()?);
+            }
+
+            // typed chunks has always at least 1 chunk.
+            let Some(embedder_name) = params else { unreachable!() };
+
+            let embedder_index = index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
+                InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
+            )?;
+            let store = SparseVectorStore::new(index.sparse_vector_postings, embedder_index);
+
+            let merger = builder.build();
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, value)) = iter.next()? {
+                let (token, docid) = key[1..].split_at(key.len() - 1 - size_of::<DocumentId>());
+                let token = std::str::from_utf8(token)?;
+                let docid = docid.try_into().map(DocumentId::from_be_bytes).unwrap();
+                match value.try_into() {
+                    Ok(weight) => store.put(wtxn, token, docid, f32::from_be_bytes(weight))?,
+                    Err(_) => {
+                        store.del(wtxn, token, docid)?;
+                    }
+                }
+            }
+
+            tracing::debug!("Finished sparse vector chunk for {}", embedder_name);
+        }
         TypedChunk::ScriptLanguageDocids(_) => {
             let span = tracing::trace_span!(target: "indexing::write_db", "script_language_docids");
             let _entered = span.enter();
@@ -20,15 +20,22 @@ use crate::index::{
 };
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
+use crate::query_rules::{validate_boost_filters, validate_query_rules};
+use crate::rerank::validate_rerankers;
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
+use crate::vector::cache::{cache_prefix, CachePrefix};
+use crate::vector::error::EmbeddingFailure;
 use crate::vector::parsed_vectors::RESERVED_VECTORS_FIELD_NAME;
 use crate::vector::settings::{
     check_set, check_unset, EmbedderAction, EmbedderSource, EmbeddingSettings, ReindexAction,
     WriteBackToDocuments,
 };
-use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
-use crate::{FieldId, FieldsIdsMap, Index, Result};
+use crate::vector::{
+    asymmetric, ArroyOptions, Distance, Embedder, EmbedderOptions, EmbeddingConfig,
+    EmbeddingConfigs,
+};
+use crate::{FieldId, FieldsIdsMap, Index, QueryRule, RerankerSettings, Result};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Setting<T> {
@@ -159,6 +166,8 @@ pub struct Settings<'a, 't, 'i> {
     proximity_precision: Setting<ProximityPrecision>,
     embedder_settings: Setting<BTreeMap<String, Setting<EmbeddingSettings>>>,
     search_cutoff: Setting<u64>,
+    query_rules: Setting<Vec<QueryRule>>,
+    rerankers: Setting<BTreeMap<String, RerankerSettings>>,
 }
 
 impl<'a, 't, 'i> Settings<'a, 't, 'i> {
@@ -193,6 +202,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             proximity_precision: Setting::NotSet,
             embedder_settings: Setting::NotSet,
             search_cutoff: Setting::NotSet,
+            query_rules: Setting::NotSet,
+            rerankers: Setting::NotSet,
             indexer_config,
         }
     }
@@ -391,6 +402,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.search_cutoff = Setting::Reset;
     }
 
+    pub fn set_query_rules(&mut self, value: Vec<QueryRule>) {
+        self.query_rules = Setting::Set(value);
+    }
+
+    pub fn reset_query_rules(&mut self) {
+        self.query_rules = Setting::Reset;
+    }
+
+    pub fn set_rerankers(&mut self, value: BTreeMap<String, RerankerSettings>) {
+        self.rerankers = Setting::Set(value);
+    }
+
+    pub fn reset_rerankers(&mut self) {
+        self.rerankers = Setting::Reset;
+    }
+
     #[tracing::instrument(
         level = "trace"
         skip(self, progress_callback, should_abort, settings_diff),
@@ -401,7 +428,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         progress_callback: &FP,
         should_abort: &FA,
         settings_diff: InnerIndexSettingsDiff,
-    ) -> Result<()>
+    ) -> Result<Vec<EmbeddingFailure>>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -409,7 +436,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         // if the settings are set before any document update, we don't need to do anything, and
         // will set the primary key during the first document addition.
         if self.index.number_of_documents(self.wtxn)? == 0 {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         let transform = Transform::new(
@@ -434,9 +461,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             &should_abort,
         )?;
 
-        indexing_builder.execute_raw(output)?;
+        let (_, embedding_failures) = indexing_builder.execute_raw(output)?;
 
-        Ok(())
+        Ok(embedding_failures)
     }
 
     fn update_displayed(&mut self) -> Result<bool> {
@@ -935,7 +962,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                 let old_configs = self.index.embedding_configs(self.wtxn)?;
                 let remove_all: Result<BTreeMap<String, EmbedderAction>> = old_configs
                     .into_iter()
-                    .map(|IndexEmbeddingConfig { name, config: _, user_provided }| -> Result<_> {
+                    .map(|IndexEmbeddingConfig { name, config, user_provided }| -> Result<_> {
                         let embedder_id =
                             self.index.embedder_category_id.get(self.wtxn, &name)?.ok_or(
                                 crate::InternalError::DatabaseMissingEntry {
@@ -945,10 +972,10 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                             )?;
                         Ok((
                             name,
-                            EmbedderAction::WriteBackToDocuments(WriteBackToDocuments {
-                                embedder_id,
-                                user_provided,
-                            }),
+                            EmbedderAction::with_write_back(
+                                WriteBackToDocuments { embedder_id, user_provided },
+                                config.arroy_options(),
+                            ),
                         ))
                     })
                     .collect();
@@ -957,12 +984,28 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
 
                 self.index.embedder_category_id.clear(self.wtxn)?;
                 self.index.delete_embedding_configs(self.wtxn)?;
+                self.index.clear_embedding_cache(self.wtxn)?;
                 Ok(remove_all)
             }
             Setting::NotSet => Ok(Default::default()),
         }
     }
 
+    /// Removes the cached embeddings of an embedder whose new options change its cache prefix,
+    /// as they can no longer be looked up.
+    fn delete_outdated_embedding_cache(
+        &mut self,
+        old_cache_prefix: &CachePrefix,
+        new: &Setting<EmbeddingSettings>,
+    ) -> Result<()> {
+        if let Setting::Set(new) = new {
+            if cache_prefix(&EmbeddingConfig::from(new.clone())) != *old_cache_prefix {
+                self.index.delete_embedding_cache_prefix(self.wtxn, old_cache_prefix)?;
+            }
+        }
+        Ok(())
+    }
+
     fn update_embedding_configs_set(
         &mut self,
         configs: BTreeMap<String, Setting<EmbeddingSettings>>,
@@ -985,7 +1028,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             match joined {
                 // updated config
                 EitherOrBoth::Both((name, (old, user_provided)), (_, new)) => {
-                    let settings_diff = SettingsDiff::from_settings(old, new);
+                    let old_options = ArroyOptions {
+                        distance: old.distance.set().unwrap_or_default(),
+                        quantized: old.binary_quantized.set().unwrap_or_default(),
+                    };
+                    let old_cache_prefix = cache_prefix(&EmbeddingConfig::from(old.clone()));
+                    let settings_diff = SettingsDiff::from_settings(&name, old, new)?;
                     match settings_diff {
                         SettingsDiff::Remove => {
                             tracing::debug!(
@@ -1002,27 +1050,34 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                                 )?;
                             // free id immediately
                             self.index.embedder_category_id.delete(self.wtxn, &name)?;
+                            self.index
+                                .delete_embedding_cache_prefix(self.wtxn, &old_cache_prefix)?;
                             embedder_actions.insert(
                                 name,
-                                EmbedderAction::WriteBackToDocuments(WriteBackToDocuments {
-                                    embedder_id,
-                                    user_provided,
-                                }),
+                                EmbedderAction::with_write_back(
+                                    WriteBackToDocuments { embedder_id, user_provided },
+                                    old_options,
+                                ),
                             );
                         }
-                        SettingsDiff::Reindex { action, updated_settings } => {
+                        SettingsDiff::Reindex { action, updated_settings, convert } => {
                             tracing::debug!(
                                 embedder = name,
                                 user_provided = user_provided.len(),
                                 ?action,
                                 "reindex embedder"
                             );
-                            embedder_actions.insert(name.clone(), EmbedderAction::Reindex(action));
+                            embedder_actions.insert(
+                                name.clone(),
+                                EmbedderAction::with_reindex(action, old_options)
+                                    .with_is_being_converted(convert),
+                            );
                             let new =
                                 validate_embedding_settings(Setting::Set(updated_settings), &name)?;
+                            self.delete_outdated_embedding_cache(&old_cache_prefix, &new)?;
                             updated_configs.insert(name, (new, user_provided));
                         }
-                        SettingsDiff::UpdateWithoutReindex { updated_settings } => {
+                        SettingsDiff::UpdateWithoutReindex { updated_settings, convert } => {
                             tracing::debug!(
                                 embedder = name,
                                 user_provided = user_provided.len(),
@@ -1030,6 +1085,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                             );
                             let new =
                                 validate_embedding_settings(Setting::Set(updated_settings), &name)?;
+                            self.delete_outdated_embedding_cache(&old_cache_prefix, &new)?;
+                            if convert {
+                                embedder_actions.insert(
+                                    name.clone(),
+                                    EmbedderAction { old_options, ..Default::default() }
+                                        .with_is_being_converted(true),
+                                );
+                            }
                             updated_configs.insert(name, (new, user_provided));
                         }
                     }
@@ -1048,8 +1111,23 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                         &mut setting,
                     );
                     let setting = validate_embedding_settings(setting, &name)?;
-                    embedder_actions
-                        .insert(name.clone(), EmbedderAction::Reindex(ReindexAction::FullReindex));
+                    // the embeddings of a new embedder are written with the default distance,
+                    // then converted to the configured distance and quantization
+                    let convert = match &setting {
+                        Setting::Set(EmbeddingSettings { binary_quantized, distance, .. }) => {
+                            matches!(binary_quantized, Setting::Set(true))
+                                || distance.set().unwrap_or_default() != Distance::default()
+                        }
+                        _ => false,
+                    };
+                    embedder_actions.insert(
+                        name.clone(),
+                        EmbedderAction::with_reindex(
+                            ReindexAction::FullReindex,
+                            ArroyOptions::default(),
+                        )
+                        .with_is_being_converted(convert),
+                    );
                     updated_configs.insert(name, (setting, RoaringBitmap::new()));
                 }
             }
@@ -1063,19 +1141,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         let mut find_free_index =
             move || free_indices.find(|(_, free)| **free).map(|(index, _)| index as u8);
         for (name, action) in embedder_actions.iter() {
-            match action {
-                EmbedderAction::Reindex(ReindexAction::RegeneratePrompts) => {
-                    /* cannot be a new embedder, so has to have an id already */
-                }
-                EmbedderAction::Reindex(ReindexAction::FullReindex) => {
-                    if self.index.embedder_category_id.get(self.wtxn, name)?.is_none() {
-                        let id = find_free_index()
-                            .ok_or(UserError::TooManyEmbedders(updated_configs.len()))?;
-                        tracing::debug!(embedder = name, id, "assigning free id to new embedder");
-                        self.index.embedder_category_id.put(self.wtxn, name, &id)?;
-                    }
-                }
-                EmbedderAction::WriteBackToDocuments(_) => { /* already removed */ }
+            // new embedders are always fully reindexed, the other ones already have an id
+            if matches!(action.reindex(), Some(ReindexAction::FullReindex))
+                && self.index.embedder_category_id.get(self.wtxn, name)?.is_none()
+            {
+                let id =
+                    find_free_index().ok_or(UserError::TooManyEmbedders(updated_configs.len()))?;
+                tracing::debug!(embedder = name, id, "assigning free id to new embedder");
+                self.index.embedder_category_id.put(self.wtxn, name, &id)?;
             }
         }
         let updated_configs: Vec<IndexEmbeddingConfig> = updated_configs
@@ -1118,7 +1191,69 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
-    pub fn execute<FP, FA>(mut self, progress_callback: FP, should_abort: FA) -> Result<()>
+    fn update_query_rules(&mut self) -> Result<bool> {
+        let changed = match &self.query_rules {
+            Setting::Set(new) => {
+                validate_query_rules(new)?;
+                let old = self.index.query_rules(self.wtxn)?;
+                if &old == new {
+                    false
+                } else if new.is_empty() {
+                    self.index.delete_query_rules(self.wtxn)?
+                } else {
+                    self.index.put_query_rules(self.wtxn, new.clone())?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_query_rules(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        // the boost filters must stay valid when the filterable attributes change
+        if changed || !matches!(self.filterable_fields, Setting::NotSet) {
+            let filterable_fields = self.index.filterable_fields(self.wtxn)?;
+            validate_boost_filters(&self.index.query_rules(self.wtxn)?, &filterable_fields)?;
+        }
+
+        Ok(changed)
+    }
+
+    fn update_rerankers(&mut self) -> Result<bool> {
+        let changed = match &self.rerankers {
+            Setting::Set(new) => {
+                validate_rerankers(new)?;
+                let old = self.index.rerankers(self.wtxn)?;
+                if &old == new {
+                    false
+                } else if new.is_empty() {
+                    self.index.delete_rerankers(self.wtxn)?
+                } else {
+                    self.index.put_rerankers(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_rerankers(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    pub fn execute<FP, FA>(self, progress_callback: FP, should_abort: FA) -> Result<()>
+    where
+        FP: Fn(UpdateIndexingStep) + Sync,
+        FA: Fn() -> bool + Sync,
+    {
+        self.execute_with_embedding_failures(progress_callback, should_abort).map(drop)
+    }
+
+    /// Applies the settings like [`Self::execute`], and returns the documents whose vectors were
+    /// skipped because their embeddings could not be generated while reindexing.
+    pub fn execute_with_embedding_failures<FP, FA>(
+        mut self,
+        progress_callback: FP,
+        should_abort: FA,
+    ) -> Result<Vec<EmbeddingFailure>>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -1139,9 +1274,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_sort_facet_values_by()?;
         self.update_pagination_max_total_hits()?;
         self.update_search_cutoff()?;
+        self.update_rerankers()?;
 
         // could trigger re-indexing
         self.update_filterable()?;
+        // checks the boost filters against the new filterable attributes
+        self.update_query_rules()?;
         self.update_sortable()?;
         self.update_stop_words()?;
         self.update_non_separator_tokens()?;
@@ -1171,10 +1309,10 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         );
 
         if inner_settings_diff.any_reindexing_needed() {
-            self.reindex(&progress_callback, &should_abort, inner_settings_diff)?;
+            self.reindex(&progress_callback, &should_abort, inner_settings_diff)
+        } else {
+            Ok(Vec::new())
         }
-
-        Ok(())
     }
 }
 
@@ -1451,22 +1589,28 @@ impl InnerIndexSettings {
 fn embedders(embedding_configs: Vec<IndexEmbeddingConfig>) -> Result<EmbeddingConfigs> {
     let res: Result<_> = embedding_configs
         .into_iter()
-        .map(
-            |IndexEmbeddingConfig {
-                 name,
-                 config: EmbeddingConfig { embedder_options, prompt },
-                 ..
-             }| {
-                let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
-
-                let embedder = Arc::new(
-                    Embedder::new(embedder_options.clone())
-                        .map_err(crate::vector::Error::from)
-                        .map_err(crate::Error::from)?,
-                );
-                Ok((name, (embedder, prompt)))
-            },
-        )
+        .map(|IndexEmbeddingConfig { name, config, .. }| {
+            let options = config.arroy_options();
+            let EmbeddingConfig { embedder_options, prompt, search_embedder, .. } = config;
+            let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
+
+            let new_embedder = |options: EmbedderOptions| {
+                Embedder::new(options).map(Arc::new).map_err(crate::vector::Error::from)
+            };
+            let mut embedder = new_embedder(embedder_options.clone())?;
+            if let Some(search_options) = search_embedder {
+                let search_embedder = if search_options.has_distinct_embedder() {
+                    new_embedder(embedder_options.with_search_options(&search_options))?
+                } else {
+                    embedder.clone()
+                };
+                embedder = Arc::new(Embedder::Asymmetric(
+                    asymmetric::Embedder::new(embedder, search_embedder, search_options.prefix)
+                        .map_err(crate::vector::Error::from)?,
+                ));
+            }
+            Ok((name, (embedder, prompt, options)))
+        })
         .collect();
     res.map(EmbeddingConfigs::new)
 }
@@ -1480,6 +1624,7 @@ fn validate_prompt(
             source,
             model,
             revision,
+            model_path,
             api_key,
             dimensions,
             document_template: Setting::Set(template),
@@ -1489,7 +1634,16 @@ fn validate_prompt(
             path_to_embeddings,
             embedding_object,
             input_type,
+            headers,
+            max_batch_size,
+            max_concurrent_requests,
             distribution,
+            binary_quantized,
+            distance,
+            chunking,
+            search_embedder,
+            on_error,
+            sparse,
         }) => {
             // validate
             let template = crate::prompt::Prompt::new(template)
@@ -1500,6 +1654,7 @@ fn validate_prompt(
                 source,
                 model,
                 revision,
+                model_path,
                 api_key,
                 dimensions,
                 document_template: Setting::Set(template),
@@ -1509,7 +1664,16 @@ fn validate_prompt(
                 path_to_embeddings,
                 embedding_object,
                 input_type,
+                headers,
+                max_batch_size,
+                max_concurrent_requests,
                 distribution,
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse,
             }))
         }
         new => Ok(new),
@@ -1526,6 +1690,7 @@ pub fn validate_embedding_settings(
         source,
         model,
         revision,
+        model_path,
         api_key,
         dimensions,
         document_template,
@@ -1535,7 +1700,16 @@ pub fn validate_embedding_settings(
         path_to_embeddings,
         embedding_object,
         input_type,
+        headers,
+        max_batch_size,
+        max_concurrent_requests,
         distribution,
+        binary_quantized,
+        distance,
+        chunking,
+        search_embedder,
+        on_error,
+        sparse,
     } = settings;
 
     if let Some(0) = dimensions.set() {
@@ -1545,6 +1719,40 @@ pub fn validate_embedding_settings(
         .into());
     }
 
+    if let (Some(true), Some(distance)) = (binary_quantized.set(), distance.set()) {
+        if !distance.supports_binary_quantization() {
+            return Err(crate::error::UserError::InvalidBinaryQuantizedDistance {
+                embedder_name: name.to_owned(),
+                distance,
+            }
+            .into());
+        }
+    }
+
+    for (limit, field) in [
+        (&max_batch_size, EmbeddingSettings::MAX_BATCH_SIZE),
+        (&max_concurrent_requests, EmbeddingSettings::MAX_CONCURRENT_REQUESTS),
+    ] {
+        if let Setting::Set(0) = limit {
+            return Err(crate::error::UserError::InvalidRestRequestLimit {
+                embedder_name: name.to_owned(),
+                field,
+            }
+            .into());
+        }
+    }
+
+    if let Some(options) = chunking.as_ref().set() {
+        if !options.is_valid() {
+            return Err(crate::error::UserError::InvalidChunking {
+                embedder_name: name.to_owned(),
+                size: options.size,
+                overlap: options.overlap,
+            }
+            .into());
+        }
+    }
+
     if let Some(url) = url.as_ref().set() {
         url::Url::parse(url).map_err(|error| crate::error::UserError::InvalidUrl {
             embedder_name: name.to_owned(),
@@ -1558,6 +1766,7 @@ pub fn validate_embedding_settings(
             source,
             model,
             revision,
+            model_path,
             api_key,
             dimensions,
             document_template,
@@ -1567,12 +1776,32 @@ pub fn validate_embedding_settings(
             path_to_embeddings,
             embedding_object,
             input_type,
+            headers,
+            max_batch_size,
+            max_concurrent_requests,
             distribution,
+            binary_quantized,
+            distance,
+            chunking,
+            search_embedder,
+            on_error,
+            sparse,
         }));
     };
+    let search_options = search_embedder.as_ref().set();
+    let search_model: Setting<&String> = search_options
+        .and_then(|search| search.model.as_ref())
+        .map(Setting::Set)
+        .unwrap_or_default();
+    let search_query: Setting<&serde_json::Value> = search_options
+        .and_then(|search| search.query.as_ref())
+        .map(Setting::Set)
+        .unwrap_or_default();
+
     match inferred_source {
         EmbedderSource::OpenAi => {
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&model_path, EmbeddingSettings::MODEL_PATH, inferred_source, name)?;
 
             check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
             check_unset(&input_field, EmbeddingSettings::INPUT_FIELD, inferred_source, name)?;
@@ -1589,6 +1818,31 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&headers, EmbeddingSettings::HEADERS, inferred_source, name)?;
+            check_unset(&max_batch_size, EmbeddingSettings::MAX_BATCH_SIZE, inferred_source, name)?;
+            check_unset(
+                &max_concurrent_requests,
+                EmbeddingSettings::MAX_CONCURRENT_REQUESTS,
+                inferred_source,
+                name,
+            )?;
+            check_unset(
+                &search_query,
+                EmbeddingSettings::SEARCH_EMBEDDER_QUERY,
+                inferred_source,
+                name,
+            )?;
+            check_unset(&sparse, EmbeddingSettings::SPARSE, inferred_source, name)?;
+
+            if let Setting::Set(model) = search_model {
+                if crate::vector::openai::EmbeddingModel::from_name(model).is_none() {
+                    return Err(crate::error::UserError::InvalidOpenAiModel {
+                        embedder_name: name.to_owned(),
+                        model: model.clone(),
+                    }
+                    .into());
+                }
+            }
 
             if let Setting::Set(model) = &model {
                 let model = crate::vector::openai::EmbeddingModel::from_name(model.as_str())
@@ -1625,6 +1879,7 @@ pub fn validate_embedding_settings(
             check_unset(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
             check_set(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&model_path, EmbeddingSettings::MODEL_PATH, inferred_source, name)?;
 
             check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
             check_unset(&input_field, EmbeddingSettings::INPUT_FIELD, inferred_source, name)?;
@@ -1641,6 +1896,21 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&headers, EmbeddingSettings::HEADERS, inferred_source, name)?;
+            check_unset(&max_batch_size, EmbeddingSettings::MAX_BATCH_SIZE, inferred_source, name)?;
+            check_unset(
+                &max_concurrent_requests,
+                EmbeddingSettings::MAX_CONCURRENT_REQUESTS,
+                inferred_source,
+                name,
+            )?;
+            check_unset(
+                &search_query,
+                EmbeddingSettings::SEARCH_EMBEDDER_QUERY,
+                inferred_source,
+                name,
+            )?;
+            check_unset(&sparse, EmbeddingSettings::SPARSE, inferred_source, name)?;
         }
         EmbedderSource::HuggingFace => {
             check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
@@ -1662,10 +1932,26 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&headers, EmbeddingSettings::HEADERS, inferred_source, name)?;
+            check_unset(&max_batch_size, EmbeddingSettings::MAX_BATCH_SIZE, inferred_source, name)?;
+            check_unset(
+                &max_concurrent_requests,
+                EmbeddingSettings::MAX_CONCURRENT_REQUESTS,
+                inferred_source,
+                name,
+            )?;
+            check_unset(
+                &search_query,
+                EmbeddingSettings::SEARCH_EMBEDDER_QUERY,
+                inferred_source,
+                name,
+            )?;
+            check_unset(&sparse, EmbeddingSettings::SPARSE, inferred_source, name)?;
         }
         EmbedderSource::UserProvided => {
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&model_path, EmbeddingSettings::MODEL_PATH, inferred_source, name)?;
             check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
             check_unset(
                 &document_template,
@@ -1673,7 +1959,31 @@ pub fn validate_embedding_settings(
                 inferred_source,
                 name,
             )?;
-            check_set(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
+            if matches!(sparse, Setting::Set(true)) {
+                // sparse vectors are not stored in arroy
+                for (is_set, field) in [
+                    (dimensions.set().is_some(), EmbeddingSettings::DIMENSIONS),
+                    (binary_quantized.set().is_some(), EmbeddingSettings::BINARY_QUANTIZED),
+                    (distance.set().is_some(), EmbeddingSettings::DISTANCE),
+                ] {
+                    if is_set {
+                        return Err(crate::error::UserError::InvalidSparseEmbedderField {
+                            embedder_name: name.to_owned(),
+                            field,
+                        }
+                        .into());
+                    }
+                }
+            } else {
+                check_set(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
+            }
+            check_unset(&chunking, EmbeddingSettings::CHUNKING, inferred_source, name)?;
+            check_unset(
+                &search_embedder,
+                EmbeddingSettings::SEARCH_EMBEDDER,
+                inferred_source,
+                name,
+            )?;
 
             check_unset(&url, EmbeddingSettings::URL, inferred_source, name)?;
             check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
@@ -1691,17 +2001,34 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&headers, EmbeddingSettings::HEADERS, inferred_source, name)?;
+            check_unset(&max_batch_size, EmbeddingSettings::MAX_BATCH_SIZE, inferred_source, name)?;
+            check_unset(
+                &max_concurrent_requests,
+                EmbeddingSettings::MAX_CONCURRENT_REQUESTS,
+                inferred_source,
+                name,
+            )?;
         }
         EmbedderSource::Rest => {
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&model_path, EmbeddingSettings::MODEL_PATH, inferred_source, name)?;
             check_set(&url, EmbeddingSettings::URL, inferred_source, name)?;
+            check_unset(
+                &search_model,
+                EmbeddingSettings::SEARCH_EMBEDDER_MODEL,
+                inferred_source,
+                name,
+            )?;
+            check_unset(&sparse, EmbeddingSettings::SPARSE, inferred_source, name)?;
         }
     }
     Ok(Setting::Set(EmbeddingSettings {
         source,
         model,
         revision,
+        model_path,
         api_key,
         dimensions,
         document_template,
@@ -1711,7 +2038,16 @@ pub fn validate_embedding_settings(
         path_to_embeddings,
         embedding_object,
         input_type,
+        headers,
+        max_batch_size,
+        max_concurrent_requests,
         distribution,
+        binary_quantized,
+        distance,
+        chunking,
+        search_embedder,
+        on_error,
+        sparse,
     }))
 }
 
@@ -2629,6 +2965,8 @@ mod tests {
                     proximity_precision,
                     embedder_settings,
                     search_cutoff,
+                    query_rules,
+                    rerankers,
                 } = settings;
                 assert!(matches!(searchable_fields, Setting::NotSet));
                 assert!(matches!(displayed_fields, Setting::NotSet));
@@ -2653,6 +2991,8 @@ mod tests {
                 assert!(matches!(proximity_precision, Setting::NotSet));
                 assert!(matches!(embedder_settings, Setting::NotSet));
                 assert!(matches!(search_cutoff, Setting::NotSet));
+                assert!(matches!(query_rules, Setting::NotSet));
+                assert!(matches!(rerankers, Setting::NotSet));
             })
             .unwrap();
     }
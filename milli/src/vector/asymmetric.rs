@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+use super::error::{EmbedError, NewEmbedderError};
+use super::Embedding;
+
+/// Search-time configuration of an embedder, for models embedding the queries differently from the documents.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchEmbedderOptions {
+    /// Model embedding the queries, producing embeddings of the same dimensions as the model embedding the documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub model: Option<String>,
+    /// Request sent to a REST embedder to embed the queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub query: Option<serde_json::Value>,
+    /// Text prepended to the queries before embedding them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub prefix: Option<String>,
+}
+
+impl SearchEmbedderOptions {
+    /// Whether the queries are embedded by another embedder than the documents.
+    pub fn has_distinct_embedder(&self) -> bool {
+        self.model.is_some() || self.query.is_some()
+    }
+}
+
+/// An embedder embedding the documents with one embedder, and the search queries with another embedder or a prefix.
+#[derive(Debug)]
+pub struct Embedder {
+    document: Arc<super::Embedder>,
+    search: Arc<super::Embedder>,
+    prefix: Option<String>,
+}
+
+impl Embedder {
+    pub fn new(
+        document: Arc<super::Embedder>,
+        search: Arc<super::Embedder>,
+        prefix: Option<String>,
+    ) -> Result<Self, NewEmbedderError> {
+        if document.dimensions() != search.dimensions() {
+            return Err(NewEmbedderError::search_embedder_dimensions(
+                document.dimensions(),
+                search.dimensions(),
+            ));
+        }
+        Ok(Self { document, search, prefix })
+    }
+
+    /// The embedder of the documents.
+    pub fn document_embedder(&self) -> &super::Embedder {
+        &self.document
+    }
+
+    pub fn embed_search(&self, query: String) -> Result<Embedding, EmbedError> {
+        let query = match &self.prefix {
+            Some(prefix) => format!("{prefix}{query}"),
+            None => query,
+        };
+        self.search.embed_search(query)
+    }
+}
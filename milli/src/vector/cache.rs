@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{EmbedderOptions, EmbeddingConfig};
+
+/// Length of the prefix of the keys of the embedding cache, a SHA-256 digest of the embedder options.
+pub const CACHE_PREFIX_LENGTH: usize = 32;
+
+/// Length of the keys of the embedding cache.
+///
+/// A key is made of the digest of the embedder options followed by the digest of the rendered prompt.
+pub const CACHE_KEY_LENGTH: usize = 2 * CACHE_PREFIX_LENGTH;
+
+pub type CachePrefix = [u8; CACHE_PREFIX_LENGTH];
+pub type CacheKey = [u8; CACHE_KEY_LENGTH];
+
+/// Returns the prefix shared by the cache keys of all the prompts embedded with the given configuration.
+///
+/// The options that don't change the generated embeddings, such as the API key, the headers,
+/// the distribution or the batching of the requests, are not part of the prefix.
+pub fn cache_prefix(config: &EmbeddingConfig) -> CachePrefix {
+    let mut options = config.embedder_options.clone();
+    match &mut options {
+        EmbedderOptions::HuggingFace(options) => options.distribution = None,
+        EmbedderOptions::OpenAi(options) => {
+            options.api_key = None;
+            options.distribution = None;
+        }
+        EmbedderOptions::Ollama(options) => {
+            options.api_key = None;
+            options.distribution = None;
+        }
+        EmbedderOptions::UserProvided(options) => options.distribution = None,
+        EmbedderOptions::Rest(options) => {
+            options.api_key = None;
+            options.distribution = None;
+            options.headers.clear();
+            options.max_batch_size = None;
+            options.max_concurrent_requests = None;
+        }
+    }
+    // a chunked prompt has one embedding per chunk
+    let options = serde_json::to_vec(&(options, &config.chunking))
+        .expect("the embedder options can always be serialized");
+    Sha256::digest(options).into()
+}
+
+/// Returns the key of the embeddings of a rendered prompt in the embedding cache.
+pub fn cache_key(prefix: &CachePrefix, prompt: &str) -> CacheKey {
+    let mut key = [0; CACHE_KEY_LENGTH];
+    key[..CACHE_PREFIX_LENGTH].copy_from_slice(prefix);
+    key[CACHE_PREFIX_LENGTH..].copy_from_slice(&Sha256::digest(prompt));
+    key
+}
+
+/// Statistics of the embedding cache of an index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingCacheStats {
+    /// Number of rendered prompts whose embeddings are cached
+    pub entries: u64,
+    /// Number of times the embeddings of a prompt were found in the cache while indexing
+    pub hits: u64,
+    /// Number of times the embeddings of a prompt had to be generated while indexing
+    pub misses: u64,
+}
+
+impl EmbeddingCacheStats {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cache_key, cache_prefix, CACHE_PREFIX_LENGTH};
+    use crate::vector::chunking::ChunkingOptions;
+    use crate::vector::{openai, rest, EmbedderOptions, EmbeddingConfig};
+
+    fn config(embedder_options: EmbedderOptions) -> EmbeddingConfig {
+        EmbeddingConfig { embedder_options, ..Default::default() }
+    }
+
+    #[test]
+    fn prefix_ignores_api_key() {
+        let with_key = config(EmbedderOptions::OpenAi(
+            openai::EmbedderOptions::with_default_model(Some("secret".into())),
+        ));
+        let without_key =
+            config(EmbedderOptions::OpenAi(openai::EmbedderOptions::with_default_model(None)));
+        assert_eq!(cache_prefix(&with_key), cache_prefix(&without_key));
+    }
+
+    #[test]
+    fn prefix_depends_on_rest_query() {
+        let first = config(EmbedderOptions::Rest(rest::EmbedderOptions {
+            query: serde_json::json!({ "model": "first" }),
+            ..Default::default()
+        }));
+        let second = config(EmbedderOptions::Rest(rest::EmbedderOptions {
+            query: serde_json::json!({ "model": "second" }),
+            ..Default::default()
+        }));
+        assert_ne!(cache_prefix(&first), cache_prefix(&second));
+    }
+
+    #[test]
+    fn prefix_ignores_request_batching() {
+        let default = config(EmbedderOptions::Rest(rest::EmbedderOptions::default()));
+        let batched = config(EmbedderOptions::Rest(rest::EmbedderOptions {
+            max_batch_size: Some(32),
+            max_concurrent_requests: Some(2),
+            headers: [("X-Api-Key".to_string(), "secret".to_string())].into(),
+            ..Default::default()
+        }));
+        assert_eq!(cache_prefix(&default), cache_prefix(&batched));
+    }
+
+    #[test]
+    fn prefix_depends_on_chunking() {
+        let options = EmbedderOptions::OpenAi(openai::EmbedderOptions::with_default_model(None));
+        let whole = config(options.clone());
+        let chunked = EmbeddingConfig {
+            chunking: Some(ChunkingOptions { size: 128, overlap: 0, source_field: None }),
+            ..config(options)
+        };
+        assert_ne!(cache_prefix(&whole), cache_prefix(&chunked));
+    }
+
+    #[test]
+    fn key_starts_with_prefix() {
+        let prefix = [42; CACHE_PREFIX_LENGTH];
+        let key = cache_key(&prefix, "a prompt");
+        assert_eq!(key[..CACHE_PREFIX_LENGTH], prefix);
+        assert_ne!(key, cache_key(&prefix, "another prompt"));
+    }
+}
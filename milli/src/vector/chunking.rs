@@ -0,0 +1,89 @@
+use std::sync::OnceLock;
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of chunks of a document, as a document can have at most this many embeddings per embedder.
+///
+/// The text following the last chunk is not embedded.
+pub const MAX_CHUNKS: usize = u8::MAX as usize;
+
+/// How the text of a document is split into several chunks, each chunk getting its own embedding.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct ChunkingOptions {
+    /// Maximum number of tokens in a chunk
+    pub size: usize,
+    /// Number of tokens shared by two consecutive chunks
+    #[serde(default)]
+    #[deserr(default)]
+    pub overlap: usize,
+    /// Top-level field of the documents whose value is chunked instead of the rendered document template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub source_field: Option<String>,
+}
+
+impl ChunkingOptions {
+    /// Whether there is at least one token in each chunk that is not shared with the previous chunk.
+    pub fn is_valid(&self) -> bool {
+        self.size > 0 && self.overlap < self.size
+    }
+
+    /// Splits a text in chunks of at most `size` tokens, two consecutive chunks sharing `overlap` tokens.
+    ///
+    /// The tokens are counted with the `cl100k_base` tokenizer, regardless of the model of the embedder.
+    pub fn split(&self, text: &str) -> Vec<String> {
+        let tokenizer = tokenizer();
+        let tokens = tokenizer.encode_ordinary(text);
+        if tokens.len() <= self.size {
+            return vec![text.to_owned()];
+        }
+
+        let step = self.size.saturating_sub(self.overlap).max(1);
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + self.size).min(tokens.len());
+            // a chunk can end in the middle of a multi-byte character
+            let bytes = tokenizer._decode_native(&tokens[start..end]);
+            chunks.push(String::from_utf8_lossy(&bytes).into_owned());
+            if end == tokens.len() || chunks.len() == MAX_CHUNKS {
+                break;
+            }
+            start += step;
+        }
+        chunks
+    }
+}
+
+/// Number of tokens of a text, as counted when splitting it in chunks.
+pub fn count_tokens(text: &str) -> usize {
+    tokenizer().encode_ordinary(text).len()
+}
+
+fn tokenizer() -> &'static tiktoken_rs::CoreBPE {
+    static TOKENIZER: OnceLock<tiktoken_rs::CoreBPE> = OnceLock::new();
+    // looking at the code it is very unclear that this can actually fail.
+    TOKENIZER.get_or_init(|| tiktoken_rs::cl100k_base().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::ChunkingOptions;
+
+    #[test]
+    fn short_text_is_not_split() {
+        let options = ChunkingOptions { size: 16, overlap: 4, source_field: None };
+        assert_eq!(options.split("a short text"), vec!["a short text".to_owned()]);
+    }
+
+    #[test]
+    fn chunks_overlap() {
+        let options = ChunkingOptions { size: 4, overlap: 2, source_field: None };
+        let text = "one two three four five six seven eight";
+        let chunks = options.split(text);
+        assert_eq!(chunks, ["one two three four", " three four five six", " five six seven eight"]);
+    }
+}
@@ -2,11 +2,24 @@ use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use hf_hub::api::sync::ApiError;
+use serde::{Deserialize, Serialize};
 
 use super::parsed_vectors::ParsedVectorsDiff;
 use crate::error::FaultSource;
 use crate::{FieldDistribution, PanicCatched};
 
+/// Maximum number of embedding failures reported by an indexing operation, the other ones are only logged.
+pub const MAX_REPORTED_EMBEDDING_FAILURES: usize = 100;
+
+/// A document whose vectors were skipped because its embeddings could not be generated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingFailure {
+    pub embedder_name: String,
+    pub document_id: String,
+    pub reason: String,
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("Error while generating embeddings: {inner}")]
 pub struct Error {
@@ -272,6 +285,17 @@ impl NewEmbedderError {
         Self { kind: NewEmbedderErrorKind::ApiGet(inner), fault: FaultSource::Undecided }
     }
 
+    pub fn missing_weights(model_path: PathBuf) -> Self {
+        Self { kind: NewEmbedderErrorKind::MissingWeights(model_path), fault: FaultSource::User }
+    }
+
+    pub fn search_embedder_dimensions(expected: usize, found: usize) -> Self {
+        Self {
+            kind: NewEmbedderErrorKind::SearchEmbedderDimensions { expected, found },
+            fault: FaultSource::User,
+        }
+    }
+
     pub fn pytorch_weight(inner: candle_core::Error) -> Self {
         Self { kind: NewEmbedderErrorKind::PytorchWeight(inner), fault: FaultSource::Runtime }
     }
@@ -339,6 +363,8 @@ pub enum NewEmbedderErrorKind {
     UnsupportedModel(UnsupportedModel),
     #[error(transparent)]
     OpenTokenizer(OpenTokenizer),
+    #[error("could not find `model.safetensors` nor `pytorch_model.bin` weights in `{}`", .0.display())]
+    MissingWeights(PathBuf),
     #[error("could not build weights from Pytorch weights: {0}")]
     PytorchWeight(candle_core::Error),
     #[error("could not build weights from Safetensor weights: {0}")]
@@ -351,6 +377,8 @@ pub enum NewEmbedderErrorKind {
     CouldNotDetermineDimension(EmbedError),
     #[error("loading model failed: {0}")]
     LoadModel(candle_core::Error),
+    #[error("the search embedder produces embeddings of {found} dimensions, while the documents are embedded with {expected} dimensions")]
+    SearchEmbedderDimensions { expected: usize, found: usize },
 }
 
 pub struct PossibleEmbeddingMistakes {
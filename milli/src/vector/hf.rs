@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use candle_core::Tensor;
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, DTYPE};
@@ -33,6 +35,9 @@ enum WeightSource {
 pub struct EmbedderOptions {
     pub model: String,
     pub revision: Option<String>,
+    /// Local directory containing the model files, loaded instead of fetching the model from the hub
+    #[serde(default)]
+    pub model_path: Option<String>,
     pub distribution: Option<DistributionShift>,
 }
 
@@ -41,6 +46,7 @@ impl EmbedderOptions {
         Self {
             model: "BAAI/bge-base-en-v1.5".to_string(),
             revision: Some("617ca489d9e86b49b8167676d8220688b99db36e".into()),
+            model_path: None,
             distribution: None,
         }
     }
@@ -52,6 +58,49 @@ impl Default for EmbedderOptions {
     }
 }
 
+type ModelFiles = (PathBuf, PathBuf, PathBuf, WeightSource);
+
+/// Fetches the files of the model from the Hugging Face hub, or from its local cache.
+fn hub_model_files(options: &EmbedderOptions) -> Result<ModelFiles, NewEmbedderError> {
+    let repo = match options.revision.clone() {
+        Some(revision) => Repo::with_revision(options.model.clone(), RepoType::Model, revision),
+        None => Repo::model(options.model.clone()),
+    };
+    let api = Api::new().map_err(NewEmbedderError::new_api_fail)?;
+    let api = api.repo(repo);
+    let config = api.get("config.json").map_err(NewEmbedderError::api_get)?;
+    let tokenizer = api.get("tokenizer.json").map_err(NewEmbedderError::api_get)?;
+    let (weights, source) = {
+        api.get("model.safetensors")
+            .map(|filename| (filename, WeightSource::Safetensors))
+            .or_else(|_| {
+                api.get("pytorch_model.bin").map(|filename| (filename, WeightSource::Pytorch))
+            })
+            .map_err(NewEmbedderError::api_get)?
+    };
+    Ok((config, tokenizer, weights, source))
+}
+
+/// Locates the files of a model stored in a local directory, without any network access.
+///
+/// Missing config and tokenizer files are reported when opening them.
+fn local_model_files(model_path: &Path) -> Result<ModelFiles, NewEmbedderError> {
+    let config = model_path.join("config.json");
+    let tokenizer = model_path.join("tokenizer.json");
+    let (weights, source) = {
+        let safetensors = model_path.join("model.safetensors");
+        let pytorch = model_path.join("pytorch_model.bin");
+        if safetensors.is_file() {
+            (safetensors, WeightSource::Safetensors)
+        } else if pytorch.is_file() {
+            (pytorch, WeightSource::Pytorch)
+        } else {
+            return Err(NewEmbedderError::missing_weights(model_path.to_owned()));
+        }
+    };
+    Ok((config, tokenizer, weights, source))
+}
+
 /// Perform embedding of documents and queries
 pub struct Embedder {
     model: BertModel,
@@ -79,26 +128,11 @@ impl Embedder {
                 candle_core::Device::Cpu
             }
         };
-        let repo = match options.revision.clone() {
-            Some(revision) => Repo::with_revision(options.model.clone(), RepoType::Model, revision),
-            None => Repo::model(options.model.clone()),
-        };
-        let (config_filename, tokenizer_filename, weights_filename, weight_source) = {
-            let api = Api::new().map_err(NewEmbedderError::new_api_fail)?;
-            let api = api.repo(repo);
-            let config = api.get("config.json").map_err(NewEmbedderError::api_get)?;
-            let tokenizer = api.get("tokenizer.json").map_err(NewEmbedderError::api_get)?;
-            let (weights, source) = {
-                api.get("model.safetensors")
-                    .map(|filename| (filename, WeightSource::Safetensors))
-                    .or_else(|_| {
-                        api.get("pytorch_model.bin")
-                            .map(|filename| (filename, WeightSource::Pytorch))
-                    })
-                    .map_err(NewEmbedderError::api_get)?
+        let (config_filename, tokenizer_filename, weights_filename, weight_source) =
+            match &options.model_path {
+                Some(model_path) => local_model_files(Path::new(model_path))?,
+                None => hub_model_files(&options)?,
             };
-            (config, tokenizer, weights, source)
-        };
 
         let config = std::fs::read_to_string(&config_filename)
             .map_err(|inner| NewEmbedderError::open_config(config_filename.clone(), inner))?;
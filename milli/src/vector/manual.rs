@@ -5,17 +5,25 @@ use super::{DistributionShift, Embeddings};
 pub struct Embedder {
     dimensions: usize,
     distribution: Option<DistributionShift>,
+    sparse: bool,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct EmbedderOptions {
     pub dimensions: usize,
     pub distribution: Option<DistributionShift>,
+    /// Whether the documents and queries provide sparse vectors instead of dense embeddings
+    #[serde(default)]
+    pub sparse: bool,
 }
 
 impl Embedder {
     pub fn new(options: EmbedderOptions) -> Self {
-        Self { dimensions: options.dimensions, distribution: options.distribution }
+        Self {
+            dimensions: options.dimensions,
+            distribution: options.distribution,
+            sparse: options.sparse,
+        }
     }
 
     pub fn embed(&self, mut texts: Vec<String>) -> Result<Vec<Embeddings<f32>>, EmbedError> {
@@ -37,4 +45,8 @@ impl Embedder {
     pub fn distribution(&self) -> Option<DistributionShift> {
         self.distribution
     }
+
+    pub fn is_sparse(&self) -> bool {
+        self.sparse
+    }
 }
@@ -1,20 +1,29 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use arroy::Unspecified;
 use deserr::{DeserializeError, Deserr};
+use heed::{RoTxn, RwTxn};
 use ordered_float::OrderedFloat;
+use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
 
+use self::asymmetric::SearchEmbedderOptions;
+use self::chunking::ChunkingOptions;
 use self::error::{EmbedError, NewEmbedderError};
 use crate::prompt::{Prompt, PromptData};
 use crate::ThreadPoolNoAbort;
 
+pub mod asymmetric;
+pub mod cache;
+pub mod chunking;
 pub mod error;
 pub mod hf;
 pub mod manual;
 pub mod openai;
 pub mod parsed_vectors;
 pub mod settings;
+pub mod sparse;
 
 pub mod ollama;
 pub mod rest;
@@ -25,6 +34,412 @@ pub type Embedding = Vec<f32>;
 
 pub const REQUEST_PARALLELISM: usize = 40;
 
+/// The distance used to compare the embeddings of an embedder.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum Distance {
+    /// The cosine of the angle between the embeddings
+    #[default]
+    Cosine,
+    Euclidean,
+    Manhattan,
+    DotProduct,
+}
+
+impl Distance {
+    /// Converts a distance returned by arroy to a similarity between `0` and `1`.
+    pub fn similarity(&self, distance: f32) -> f32 {
+        match self {
+            Distance::Cosine => 1.0 - distance,
+            Distance::Euclidean | Distance::Manhattan => 1.0 / (1.0 + distance),
+            // arroy returns the opposite of the dot product as the distance
+            Distance::DotProduct => 1.0 / (1.0 + distance.exp()),
+        }
+    }
+
+    /// Whether the embeddings can be binary quantized with this distance.
+    pub fn supports_binary_quantization(&self) -> bool {
+        !matches!(self, Distance::DotProduct)
+    }
+}
+
+impl std::fmt::Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Distance::Cosine => "cosine",
+            Distance::Euclidean => "euclidean",
+            Distance::Manhattan => "manhattan",
+            Distance::DotProduct => "dotProduct",
+        };
+        f.write_str(s)
+    }
+}
+
+/// What happens to a document whose embeddings cannot be generated while indexing.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum OnEmbeddingError {
+    /// The whole indexing operation fails
+    #[default]
+    Fail,
+    /// The document is indexed without vectors for this embedder, and the failure is reported
+    ///
+    /// The vectors are not generated again as long as the rendered prompt of the document doesn't
+    /// change: sending the same document again is a no-op for this embedder. To retry, delete the
+    /// document and add it again, or update the `documentTemplate` of the embedder.
+    SkipVectors,
+}
+
+/// How the embeddings of an embedder are stored in arroy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArroyOptions {
+    pub distance: Distance,
+    /// Whether the embeddings are binary quantized
+    pub quantized: bool,
+}
+
+/// Evaluates `$body` with `$d` being the arroy distance type matching the given [`ArroyOptions`].
+macro_rules! with_arroy_distance {
+    ($options:expr, $d:ident => $body:expr) => {{
+        use arroy::distances::*;
+        match $options {
+            ArroyOptions { distance: Distance::Cosine, quantized: false } => {
+                type $d = Angular;
+                $body
+            }
+            ArroyOptions { distance: Distance::Cosine, quantized: true } => {
+                type $d = BinaryQuantizedAngular;
+                $body
+            }
+            ArroyOptions { distance: Distance::Euclidean, quantized: false } => {
+                type $d = Euclidean;
+                $body
+            }
+            ArroyOptions { distance: Distance::Euclidean, quantized: true } => {
+                type $d = BinaryQuantizedEuclidean;
+                $body
+            }
+            ArroyOptions { distance: Distance::Manhattan, quantized: false } => {
+                type $d = Manhattan;
+                $body
+            }
+            ArroyOptions { distance: Distance::Manhattan, quantized: true } => {
+                type $d = BinaryQuantizedManhattan;
+                $body
+            }
+            // rejected by the settings validation
+            ArroyOptions { distance: Distance::DotProduct, quantized: _ } => {
+                type $d = DotProduct;
+                $body
+            }
+        }
+    }};
+}
+
+/// Gives access to the embeddings of an embedder in the arroy store.
+///
+/// The n-th embedding of each document is stored in the n-th arroy index of the embedder,
+/// using the distance of the embedder, optionally binary quantized.
+#[derive(Debug, Clone, Copy)]
+pub struct ArroyWrapper {
+    options: ArroyOptions,
+    embedder_index: u8,
+    database: arroy::Database<Unspecified>,
+}
+
+impl ArroyWrapper {
+    pub fn new(
+        database: arroy::Database<Unspecified>,
+        embedder_index: u8,
+        options: ArroyOptions,
+    ) -> Self {
+        Self { database, embedder_index, options }
+    }
+
+    pub fn embedder_index(&self) -> u8 {
+        self.embedder_index
+    }
+
+    pub fn options(&self) -> ArroyOptions {
+        self.options
+    }
+
+    fn db<D: arroy::Distance>(&self) -> arroy::Database<D> {
+        self.database.remap_data_type()
+    }
+
+    /// Iterates over the readers of the embedder, stopping at the first one that is missing or empty.
+    fn readers<'a, D: arroy::Distance>(
+        &'a self,
+        rtxn: &'a RoTxn<'a>,
+    ) -> impl Iterator<Item = Result<arroy::Reader<'a, D>, arroy::Error>> + 'a {
+        let db = self.db::<D>();
+        arroy_db_range_for_embedder(self.embedder_index).map_while(move |index| {
+            match arroy::Reader::open(rtxn, index, db) {
+                Ok(reader) => match reader.is_empty(rtxn) {
+                    Ok(false) => Some(Ok(reader)),
+                    Ok(true) => None,
+                    Err(e) => Some(Err(e)),
+                },
+                Err(arroy::Error::MissingMetadata(_)) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+
+    /// The dimensions of the embeddings, or `None` if there is no embedding stored yet.
+    pub fn dimensions(&self, rtxn: &RoTxn) -> Result<Option<usize>, arroy::Error> {
+        with_arroy_distance!(self.options, D => {
+            Ok(self.readers::<D>(rtxn).next().transpose()?.map(|reader| reader.dimensions()))
+        })
+    }
+
+    /// Builds the trees of the updated indexes, converting them first if the target options
+    /// use a different distance or quantization.
+    pub fn build_and_convert<R: rand::Rng + rand::SeedableRng>(
+        &mut self,
+        wtxn: &mut RwTxn,
+        rng: &mut R,
+        dimension: usize,
+        target: ArroyOptions,
+    ) -> Result<(), arroy::Error> {
+        let converting = target != self.options;
+        with_arroy_distance!(self.options, D => {
+            for index in arroy_db_range_for_embedder(self.embedder_index) {
+                let writer = arroy::Writer::new(self.db::<D>(), index, dimension);
+                // If we are converting the databases, we can't know from meilisearch
+                // if the db was empty but still contained the wrong metadata, thus we need
+                // to convert everything and can't stop early. Since this operation can
+                // only happens when the settings of an embedder change, it's not very
+                // performance sensitive.
+                if converting {
+                    with_arroy_distance!(target, T => {
+                        let writer = writer.prepare_changing_distance::<T>(wtxn)?;
+                        writer.build(wtxn, rng, None)?
+                    })
+                } else if writer.need_build(wtxn)? {
+                    writer.build(wtxn, rng, None)?
+                } else if writer.is_empty(wtxn)? {
+                    break;
+                }
+            }
+        });
+        self.options = target;
+        Ok(())
+    }
+
+    /// Adds all the embeddings of a document, the n-th embedding going to the n-th index.
+    ///
+    /// The embeddings must all have the same dimension and there can't be more than 256 of them.
+    pub fn add_items(
+        &self,
+        wtxn: &mut RwTxn,
+        item_id: arroy::ItemId,
+        embeddings: &Embeddings<f32>,
+    ) -> Result<(), arroy::Error> {
+        let dimension = embeddings.dimension();
+        with_arroy_distance!(self.options, D => {
+            for (index, vector) in
+                arroy_db_range_for_embedder(self.embedder_index).zip(embeddings.iter())
+            {
+                arroy::Writer::new(self.db::<D>(), index, dimension)
+                    .add_item(wtxn, item_id, vector)?
+            }
+        });
+        Ok(())
+    }
+
+    /// Adds an embedding to a document, in the first index that doesn't contain it yet.
+    pub fn add_item(
+        &self,
+        wtxn: &mut RwTxn,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> Result<(), arroy::Error> {
+        let dimension = vector.len();
+        with_arroy_distance!(self.options, D => {
+            for index in arroy_db_range_for_embedder(self.embedder_index) {
+                let writer = arroy::Writer::new(self.db::<D>(), index, dimension);
+                if !writer.contains_item(wtxn, item_id)? {
+                    writer.add_item(wtxn, item_id, vector)?;
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Deletes all the embeddings of a document.
+    pub fn del_items(
+        &self,
+        wtxn: &mut RwTxn,
+        dimension: usize,
+        item_id: arroy::ItemId,
+    ) -> Result<(), arroy::Error> {
+        with_arroy_distance!(self.options, D => {
+            for index in arroy_db_range_for_embedder(self.embedder_index) {
+                // Uses invariant: vectors are packed in the first writers.
+                let writer = arroy::Writer::new(self.db::<D>(), index, dimension);
+                if !writer.del_item(wtxn, item_id)? {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Deletes one embedding of a document, keeping the remaining embeddings packed in the first indexes.
+    ///
+    /// Returns whether the embedding was found and deleted.
+    pub fn del_item(
+        &self,
+        wtxn: &mut RwTxn,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> Result<bool, arroy::Error> {
+        with_arroy_distance!(self.options, D => self._del_item::<D>(wtxn, item_id, vector))
+    }
+
+    fn _del_item<D: arroy::Distance>(
+        &self,
+        wtxn: &mut RwTxn,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> Result<bool, arroy::Error> {
+        let dimension = vector.len();
+        let writers: Vec<_> = arroy_db_range_for_embedder(self.embedder_index)
+            .map(|index| arroy::Writer::new(self.db::<D>(), index, dimension))
+            .collect();
+
+        let mut deleted_index = None;
+        for (index, writer) in writers.iter().enumerate() {
+            let Some(candidate) = writer.item_vector(wtxn, item_id)? else {
+                // uses invariant: vectors are packed in the first writers.
+                break;
+            };
+            if candidate == vector {
+                writer.del_item(wtxn, item_id)?;
+                deleted_index = Some(index);
+            }
+        }
+
+        // 🥲 enforce invariant: vectors are packed in the first writers.
+        if let Some(deleted_index) = deleted_index {
+            let mut last_index_with_a_vector = None;
+            for (index, writer) in writers.iter().enumerate().skip(deleted_index) {
+                let Some(candidate) = writer.item_vector(wtxn, item_id)? else {
+                    break;
+                };
+                last_index_with_a_vector = Some((index, candidate));
+            }
+            if let Some((last_index, vector)) = last_index_with_a_vector {
+                // unwrap: computed the index from the list of writers
+                writers.get(last_index).unwrap().del_item(wtxn, item_id)?;
+                writers.get(deleted_index).unwrap().add_item(wtxn, item_id, &vector)?;
+            }
+        }
+        Ok(deleted_index.is_some())
+    }
+
+    /// Removes all the embeddings of the embedder.
+    pub fn clear(&self, wtxn: &mut RwTxn, dimension: usize) -> Result<(), arroy::Error> {
+        with_arroy_distance!(self.options, D => {
+            for index in arroy_db_range_for_embedder(self.embedder_index) {
+                let writer = arroy::Writer::new(self.db::<D>(), index, dimension);
+                if writer.is_empty(wtxn)? {
+                    break;
+                }
+                writer.clear(wtxn)?;
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns the documents nearest to the given document, along with their distance and
+    /// the position of their matching embedding in [`Self::item_vectors`],
+    /// sorted by increasing distance.
+    pub fn nns_by_item(
+        &self,
+        rtxn: &RoTxn,
+        item: arroy::ItemId,
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(arroy::ItemId, f32, usize)>, arroy::Error> {
+        let mut results = Vec::new();
+
+        with_arroy_distance!(self.options, D => {
+            // the embeddings of a document are stored in consecutive readers,
+            // in the order `item_vectors` returns them
+            for (embedding_index, reader) in self.readers::<D>(rtxn).enumerate() {
+                let reader = reader?;
+                if let Some(ret) = reader.nns_by_item(rtxn, item, limit, None, None, filter)? {
+                    results.extend(
+                        ret.into_iter().map(|(docid, distance)| (docid, distance, embedding_index)),
+                    );
+                } else {
+                    break;
+                }
+            }
+        });
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
+        Ok(results)
+    }
+
+    /// Returns the documents nearest to the given vector, along with their distance and
+    /// the position of their matching embedding in [`Self::item_vectors`],
+    /// sorted by increasing distance.
+    pub fn nns_by_vector(
+        &self,
+        rtxn: &RoTxn,
+        vector: &[f32],
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(arroy::ItemId, f32, usize)>, arroy::Error> {
+        let mut results = Vec::new();
+
+        with_arroy_distance!(self.options, D => {
+            // the embeddings of a document are stored in consecutive readers,
+            // in the order `item_vectors` returns them
+            for (embedding_index, reader) in self.readers::<D>(rtxn).enumerate() {
+                let reader = reader?;
+                let ret = reader.nns_by_vector(rtxn, vector, limit, None, None, filter)?;
+                results.extend(
+                    ret.into_iter().map(|(docid, distance)| (docid, distance, embedding_index)),
+                );
+            }
+        });
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
+        Ok(results)
+    }
+
+    /// Returns all the embeddings of a document.
+    ///
+    /// The embeddings are returned in the order they are stored, which isn't the order they were
+    /// sent in: they are sorted and deduplicated when indexed, and reordered when some are removed.
+    ///
+    /// The embeddings of a quantized embedder are lossy: only the sign of their dimensions is kept.
+    pub fn item_vectors(
+        &self,
+        rtxn: &RoTxn,
+        item_id: arroy::ItemId,
+    ) -> Result<Vec<Embedding>, arroy::Error> {
+        let mut vectors = Vec::new();
+
+        with_arroy_distance!(self.options, D => {
+            for reader in self.readers::<D>(rtxn) {
+                if let Some(vec) = reader?.item_vector(rtxn, item_id)? {
+                    vectors.push(vec);
+                } else {
+                    break;
+                }
+            }
+        });
+        Ok(vectors)
+    }
+}
+
 /// One or multiple embeddings stored consecutively in a flat vector.
 pub struct Embeddings<F> {
     data: Vec<F>,
@@ -114,6 +529,8 @@ pub enum Embedder {
     Ollama(ollama::Embedder),
     /// An embedder based on making embedding queries against a generic JSON/REST embedding server.
     Rest(rest::Embedder),
+    /// An embedder embedding the search queries differently from the documents.
+    Asymmetric(asymmetric::Embedder),
 }
 
 /// Configuration for an embedder.
@@ -123,36 +540,68 @@ pub struct EmbeddingConfig {
     pub embedder_options: EmbedderOptions,
     /// Document template
     pub prompt: PromptData,
-    // TODO: add metrics and anything needed
+    /// If this embedder is binary quantized
+    #[serde(default)]
+    pub quantized: Option<bool>,
+    /// Distance used to compare the embeddings
+    #[serde(default)]
+    pub distance: Option<Distance>,
+    /// How the text of the documents is split before being embedded
+    #[serde(default)]
+    pub chunking: Option<ChunkingOptions>,
+    /// How the search queries are embedded, when it differs from the documents
+    #[serde(default)]
+    pub search_embedder: Option<SearchEmbedderOptions>,
+    /// What happens to the documents whose embeddings cannot be generated
+    #[serde(default)]
+    pub on_error: Option<OnEmbeddingError>,
+}
+
+impl EmbeddingConfig {
+    pub fn quantized(&self) -> bool {
+        self.quantized.unwrap_or_default()
+    }
+
+    pub fn distance(&self) -> Distance {
+        self.distance.unwrap_or_default()
+    }
+
+    pub fn on_error(&self) -> OnEmbeddingError {
+        self.on_error.unwrap_or_default()
+    }
+
+    pub fn arroy_options(&self) -> ArroyOptions {
+        ArroyOptions { distance: self.distance(), quantized: self.quantized() }
+    }
 }
 
 /// Map of embedder configurations.
 ///
 /// Each configuration is mapped to a name.
 #[derive(Clone, Default)]
-pub struct EmbeddingConfigs(HashMap<String, (Arc<Embedder>, Arc<Prompt>)>);
+pub struct EmbeddingConfigs(HashMap<String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions)>);
 
 impl EmbeddingConfigs {
     /// Create the map from its internal component.s
-    pub fn new(data: HashMap<String, (Arc<Embedder>, Arc<Prompt>)>) -> Self {
+    pub fn new(data: HashMap<String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions)>) -> Self {
         Self(data)
     }
 
-    /// Get an embedder configuration and template from its name.
-    pub fn get(&self, name: &str) -> Option<(Arc<Embedder>, Arc<Prompt>)> {
+    /// Get an embedder configuration, its template and how its embeddings are stored, from its name.
+    pub fn get(&self, name: &str) -> Option<(Arc<Embedder>, Arc<Prompt>, ArroyOptions)> {
         self.0.get(name).cloned()
     }
 
     /// Get the default embedder configuration, if any.
-    pub fn get_default(&self) -> Option<(Arc<Embedder>, Arc<Prompt>)> {
+    pub fn get_default(&self) -> Option<(Arc<Embedder>, Arc<Prompt>, ArroyOptions)> {
         self.get(self.get_default_embedder_name())
     }
 
-    pub fn inner_as_ref(&self) -> &HashMap<String, (Arc<Embedder>, Arc<Prompt>)> {
+    pub fn inner_as_ref(&self) -> &HashMap<String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions)> {
         &self.0
     }
 
-    pub fn into_inner(self) -> HashMap<String, (Arc<Embedder>, Arc<Prompt>)> {
+    pub fn into_inner(self) -> HashMap<String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions)> {
         self.0
     }
 
@@ -176,9 +625,10 @@ impl EmbeddingConfigs {
 }
 
 impl IntoIterator for EmbeddingConfigs {
-    type Item = (String, (Arc<Embedder>, Arc<Prompt>));
+    type Item = (String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions));
 
-    type IntoIter = std::collections::hash_map::IntoIter<String, (Arc<Embedder>, Arc<Prompt>)>;
+    type IntoIter =
+        std::collections::hash_map::IntoIter<String, (Arc<Embedder>, Arc<Prompt>, ArroyOptions)>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -215,6 +665,44 @@ impl EmbedderOptions {
     pub fn ollama(api_key: Option<String>, url: Option<String>) -> Self {
         Self::Ollama(ollama::EmbedderOptions::with_default_model(api_key, url))
     }
+
+    /// Options of the embedder of the search queries, using the model or the request of the search-time configuration.
+    pub fn with_search_options(&self, search: &SearchEmbedderOptions) -> Self {
+        let mut options = self.clone();
+        match &mut options {
+            EmbedderOptions::HuggingFace(options) => {
+                if let Some(model) = &search.model {
+                    options.model = model.clone();
+                    options.revision = None;
+                    options.model_path = None;
+                }
+            }
+            EmbedderOptions::OpenAi(options) => {
+                if let Some(model) =
+                    search.model.as_deref().and_then(openai::EmbeddingModel::from_name)
+                {
+                    options.embedding_model = model;
+                }
+            }
+            EmbedderOptions::Ollama(options) => {
+                if let Some(model) = &search.model {
+                    options.embedding_model = model.clone();
+                }
+            }
+            EmbedderOptions::Rest(options) => {
+                if let Some(query) = &search.query {
+                    options.query = query.clone();
+                }
+            }
+            EmbedderOptions::UserProvided(_) => {}
+        }
+        options
+    }
+
+    /// Whether the embedder stores sparse vectors instead of dense embeddings.
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, EmbedderOptions::UserProvided(options) if options.sparse)
+    }
 }
 
 impl Embedder {
@@ -244,6 +732,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.embed(texts),
             Embedder::UserProvided(embedder) => embedder.embed(texts),
             Embedder::Rest(embedder) => embedder.embed(texts),
+            Embedder::Asymmetric(embedder) => embedder.document_embedder().embed(texts),
         }
     }
 
@@ -258,6 +747,14 @@ impl Embedder {
         })
     }
 
+    /// Embed a search query, with the search-time configuration of the embedder if any.
+    pub fn embed_search(&self, query: String) -> std::result::Result<Embedding, EmbedError> {
+        match self {
+            Embedder::Asymmetric(embedder) => embedder.embed_search(query),
+            _ => self.embed_one(query),
+        }
+    }
+
     /// Embed multiple chunks of texts.
     ///
     /// Each chunk is composed of one or multiple texts.
@@ -272,6 +769,9 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.embed_chunks(text_chunks, threads),
             Embedder::UserProvided(embedder) => embedder.embed_chunks(text_chunks),
             Embedder::Rest(embedder) => embedder.embed_chunks(text_chunks, threads),
+            Embedder::Asymmetric(embedder) => {
+                embedder.document_embedder().embed_chunks(text_chunks, threads)
+            }
         }
     }
 
@@ -283,6 +783,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.chunk_count_hint(),
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.chunk_count_hint(),
+            Embedder::Asymmetric(embedder) => embedder.document_embedder().chunk_count_hint(),
         }
     }
 
@@ -294,6 +795,9 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.prompt_count_in_chunk_hint(),
+            Embedder::Asymmetric(embedder) => {
+                embedder.document_embedder().prompt_count_in_chunk_hint()
+            }
         }
     }
 
@@ -305,6 +809,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.dimensions(),
             Embedder::UserProvided(embedder) => embedder.dimensions(),
             Embedder::Rest(embedder) => embedder.dimensions(),
+            Embedder::Asymmetric(embedder) => embedder.document_embedder().dimensions(),
         }
     }
 
@@ -316,6 +821,15 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.distribution(),
             Embedder::UserProvided(embedder) => embedder.distribution(),
             Embedder::Rest(embedder) => embedder.distribution(),
+            Embedder::Asymmetric(embedder) => embedder.document_embedder().distribution(),
+        }
+    }
+
+    /// Whether the embedder stores sparse vectors instead of dense embeddings.
+    pub fn is_sparse(&self) -> bool {
+        match self {
+            Embedder::UserProvided(embedder) => embedder.is_sparse(),
+            _ => false,
         }
     }
 }
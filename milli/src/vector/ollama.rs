@@ -39,6 +39,9 @@ impl Embedder {
             input_field: vec!["prompt".to_owned()],
             path_to_embeddings: Default::default(),
             embedding_object: vec!["embedding".to_owned()],
+            headers: Default::default(),
+            max_batch_size: None,
+            max_concurrent_requests: None,
             input_type: super::rest::InputType::Text,
         }) {
             Ok(embedder) => embedder,
@@ -190,6 +190,9 @@ impl Embedder {
             input_type: crate::vector::rest::InputType::TextArray,
             path_to_embeddings: vec!["data".to_owned()],
             embedding_object: vec!["embedding".to_owned()],
+            headers: Default::default(),
+            max_batch_size: None,
+            max_concurrent_requests: None,
         })?;
 
         // looking at the code it is very unclear that this can actually fail.
@@ -1,9 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use deserr::{take_cf_content, DeserializeError, Deserr, Sequence};
+use deserr::{take_cf_content, DeserializeError, Deserr, Map, Sequence};
 use obkv::KvReader;
 use serde_json::{from_slice, Value};
 
+use super::sparse::SparseVector;
 use super::Embedding;
 use crate::index::IndexEmbeddingConfig;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd};
@@ -16,6 +17,8 @@ pub const RESERVED_VECTORS_FIELD_NAME: &str = "_vectors";
 pub enum Vectors {
     ImplicitlyUserProvided(VectorOrArrayOfVectors),
     Explicit(ExplicitVectors),
+    /// The weights of the tokens of a sparse embedder
+    Sparse(SparseVector),
 }
 
 impl<E: DeserializeError> Deserr<E> for Vectors {
@@ -29,8 +32,58 @@ impl<E: DeserializeError> Deserr<E> for Vectors {
                     value, location,
                 )?))
             }
-            deserr::Value::Map(_) => {
-                Ok(Vectors::Explicit(ExplicitVectors::deserialize_from_value(value, location)?))
+            deserr::Value::Map(map) => {
+                // a map is either explicit vectors, or the weights of the tokens of a sparse vector
+                let mut embeddings = None;
+                let mut regenerate = None;
+                let mut weights = Vec::new();
+                for (key, value) in map.into_iter() {
+                    match key.as_str() {
+                        "embeddings" => embeddings = Some(value),
+                        "regenerate" => regenerate = Some(value),
+                        _ => weights.push((key, value)),
+                    }
+                }
+
+                if embeddings.is_none() && regenerate.is_none() && !weights.is_empty() {
+                    let mut sparse = BTreeMap::new();
+                    for (token, weight) in weights {
+                        let weight = f32::deserialize_from_value(
+                            weight.into_value(),
+                            location.push_key(&token),
+                        )?;
+                        sparse.insert(token, weight);
+                    }
+                    return SparseVector::from_weights(sparse).map(Vectors::Sparse).map_err(
+                        |msg| {
+                            take_cf_content(E::error::<std::convert::Infallible>(
+                                None,
+                                deserr::ErrorKind::Unexpected { msg },
+                                location,
+                            ))
+                        },
+                    );
+                }
+
+                let embeddings = match embeddings {
+                    Some(embeddings) => Option::<VectorOrArrayOfVectors>::deserialize_from_value(
+                        embeddings.into_value(),
+                        location.push_key("embeddings"),
+                    )?,
+                    None => None,
+                };
+                let Some(regenerate) = regenerate else {
+                    return Err(take_cf_content(E::error::<std::convert::Infallible>(
+                        None,
+                        deserr::ErrorKind::MissingField { field: "regenerate" },
+                        location,
+                    )));
+                };
+                let regenerate = bool::deserialize_from_value(
+                    regenerate.into_value(),
+                    location.push_key("regenerate"),
+                )?;
+                Ok(Vectors::Explicit(ExplicitVectors { embeddings, regenerate }))
             }
 
             value => Err(take_cf_content(E::error(
@@ -52,7 +105,7 @@ impl<E: DeserializeError> Deserr<E> for Vectors {
 impl Vectors {
     pub fn must_regenerate(&self) -> bool {
         match self {
-            Vectors::ImplicitlyUserProvided(_) => false,
+            Vectors::ImplicitlyUserProvided(_) | Vectors::Sparse(_) => false,
             Vectors::Explicit(ExplicitVectors { regenerate, .. }) => *regenerate,
         }
     }
@@ -65,6 +118,8 @@ impl Vectors {
             Vectors::Explicit(ExplicitVectors { embeddings, regenerate: _ }) => {
                 embeddings.map(|embeddings| embeddings.into_array_of_vectors().unwrap_or_default())
             }
+            // sparse vectors are extracted separately
+            Vectors::Sparse(_) => None,
         }
     }
 }
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use deserr::Deserr;
 use rand::Rng;
-use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
 use serde::{Deserialize, Serialize};
 
 use super::error::EmbedErrorKind;
@@ -21,7 +23,7 @@ pub enum RetryStrategy {
     GiveUp,
     Retry,
     RetryTokenized,
-    RetryAfterRateLimit,
+    RetryAfterRateLimit(Option<std::time::Duration>),
 }
 
 impl Retry {
@@ -38,7 +40,12 @@ impl Retry {
     }
 
     pub fn rate_limited(error: EmbedError) -> Self {
-        Self { error, strategy: RetryStrategy::RetryAfterRateLimit }
+        Self { error, strategy: RetryStrategy::RetryAfterRateLimit(None) }
+    }
+
+    /// Rate limited by a server that told how long to wait before sending the next request.
+    pub fn rate_limited_for(error: EmbedError, retry_after: std::time::Duration) -> Self {
+        Self { error, strategy: RetryStrategy::RetryAfterRateLimit(Some(retry_after)) }
     }
 
     pub fn into_duration(self, attempt: u32) -> Result<std::time::Duration, EmbedError> {
@@ -46,8 +53,10 @@ impl Retry {
             RetryStrategy::GiveUp => Err(self.error),
             RetryStrategy::Retry => Ok(std::time::Duration::from_millis((10u64).pow(attempt))),
             RetryStrategy::RetryTokenized => Ok(std::time::Duration::from_millis(1)),
-            RetryStrategy::RetryAfterRateLimit => {
-                Ok(std::time::Duration::from_millis(100 + 10u64.pow(attempt)))
+            RetryStrategy::RetryAfterRateLimit(retry_after) => {
+                // back off exponentially, waiting at least as long as requested by the server
+                let backoff = std::time::Duration::from_millis(100 * 2u64.pow(attempt));
+                Ok(retry_after.map_or(backoff, |retry_after| retry_after.max(backoff)))
             }
         }
     }
@@ -82,6 +91,15 @@ pub struct EmbedderOptions {
     // shape of a single embedding
     pub embedding_object: Vec<String>,
     pub input_type: InputType,
+    // additional headers sent with each request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    // maximum number of texts embedded in a single request
+    #[serde(default)]
+    pub max_batch_size: Option<usize>,
+    // maximum number of requests sent in parallel
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
 }
 
 impl Default for EmbedderOptions {
@@ -96,6 +114,9 @@ impl Default for EmbedderOptions {
             api_key: None,
             distribution: None,
             dimensions: None,
+            headers: Default::default(),
+            max_batch_size: None,
+            max_concurrent_requests: None,
         }
     }
 }
@@ -113,6 +134,9 @@ impl std::hash::Hash for EmbedderOptions {
         self.path_to_embeddings.hash(state);
         self.embedding_object.hash(state);
         self.input_type.hash(state);
+        self.headers.hash(state);
+        self.max_batch_size.hash(state);
+        self.max_concurrent_requests.hash(state);
     }
 }
 
@@ -143,14 +167,25 @@ impl Embedder {
     }
 
     pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Embeddings<f32>>, EmbedError> {
-        embed(&self.client, &self.options, self.bearer.as_deref(), texts.as_slice(), texts.len())
+        self.embed_ref(&texts)
     }
 
+    /// Embeds the texts, sending one request per batch of at most `max_batch_size` texts.
     pub fn embed_ref<S>(&self, texts: &[S]) -> Result<Vec<Embeddings<f32>>, EmbedError>
     where
         S: AsRef<str> + Serialize,
     {
-        embed(&self.client, &self.options, self.bearer.as_deref(), texts, texts.len())
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.max_batch_size()) {
+            embeddings.extend(embed(
+                &self.client,
+                &self.options,
+                self.bearer.as_deref(),
+                batch,
+                batch.len(),
+            )?);
+        }
+        Ok(embeddings)
     }
 
     pub fn embed_tokens(&self, tokens: &[usize]) -> Result<Embeddings<f32>, EmbedError> {
@@ -164,9 +199,17 @@ impl Embedder {
         text_chunks: Vec<Vec<String>>,
         threads: &ThreadPoolNoAbort,
     ) -> Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        // at most `max_concurrent_requests` chunks are embedded at the same time
+        let max_concurrent_requests = self.chunk_count_hint();
         threads
             .install(move || {
-                text_chunks.into_par_iter().map(move |chunk| self.embed(chunk)).collect()
+                let mut embeddings = Vec::with_capacity(text_chunks.len());
+                for chunks in text_chunks.chunks(max_concurrent_requests) {
+                    let chunks: Result<Vec<_>, _> =
+                        chunks.par_iter().map(|chunk| self.embed_ref(chunk)).collect();
+                    embeddings.extend(chunks?);
+                }
+                Ok(embeddings)
             })
             .map_err(|error| EmbedError {
                 kind: EmbedErrorKind::PanicInThreadPool(error),
@@ -175,13 +218,21 @@ impl Embedder {
     }
 
     pub fn chunk_count_hint(&self) -> usize {
-        super::REQUEST_PARALLELISM
+        self.options.max_concurrent_requests.unwrap_or(super::REQUEST_PARALLELISM)
     }
 
     pub fn prompt_count_in_chunk_hint(&self) -> usize {
         match self.options.input_type {
             InputType::Text => 1,
-            InputType::TextArray => 10,
+            InputType::TextArray => self.options.max_batch_size.unwrap_or(10),
+        }
+    }
+
+    /// Maximum number of texts sent in a single request.
+    fn max_batch_size(&self) -> usize {
+        match self.options.input_type {
+            InputType::Text => 1,
+            InputType::TextArray => self.options.max_batch_size.unwrap_or(usize::MAX),
         }
     }
 
@@ -219,6 +270,8 @@ where
     let request =
         if let Some(bearer) = bearer { request.set("Authorization", bearer) } else { request };
     let request = request.set("Content-Type", "application/json");
+    let request =
+        options.headers.iter().fold(request, |request, (name, value)| request.set(name, value));
 
     let input_value = match options.input_type {
         InputType::Text => serde_json::json!(inputs.first()),
@@ -298,10 +351,17 @@ fn check_response(response: Result<ureq::Response, ureq::Error>) -> Result<ureq:
     match response {
         Ok(response) => Ok(response),
         Err(ureq::Error::Status(code, response)) => {
+            let retry_after = retry_after(&response);
             let error_response: Option<String> = response.into_string().ok();
             Err(match code {
                 401 => Retry::give_up(EmbedError::rest_unauthorized(error_response)),
-                429 => Retry::rate_limited(EmbedError::rest_too_many_requests(error_response)),
+                429 => {
+                    let error = EmbedError::rest_too_many_requests(error_response);
+                    match retry_after {
+                        Some(retry_after) => Retry::rate_limited_for(error, retry_after),
+                        None => Retry::rate_limited(error),
+                    }
+                }
                 400 => Retry::give_up(EmbedError::rest_bad_request(error_response)),
                 500..=599 => {
                     Retry::retry_later(EmbedError::rest_internal_server_error(code, error_response))
@@ -318,6 +378,12 @@ fn check_response(response: Result<ureq::Response, ureq::Error>) -> Result<ureq:
     }
 }
 
+/// Reads the number of seconds to wait before retrying from the `Retry-After` header of the response.
+fn retry_after(response: &ureq::Response) -> Option<std::time::Duration> {
+    let seconds: u64 = response.header("Retry-After")?.trim().parse().ok()?;
+    Some(std::time::Duration::from_secs(seconds))
+}
+
 fn response_to_embedding(
     response: ureq::Response,
     options: &EmbedderOptions,
@@ -1,9 +1,13 @@
+use std::collections::BTreeMap;
+
 use deserr::Deserr;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
 
+use super::asymmetric::SearchEmbedderOptions;
+use super::chunking::ChunkingOptions;
 use super::rest::InputType;
-use super::{ollama, openai, DistributionShift};
+use super::{ollama, openai, ArroyOptions, Distance, DistributionShift, OnEmbeddingError};
 use crate::prompt::PromptData;
 use crate::update::Setting;
 use crate::vector::EmbeddingConfig;
@@ -24,6 +28,9 @@ pub struct EmbeddingSettings {
     pub revision: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    pub model_path: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     pub api_key: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
@@ -51,7 +58,34 @@ pub struct EmbeddingSettings {
     pub input_type: Setting<InputType>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    pub headers: Setting<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_batch_size: Setting<usize>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_concurrent_requests: Setting<usize>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     pub distribution: Setting<DistributionShift>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub binary_quantized: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub distance: Setting<Distance>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub chunking: Setting<ChunkingOptions>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub search_embedder: Setting<SearchEmbedderOptions>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub on_error: Setting<OnEmbeddingError>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub sparse: Setting<bool>,
 }
 
 pub fn check_unset<T>(
@@ -86,28 +120,67 @@ pub enum ReindexAction {
 
 pub enum SettingsDiff {
     Remove,
-    Reindex { action: ReindexAction, updated_settings: EmbeddingSettings },
-    UpdateWithoutReindex { updated_settings: EmbeddingSettings },
+    Reindex { action: ReindexAction, updated_settings: EmbeddingSettings, convert: bool },
+    UpdateWithoutReindex { updated_settings: EmbeddingSettings, convert: bool },
+}
+
+/// What must happen to the embeddings of an embedder after its settings were updated
+#[derive(Default, Debug)]
+pub struct EmbedderAction {
+    /// How the embeddings were stored before the update
+    pub old_options: ArroyOptions,
+    /// Whether the embeddings must be converted to another distance or binary quantized during the update
+    pub is_being_converted: bool,
+    pub write_back: Option<WriteBackToDocuments>,
+    pub reindex: Option<ReindexAction>,
 }
 
-pub enum EmbedderAction {
-    WriteBackToDocuments(WriteBackToDocuments),
-    Reindex(ReindexAction),
+impl EmbedderAction {
+    pub fn is_being_converted(&self) -> bool {
+        self.is_being_converted
+    }
+
+    pub fn write_back(&self) -> Option<&WriteBackToDocuments> {
+        self.write_back.as_ref()
+    }
+
+    pub fn reindex(&self) -> Option<&ReindexAction> {
+        self.reindex.as_ref()
+    }
+
+    pub fn with_is_being_converted(mut self, convert: bool) -> Self {
+        self.is_being_converted = convert;
+        self
+    }
+
+    pub fn with_write_back(write_back: WriteBackToDocuments, old_options: ArroyOptions) -> Self {
+        Self { old_options, is_being_converted: false, write_back: Some(write_back), reindex: None }
+    }
+
+    pub fn with_reindex(reindex: ReindexAction, old_options: ArroyOptions) -> Self {
+        Self { old_options, is_being_converted: false, write_back: None, reindex: Some(reindex) }
+    }
 }
 
+#[derive(Debug)]
 pub struct WriteBackToDocuments {
     pub embedder_id: u8,
     pub user_provided: RoaringBitmap,
 }
 
 impl SettingsDiff {
-    pub fn from_settings(old: EmbeddingSettings, new: Setting<EmbeddingSettings>) -> Self {
-        match new {
+    pub fn from_settings(
+        embedder_name: &str,
+        old: EmbeddingSettings,
+        new: Setting<EmbeddingSettings>,
+    ) -> Result<Self, UserError> {
+        let ret = match new {
             Setting::Set(new) => {
                 let EmbeddingSettings {
                     mut source,
                     mut model,
                     mut revision,
+                    mut model_path,
                     mut api_key,
                     mut dimensions,
                     mut document_template,
@@ -117,13 +190,23 @@ impl SettingsDiff {
                     mut path_to_embeddings,
                     mut embedding_object,
                     mut input_type,
+                    mut headers,
+                    mut max_batch_size,
+                    mut max_concurrent_requests,
                     mut distribution,
+                    mut binary_quantized,
+                    mut distance,
+                    mut chunking,
+                    mut search_embedder,
+                    mut on_error,
+                    mut sparse,
                 } = old;
 
                 let EmbeddingSettings {
                     source: new_source,
                     model: new_model,
                     revision: new_revision,
+                    model_path: new_model_path,
                     api_key: new_api_key,
                     dimensions: new_dimensions,
                     document_template: new_document_template,
@@ -133,9 +216,29 @@ impl SettingsDiff {
                     path_to_embeddings: new_path_to_embeddings,
                     embedding_object: new_embedding_object,
                     input_type: new_input_type,
+                    headers: new_headers,
+                    max_batch_size: new_max_batch_size,
+                    max_concurrent_requests: new_max_concurrent_requests,
                     distribution: new_distribution,
+                    binary_quantized: new_binary_quantized,
+                    distance: new_distance,
+                    chunking: new_chunking,
+                    search_embedder: new_search_embedder,
+                    on_error: new_on_error,
+                    sparse: new_sparse,
                 } = new;
 
+                // the binary quantization is lossy, it can't be reverted
+                if matches!(binary_quantized, Setting::Set(true))
+                    && matches!(new_binary_quantized, Setting::Set(false) | Setting::Reset)
+                {
+                    return Err(UserError::InvalidDisableBinaryQuantization {
+                        embedder_name: embedder_name.to_owned(),
+                    });
+                }
+
+                let was_sparse = is_sparse(&source, &sparse);
+
                 let mut reindex_action = None;
 
                 // **Warning**: do not use short-circuiting || here, we want all these operations applied
@@ -146,6 +249,7 @@ impl SettingsDiff {
                         &source,
                         &mut model,
                         &mut revision,
+                        &mut model_path,
                         &mut dimensions,
                         &mut url,
                         &mut query,
@@ -153,15 +257,31 @@ impl SettingsDiff {
                         &mut path_to_embeddings,
                         &mut embedding_object,
                         &mut input_type,
+                        &mut headers,
+                        &mut max_batch_size,
+                        &mut max_concurrent_requests,
                         &mut document_template,
+                        &mut chunking,
+                        &mut search_embedder,
+                        &mut sparse,
                     )
                 }
+                sparse.apply(new_sparse);
+                // sparse vectors are stored in a different structure than dense embeddings
+                if was_sparse != is_sparse(&source, &sparse) {
+                    return Err(UserError::InvalidSparseChange {
+                        embedder_name: embedder_name.to_owned(),
+                    });
+                }
                 if model.apply(new_model) {
                     ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
                 }
                 if revision.apply(new_revision) {
                     ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
                 }
+                if model_path.apply(new_model_path) {
+                    ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
+                }
                 if dimensions.apply(new_dimensions) {
                     ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
                 }
@@ -198,14 +318,31 @@ impl SettingsDiff {
                         ReindexAction::RegeneratePrompts,
                     );
                 }
+                if chunking.apply(new_chunking) {
+                    ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
+                }
 
                 distribution.apply(new_distribution);
+                // the search embedder is only used to embed the queries
+                search_embedder.apply(new_search_embedder);
+                // only the next indexing operations tolerate failures
+                on_error.apply(new_on_error);
                 api_key.apply(new_api_key);
+                // the headers and the batching of the requests don't change the embeddings
+                headers.apply(new_headers);
+                max_batch_size.apply(new_max_batch_size);
+                max_concurrent_requests.apply(new_max_concurrent_requests);
+                let quantize = binary_quantized.apply(new_binary_quantized)
+                    && matches!(binary_quantized, Setting::Set(true));
+                // changing the distance doesn't require to regenerate the embeddings,
+                // only to rebuild the arroy trees
+                let convert = distance.apply(new_distance) | quantize;
 
                 let updated_settings = EmbeddingSettings {
                     source,
                     model,
                     revision,
+                    model_path,
                     api_key,
                     dimensions,
                     document_template,
@@ -215,17 +352,27 @@ impl SettingsDiff {
                     path_to_embeddings,
                     embedding_object,
                     input_type,
+                    headers,
+                    max_batch_size,
+                    max_concurrent_requests,
                     distribution,
+                    binary_quantized,
+                    distance,
+                    chunking,
+                    search_embedder,
+                    on_error,
+                    sparse,
                 };
 
                 match reindex_action {
-                    Some(action) => Self::Reindex { action, updated_settings },
-                    None => Self::UpdateWithoutReindex { updated_settings },
+                    Some(action) => Self::Reindex { action, updated_settings, convert },
+                    None => Self::UpdateWithoutReindex { updated_settings, convert },
                 }
             }
             Setting::Reset => Self::Remove,
-            Setting::NotSet => Self::UpdateWithoutReindex { updated_settings: old },
-        }
+            Setting::NotSet => Self::UpdateWithoutReindex { updated_settings: old, convert: false },
+        };
+        Ok(ret)
     }
 }
 
@@ -244,6 +391,7 @@ fn apply_default_for_source(
     source: &Setting<EmbedderSource>,
     model: &mut Setting<String>,
     revision: &mut Setting<String>,
+    model_path: &mut Setting<String>,
     dimensions: &mut Setting<usize>,
     url: &mut Setting<String>,
     query: &mut Setting<serde_json::Value>,
@@ -251,12 +399,19 @@ fn apply_default_for_source(
     path_to_embeddings: &mut Setting<Vec<String>>,
     embedding_object: &mut Setting<Vec<String>>,
     input_type: &mut Setting<InputType>,
+    headers: &mut Setting<BTreeMap<String, String>>,
+    max_batch_size: &mut Setting<usize>,
+    max_concurrent_requests: &mut Setting<usize>,
     document_template: &mut Setting<String>,
+    chunking: &mut Setting<ChunkingOptions>,
+    search_embedder: &mut Setting<SearchEmbedderOptions>,
+    sparse: &mut Setting<bool>,
 ) {
     match source {
         Setting::Set(EmbedderSource::HuggingFace) => {
             *model = Setting::Reset;
             *revision = Setting::Reset;
+            *model_path = Setting::Reset;
             *dimensions = Setting::NotSet;
             *url = Setting::NotSet;
             *query = Setting::NotSet;
@@ -264,10 +419,16 @@ fn apply_default_for_source(
             *path_to_embeddings = Setting::NotSet;
             *embedding_object = Setting::NotSet;
             *input_type = Setting::NotSet;
+            *headers = Setting::NotSet;
+            *max_batch_size = Setting::NotSet;
+            *max_concurrent_requests = Setting::NotSet;
+            *search_embedder = Setting::Reset;
+            *sparse = Setting::NotSet;
         }
         Setting::Set(EmbedderSource::Ollama) => {
             *model = Setting::Reset;
             *revision = Setting::NotSet;
+            *model_path = Setting::NotSet;
             *dimensions = Setting::Reset;
             *url = Setting::NotSet;
             *query = Setting::NotSet;
@@ -275,10 +436,16 @@ fn apply_default_for_source(
             *path_to_embeddings = Setting::NotSet;
             *embedding_object = Setting::NotSet;
             *input_type = Setting::NotSet;
+            *headers = Setting::NotSet;
+            *max_batch_size = Setting::NotSet;
+            *max_concurrent_requests = Setting::NotSet;
+            *search_embedder = Setting::Reset;
+            *sparse = Setting::NotSet;
         }
         Setting::Set(EmbedderSource::OpenAi) | Setting::Reset => {
             *model = Setting::Reset;
             *revision = Setting::NotSet;
+            *model_path = Setting::NotSet;
             *dimensions = Setting::NotSet;
             *url = Setting::Reset;
             *query = Setting::NotSet;
@@ -286,10 +453,16 @@ fn apply_default_for_source(
             *path_to_embeddings = Setting::NotSet;
             *embedding_object = Setting::NotSet;
             *input_type = Setting::NotSet;
+            *headers = Setting::NotSet;
+            *max_batch_size = Setting::NotSet;
+            *max_concurrent_requests = Setting::NotSet;
+            *search_embedder = Setting::Reset;
+            *sparse = Setting::NotSet;
         }
         Setting::Set(EmbedderSource::Rest) => {
             *model = Setting::NotSet;
             *revision = Setting::NotSet;
+            *model_path = Setting::NotSet;
             *dimensions = Setting::Reset;
             *url = Setting::Reset;
             *query = Setting::Reset;
@@ -297,10 +470,16 @@ fn apply_default_for_source(
             *path_to_embeddings = Setting::Reset;
             *embedding_object = Setting::Reset;
             *input_type = Setting::Reset;
+            *headers = Setting::Reset;
+            *max_batch_size = Setting::Reset;
+            *max_concurrent_requests = Setting::Reset;
+            *search_embedder = Setting::Reset;
+            *sparse = Setting::NotSet;
         }
         Setting::Set(EmbedderSource::UserProvided) => {
             *model = Setting::NotSet;
             *revision = Setting::NotSet;
+            *model_path = Setting::NotSet;
             *dimensions = Setting::Reset;
             *url = Setting::NotSet;
             *query = Setting::NotSet;
@@ -308,12 +487,24 @@ fn apply_default_for_source(
             *path_to_embeddings = Setting::NotSet;
             *embedding_object = Setting::NotSet;
             *input_type = Setting::NotSet;
+            *headers = Setting::NotSet;
+            *max_batch_size = Setting::NotSet;
+            *max_concurrent_requests = Setting::NotSet;
             *document_template = Setting::NotSet;
+            *chunking = Setting::NotSet;
+            *search_embedder = Setting::NotSet;
+            *sparse = Setting::Reset;
         }
         Setting::NotSet => {}
     }
 }
 
+/// Whether the embeddings of an embedder with these settings are sparse vectors.
+fn is_sparse(source: &Setting<EmbedderSource>, sparse: &Setting<bool>) -> bool {
+    matches!(source, Setting::Set(EmbedderSource::UserProvided))
+        && matches!(sparse, Setting::Set(true))
+}
+
 pub fn check_set<T>(
     key: &Setting<T>,
     field: &'static str,
@@ -335,6 +526,7 @@ impl EmbeddingSettings {
     pub const SOURCE: &'static str = "source";
     pub const MODEL: &'static str = "model";
     pub const REVISION: &'static str = "revision";
+    pub const MODEL_PATH: &'static str = "modelPath";
     pub const API_KEY: &'static str = "apiKey";
     pub const DIMENSIONS: &'static str = "dimensions";
     pub const DOCUMENT_TEMPLATE: &'static str = "documentTemplate";
@@ -345,9 +537,26 @@ impl EmbeddingSettings {
     pub const PATH_TO_EMBEDDINGS: &'static str = "pathToEmbeddings";
     pub const EMBEDDING_OBJECT: &'static str = "embeddingObject";
     pub const INPUT_TYPE: &'static str = "inputType";
+    pub const HEADERS: &'static str = "headers";
+    pub const MAX_BATCH_SIZE: &'static str = "maxBatchSize";
+    pub const MAX_CONCURRENT_REQUESTS: &'static str = "maxConcurrentRequests";
 
     pub const DISTRIBUTION: &'static str = "distribution";
 
+    pub const BINARY_QUANTIZED: &'static str = "binaryQuantized";
+
+    pub const DISTANCE: &'static str = "distance";
+
+    pub const CHUNKING: &'static str = "chunking";
+
+    pub const SEARCH_EMBEDDER: &'static str = "searchEmbedder";
+    pub const SEARCH_EMBEDDER_MODEL: &'static str = "searchEmbedder.model";
+    pub const SEARCH_EMBEDDER_QUERY: &'static str = "searchEmbedder.query";
+
+    pub const ON_ERROR: &'static str = "onError";
+
+    pub const SPARSE: &'static str = "sparse";
+
     pub fn allowed_sources_for_field(field: &'static str) -> &'static [EmbedderSource] {
         match field {
             Self::SOURCE => &[
@@ -361,6 +570,7 @@ impl EmbeddingSettings {
                 &[EmbedderSource::HuggingFace, EmbedderSource::OpenAi, EmbedderSource::Ollama]
             }
             Self::REVISION => &[EmbedderSource::HuggingFace],
+            Self::MODEL_PATH => &[EmbedderSource::HuggingFace],
             Self::API_KEY => {
                 &[EmbedderSource::OpenAi, EmbedderSource::Ollama, EmbedderSource::Rest]
             }
@@ -379,6 +589,9 @@ impl EmbeddingSettings {
             Self::PATH_TO_EMBEDDINGS => &[EmbedderSource::Rest],
             Self::EMBEDDING_OBJECT => &[EmbedderSource::Rest],
             Self::INPUT_TYPE => &[EmbedderSource::Rest],
+            Self::HEADERS => &[EmbedderSource::Rest],
+            Self::MAX_BATCH_SIZE => &[EmbedderSource::Rest],
+            Self::MAX_CONCURRENT_REQUESTS => &[EmbedderSource::Rest],
             Self::DISTRIBUTION => &[
                 EmbedderSource::HuggingFace,
                 EmbedderSource::Ollama,
@@ -386,6 +599,44 @@ impl EmbeddingSettings {
                 EmbedderSource::Rest,
                 EmbedderSource::UserProvided,
             ],
+            Self::BINARY_QUANTIZED => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::Ollama,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Rest,
+                EmbedderSource::UserProvided,
+            ],
+            Self::DISTANCE => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::Ollama,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Rest,
+                EmbedderSource::UserProvided,
+            ],
+            Self::CHUNKING => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::Ollama,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Rest,
+            ],
+            Self::SEARCH_EMBEDDER => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::Ollama,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Rest,
+            ],
+            Self::SEARCH_EMBEDDER_MODEL => {
+                &[EmbedderSource::HuggingFace, EmbedderSource::OpenAi, EmbedderSource::Ollama]
+            }
+            Self::SEARCH_EMBEDDER_QUERY => &[EmbedderSource::Rest],
+            Self::ON_ERROR => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::Ollama,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Rest,
+                EmbedderSource::UserProvided,
+            ],
+            Self::SPARSE => &[EmbedderSource::UserProvided],
             _other => unreachable!("unknown field"),
         }
     }
@@ -399,14 +650,25 @@ impl EmbeddingSettings {
                 Self::DOCUMENT_TEMPLATE,
                 Self::DIMENSIONS,
                 Self::DISTRIBUTION,
+                Self::BINARY_QUANTIZED,
+                Self::DISTANCE,
+                Self::CHUNKING,
+                Self::SEARCH_EMBEDDER,
+                Self::ON_ERROR,
                 Self::URL,
             ],
             EmbedderSource::HuggingFace => &[
                 Self::SOURCE,
                 Self::MODEL,
                 Self::REVISION,
+                Self::MODEL_PATH,
                 Self::DOCUMENT_TEMPLATE,
                 Self::DISTRIBUTION,
+                Self::BINARY_QUANTIZED,
+                Self::DISTANCE,
+                Self::CHUNKING,
+                Self::SEARCH_EMBEDDER,
+                Self::ON_ERROR,
             ],
             EmbedderSource::Ollama => &[
                 Self::SOURCE,
@@ -415,8 +677,21 @@ impl EmbeddingSettings {
                 Self::URL,
                 Self::API_KEY,
                 Self::DISTRIBUTION,
+                Self::BINARY_QUANTIZED,
+                Self::DISTANCE,
+                Self::CHUNKING,
+                Self::SEARCH_EMBEDDER,
+                Self::ON_ERROR,
+            ],
+            EmbedderSource::UserProvided => &[
+                Self::SOURCE,
+                Self::DIMENSIONS,
+                Self::DISTRIBUTION,
+                Self::BINARY_QUANTIZED,
+                Self::DISTANCE,
+                Self::ON_ERROR,
+                Self::SPARSE,
             ],
-            EmbedderSource::UserProvided => &[Self::SOURCE, Self::DIMENSIONS, Self::DISTRIBUTION],
             EmbedderSource::Rest => &[
                 Self::SOURCE,
                 Self::API_KEY,
@@ -428,7 +703,15 @@ impl EmbeddingSettings {
                 Self::PATH_TO_EMBEDDINGS,
                 Self::EMBEDDING_OBJECT,
                 Self::INPUT_TYPE,
+                Self::HEADERS,
+                Self::MAX_BATCH_SIZE,
+                Self::MAX_CONCURRENT_REQUESTS,
                 Self::DISTRIBUTION,
+                Self::BINARY_QUANTIZED,
+                Self::DISTANCE,
+                Self::CHUNKING,
+                Self::SEARCH_EMBEDDER,
+                Self::ON_ERROR,
             ],
         }
     }
@@ -482,16 +765,36 @@ impl std::fmt::Display for EmbedderSource {
 
 impl From<EmbeddingConfig> for EmbeddingSettings {
     fn from(value: EmbeddingConfig) -> Self {
-        let EmbeddingConfig { embedder_options, prompt } = value;
+        let EmbeddingConfig {
+            embedder_options,
+            prompt,
+            quantized,
+            distance,
+            chunking,
+            search_embedder,
+            on_error,
+        } = value;
+        let binary_quantized = quantized.map(Setting::Set).unwrap_or_default();
+        let distance = distance.map(Setting::Set).unwrap_or_default();
+        let chunking = chunking.map(Setting::Set).unwrap_or_default();
+        let search_embedder = search_embedder.map(Setting::Set).unwrap_or_default();
+        let on_error = on_error.map(Setting::Set).unwrap_or_default();
         match embedder_options {
             super::EmbedderOptions::HuggingFace(super::hf::EmbedderOptions {
                 model,
                 revision,
+                model_path,
                 distribution,
             }) => Self {
                 source: Setting::Set(EmbedderSource::HuggingFace),
-                model: Setting::Set(model),
+                // the model of a local model defaults to its path
+                model: if model_path.as_ref() == Some(&model) {
+                    Setting::NotSet
+                } else {
+                    Setting::Set(model)
+                },
                 revision: revision.map(Setting::Set).unwrap_or_default(),
+                model_path: model_path.map(Setting::Set).unwrap_or_default(),
                 api_key: Setting::NotSet,
                 dimensions: Setting::NotSet,
                 document_template: Setting::Set(prompt.template),
@@ -501,7 +804,16 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                headers: Setting::NotSet,
+                max_batch_size: Setting::NotSet,
+                max_concurrent_requests: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse: Setting::NotSet,
             },
             super::EmbedderOptions::OpenAi(super::openai::EmbedderOptions {
                 url,
@@ -513,6 +825,7 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::OpenAi),
                 model: Setting::Set(embedding_model.name().to_owned()),
                 revision: Setting::NotSet,
+                model_path: Setting::NotSet,
                 api_key: api_key.map(Setting::Set).unwrap_or_default(),
                 dimensions: dimensions.map(Setting::Set).unwrap_or_default(),
                 document_template: Setting::Set(prompt.template),
@@ -522,7 +835,16 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                headers: Setting::NotSet,
+                max_batch_size: Setting::NotSet,
+                max_concurrent_requests: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse: Setting::NotSet,
             },
             super::EmbedderOptions::Ollama(super::ollama::EmbedderOptions {
                 embedding_model,
@@ -533,6 +855,7 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::Ollama),
                 model: Setting::Set(embedding_model),
                 revision: Setting::NotSet,
+                model_path: Setting::NotSet,
                 api_key: api_key.map(Setting::Set).unwrap_or_default(),
                 dimensions: Setting::NotSet,
                 document_template: Setting::Set(prompt.template),
@@ -542,17 +865,29 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                headers: Setting::NotSet,
+                max_batch_size: Setting::NotSet,
+                max_concurrent_requests: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse: Setting::NotSet,
             },
             super::EmbedderOptions::UserProvided(super::manual::EmbedderOptions {
                 dimensions,
                 distribution,
+                sparse,
             }) => Self {
                 source: Setting::Set(EmbedderSource::UserProvided),
                 model: Setting::NotSet,
                 revision: Setting::NotSet,
+                model_path: Setting::NotSet,
                 api_key: Setting::NotSet,
-                dimensions: Setting::Set(dimensions),
+                // sparse vectors have no dimensions
+                dimensions: if sparse { Setting::NotSet } else { Setting::Set(dimensions) },
                 document_template: Setting::NotSet,
                 url: Setting::NotSet,
                 query: Setting::NotSet,
@@ -560,7 +895,16 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                headers: Setting::NotSet,
+                max_batch_size: Setting::NotSet,
+                max_concurrent_requests: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse: if sparse { Setting::Set(true) } else { Setting::NotSet },
             },
             super::EmbedderOptions::Rest(super::rest::EmbedderOptions {
                 api_key,
@@ -571,11 +915,15 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings,
                 embedding_object,
                 input_type,
+                headers,
+                max_batch_size,
+                max_concurrent_requests,
                 distribution,
             }) => Self {
                 source: Setting::Set(EmbedderSource::Rest),
                 model: Setting::NotSet,
                 revision: Setting::NotSet,
+                model_path: Setting::NotSet,
                 api_key: api_key.map(Setting::Set).unwrap_or_default(),
                 dimensions: dimensions.map(Setting::Set).unwrap_or_default(),
                 document_template: Setting::Set(prompt.template),
@@ -585,7 +933,18 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::Set(path_to_embeddings),
                 embedding_object: Setting::Set(embedding_object),
                 input_type: Setting::Set(input_type),
+                headers: if headers.is_empty() { Setting::NotSet } else { Setting::Set(headers) },
+                max_batch_size: max_batch_size.map(Setting::Set).unwrap_or_default(),
+                max_concurrent_requests: max_concurrent_requests
+                    .map(Setting::Set)
+                    .unwrap_or_default(),
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                binary_quantized,
+                distance,
+                chunking,
+                search_embedder,
+                on_error,
+                sparse: Setting::NotSet,
             },
         }
     }
@@ -598,6 +957,7 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             source,
             model,
             revision,
+            model_path,
             api_key,
             dimensions,
             document_template,
@@ -607,9 +967,24 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             path_to_embeddings,
             embedding_object,
             input_type,
+            headers,
+            max_batch_size,
+            max_concurrent_requests,
             distribution,
+            binary_quantized,
+            distance,
+            chunking,
+            search_embedder,
+            on_error,
+            sparse,
         } = value;
 
+        this.quantized = binary_quantized.set();
+        this.distance = distance.set();
+        this.chunking = chunking.set();
+        this.search_embedder = search_embedder.set();
+        this.on_error = on_error.set();
+
         if let Some(source) = source.set() {
             match source {
                 EmbedderSource::OpenAi => {
@@ -646,6 +1021,7 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                 }
                 EmbedderSource::HuggingFace => {
                     let mut options = super::hf::EmbedderOptions::default();
+                    let has_model = matches!(model, Setting::Set(_));
                     if let Some(model) = model.set() {
                         options.model = model;
                         // Reset the revision if we are setting the model.
@@ -658,14 +1034,24 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                     if let Some(revision) = revision.set() {
                         options.revision = Some(revision);
                     }
+                    if let Some(model_path) = model_path.set() {
+                        // a local model is not fetched from the hub, its revision is ignored
+                        options.revision = None;
+                        if !has_model {
+                            options.model.clone_from(&model_path);
+                        }
+                        options.model_path = Some(model_path);
+                    }
                     options.distribution = distribution.set();
                     this.embedder_options = super::EmbedderOptions::HuggingFace(options);
                 }
                 EmbedderSource::UserProvided => {
                     this.embedder_options =
                         super::EmbedderOptions::UserProvided(super::manual::EmbedderOptions {
-                            dimensions: dimensions.set().unwrap(),
+                            // sparse embedders have no dimensions
+                            dimensions: dimensions.set().unwrap_or_default(),
                             distribution: distribution.set(),
+                            sparse: sparse.set().unwrap_or_default(),
                         });
                 }
                 EmbedderSource::Rest => {
@@ -685,6 +1071,9 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                                 .set()
                                 .unwrap_or(embedder_options.embedding_object),
                             input_type: input_type.set().unwrap_or(embedder_options.input_type),
+                            headers: headers.set().unwrap_or(embedder_options.headers),
+                            max_batch_size: max_batch_size.set(),
+                            max_concurrent_requests: max_concurrent_requests.set(),
                             distribution: distribution.set(),
                         })
                 }
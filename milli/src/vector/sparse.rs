@@ -0,0 +1,173 @@
+//! Sparse vectors, such as the ones produced by learned sparse retrieval models, and their inverted index.
+
+use std::collections::{BTreeMap, HashMap};
+
+use deserr::{take_cf_content, DeserializeError, Deserr};
+use heed::types::Bytes;
+use heed::{Database, RoTxn, RwTxn};
+use roaring::RoaringBitmap;
+
+use crate::DocumentId;
+
+/// The maximum length in bytes of a token of a sparse vector.
+pub const MAX_TOKEN_LENGTH: usize = u8::MAX as usize;
+
+/// Maps the tokens of a vocabulary to their weight.
+///
+/// The tokens that are not in the map have a weight of zero.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct SparseVector(BTreeMap<String, f32>);
+
+impl SparseVector {
+    /// Builds a sparse vector from the weights of its tokens.
+    ///
+    /// Fails if a token is empty or too long, or if a weight is not finite.
+    pub fn from_weights(weights: BTreeMap<String, f32>) -> Result<Self, String> {
+        for (token, weight) in &weights {
+            if token.is_empty() {
+                return Err("A token cannot be empty.".to_owned());
+            }
+            if token.len() > MAX_TOKEN_LENGTH {
+                return Err(format!(
+                    "The token `{token}` is longer than {MAX_TOKEN_LENGTH} bytes."
+                ));
+            }
+            if !weight.is_finite() {
+                return Err(format!("The weight of the token `{token}` must be a finite number."));
+            }
+        }
+        Ok(Self(weights))
+    }
+
+    /// Iterates over the tokens and their weight, in the lexicographic order of the tokens.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> + '_ {
+        self.0.iter().map(|(token, weight)| (token.as_str(), *weight))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_inner(self) -> BTreeMap<String, f32> {
+        self.0
+    }
+}
+
+impl<E: DeserializeError> Deserr<E> for SparseVector {
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: deserr::ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        let weights = BTreeMap::<String, f32>::deserialize_from_value(value, location)?;
+        Self::from_weights(weights).map_err(|msg| {
+            take_cf_content(E::error::<std::convert::Infallible>(
+                None,
+                deserr::ErrorKind::Unexpected { msg },
+                location,
+            ))
+        })
+    }
+}
+
+/// Converts the dot product of two sparse vectors to a similarity between `0` and `1`.
+pub fn similarity(dot_product: f32) -> f32 {
+    let dot_product = dot_product.max(0.0);
+    dot_product / (1.0 + dot_product)
+}
+
+/// The inverted index of the sparse vectors of an embedder.
+///
+/// Each key is made of the id of the embedder, the length of the token, the token and the document id,
+/// and is associated with the weight of the token in the document.
+#[derive(Clone, Copy)]
+pub struct SparseVectorStore {
+    database: Database<Bytes, Bytes>,
+    embedder_index: u8,
+}
+
+impl SparseVectorStore {
+    pub fn new(database: Database<Bytes, Bytes>, embedder_index: u8) -> Self {
+        Self { database, embedder_index }
+    }
+
+    fn token_prefix(&self, token: &str) -> Vec<u8> {
+        let mut key = Vec::with_capacity(2 + token.len() + std::mem::size_of::<DocumentId>());
+        key.push(self.embedder_index);
+        // the length of the tokens is checked when the sparse vectors are parsed
+        key.push(token.len() as u8);
+        key.extend_from_slice(token.as_bytes());
+        key
+    }
+
+    fn posting_key(&self, token: &str, docid: DocumentId) -> Vec<u8> {
+        let mut key = self.token_prefix(token);
+        key.extend_from_slice(&docid.to_be_bytes());
+        key
+    }
+
+    /// Sets the weight of a token in a document.
+    pub fn put(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        token: &str,
+        docid: DocumentId,
+        weight: f32,
+    ) -> heed::Result<()> {
+        self.database.put(wtxn, &self.posting_key(token, docid), &weight.to_be_bytes())
+    }
+
+    /// Removes a token from a document.
+    pub fn del(&self, wtxn: &mut RwTxn<'_>, token: &str, docid: DocumentId) -> heed::Result<bool> {
+        self.database.delete(wtxn, &self.posting_key(token, docid))
+    }
+
+    /// Removes the sparse vectors of all the documents for this embedder.
+    pub fn clear(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<()> {
+        let mut iter = self.database.prefix_iter_mut(wtxn, &[self.embedder_index])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
+    /// Returns the `limit` candidates with the highest dot product with the query, along with their dot product.
+    ///
+    /// The candidates sharing no token with the query are not returned.
+    pub fn nearest(
+        &self,
+        rtxn: &RoTxn<'_>,
+        query: &SparseVector,
+        limit: usize,
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Vec<(DocumentId, f32)>> {
+        let mut dot_products: HashMap<DocumentId, f32> = HashMap::new();
+        for (token, query_weight) in query.iter() {
+            if query_weight == 0.0 {
+                continue;
+            }
+            let prefix = self.token_prefix(token);
+            for result in self.database.prefix_iter(rtxn, &prefix)? {
+                let (key, value) = result?;
+                let (Ok(docid), Ok(weight)) =
+                    (<[u8; 4]>::try_from(&key[prefix.len()..]), <[u8; 4]>::try_from(value))
+                else {
+                    continue;
+                };
+                let docid = DocumentId::from_be_bytes(docid);
+                if candidates.contains(docid) {
+                    *dot_products.entry(docid).or_default() +=
+                        query_weight * f32::from_be_bytes(weight);
+                }
+            }
+        }
+
+        let mut nearest: Vec<_> = dot_products.into_iter().collect();
+        nearest.sort_unstable_by(|(left_docid, left), (right_docid, right)| {
+            right.total_cmp(left).then(left_docid.cmp(right_docid))
+        });
+        nearest.truncate(limit);
+        Ok(nearest)
+    }
+}
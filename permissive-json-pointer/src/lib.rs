@@ -29,6 +29,8 @@ fn contained_in(selector: &str, key: &str) -> bool {
 }
 
 /// Map the selected leaf values of a json allowing you to update only the fields that were selected.
+/// The mapper also receives the indices of the arrays crossed to reach the leaf, from the outermost
+/// array to the innermost one.
 /// ```
 /// use serde_json::{Value, json};
 /// use permissive_json_pointer::map_leaf_values;
@@ -45,7 +47,7 @@ fn contained_in(selector: &str, key: &str) -> bool {
 /// map_leaf_values(
 ///     value.as_object_mut().unwrap(),
 ///     ["jean.race.name"],
-///     |key, value| match (value, key) {
+///     |key, _array_indices, value| match (value, key) {
 ///         (Value::String(name), "jean.race.name") => *name = "patou".to_string(),
 ///         _ => unreachable!(),
 ///     },
@@ -66,17 +68,18 @@ fn contained_in(selector: &str, key: &str) -> bool {
 pub fn map_leaf_values<'a>(
     value: &mut Map<String, Value>,
     selectors: impl IntoIterator<Item = &'a str>,
-    mut mapper: impl FnMut(&str, &mut Value),
+    mut mapper: impl FnMut(&str, &[usize], &mut Value),
 ) {
     let selectors: Vec<_> = selectors.into_iter().collect();
-    map_leaf_values_in_object(value, &selectors, "", &mut mapper);
+    map_leaf_values_in_object(value, &selectors, "", &mut Vec::new(), &mut mapper);
 }
 
 pub fn map_leaf_values_in_object(
     value: &mut Map<String, Value>,
     selectors: &[&str],
     base_key: &str,
-    mapper: &mut impl FnMut(&str, &mut Value),
+    array_indices: &mut Vec<usize>,
+    mapper: &mut impl FnMut(&str, &[usize], &mut Value),
 ) {
     for (key, value) in value.iter_mut() {
         let base_key = if base_key.is_empty() {
@@ -94,12 +97,12 @@ pub fn map_leaf_values_in_object(
         if should_continue {
             match value {
                 Value::Object(object) => {
-                    map_leaf_values_in_object(object, selectors, &base_key, mapper)
+                    map_leaf_values_in_object(object, selectors, &base_key, array_indices, mapper)
                 }
                 Value::Array(array) => {
-                    map_leaf_values_in_array(array, selectors, &base_key, mapper)
+                    map_leaf_values_in_array(array, selectors, &base_key, array_indices, mapper)
                 }
-                value => mapper(&base_key, value),
+                value => mapper(&base_key, array_indices, value),
             }
         }
     }
@@ -109,14 +112,21 @@ pub fn map_leaf_values_in_array(
     values: &mut [Value],
     selectors: &[&str],
     base_key: &str,
-    mapper: &mut impl FnMut(&str, &mut Value),
+    array_indices: &mut Vec<usize>,
+    mapper: &mut impl FnMut(&str, &[usize], &mut Value),
 ) {
-    for value in values.iter_mut() {
+    for (i, value) in values.iter_mut().enumerate() {
+        array_indices.push(i);
         match value {
-            Value::Object(object) => map_leaf_values_in_object(object, selectors, base_key, mapper),
-            Value::Array(array) => map_leaf_values_in_array(array, selectors, base_key, mapper),
-            value => mapper(base_key, value),
+            Value::Object(object) => {
+                map_leaf_values_in_object(object, selectors, base_key, array_indices, mapper)
+            }
+            Value::Array(array) => {
+                map_leaf_values_in_array(array, selectors, base_key, array_indices, mapper)
+            }
+            value => mapper(base_key, array_indices, value),
         }
+        array_indices.pop();
     }
 }
 
@@ -743,12 +753,14 @@ mod tests {
             }
         });
 
-        map_leaf_values(value.as_object_mut().unwrap(), ["jean.race.name"], |key, value| {
-            match (value, key) {
+        map_leaf_values(
+            value.as_object_mut().unwrap(),
+            ["jean.race.name"],
+            |key, _, value| match (value, key) {
                 (Value::String(name), "jean.race.name") => *name = S("patou"),
                 _ => unreachable!(),
-            }
-        });
+            },
+        );
 
         assert_eq!(
             value,
@@ -775,7 +787,7 @@ mod tests {
         });
 
         let mut calls = 0;
-        map_leaf_values(value.as_object_mut().unwrap(), ["jean"], |key, value| {
+        map_leaf_values(value.as_object_mut().unwrap(), ["jean"], |key, _, value| {
             calls += 1;
             match (value, key) {
                 (Value::String(name), "jean.race.name") => *name = S("patou"),
@@ -798,4 +810,36 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn map_array_indices() {
+        let mut value: Value = json!({
+            "tags": ["dog", "cat"],
+            "paragraphs": [
+                { "text": "hello" },
+                [{ "text": "world" }, { "text": "doggo" }],
+            ],
+        });
+
+        let mut calls = Vec::new();
+        map_leaf_values(
+            value.as_object_mut().unwrap(),
+            ["tags", "paragraphs"],
+            |key, indices, _| {
+                calls.push((key.to_string(), indices.to_vec()));
+            },
+        );
+        calls.sort();
+
+        assert_eq!(
+            calls,
+            vec![
+                (S("paragraphs.text"), vec![0]),
+                (S("paragraphs.text"), vec![1, 0]),
+                (S("paragraphs.text"), vec![1, 1]),
+                (S("tags"), vec![0]),
+                (S("tags"), vec![1]),
+            ]
+        );
+    }
 }